@@ -1,18 +1,28 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 
-use nanostat::Summary;
+use nanostat::{Confidence, Summary};
 
 fn summarize(c: &mut Criterion) {
     let v = vec![0.0; 1000];
     c.bench_function("summarize", move |b| b.iter(|| v.iter().collect::<Summary>()));
 }
 
+fn summarize_slice(c: &mut Criterion) {
+    let v: Vec<f64> = (0..1_000_000).map(f64::from).collect();
+    let mut g = c.benchmark_group("summarize_slice");
+    g.bench_function("collect", |b| b.iter(|| v.iter().collect::<Summary>()));
+    g.bench_function("of", |b| b.iter(|| Summary::of(&v)));
+    #[cfg(feature = "rayon")]
+    g.bench_function("par_of", |b| b.iter(|| Summary::par_of(&v)));
+    g.finish();
+}
+
 fn compare(c: &mut Criterion) {
-    let s1: Summary = vec![0.0; 10].iter().collect();
-    let s2: Summary = vec![0.1; 10].iter().collect();
+    let s1: Summary = [0.0; 10].iter().collect();
+    let s2: Summary = [0.1; 10].iter().collect();
 
-    c.bench_function("compare", move |b| b.iter(|| s1.compare(&s2, 98.0)));
+    c.bench_function("compare", move |b| b.iter(|| s1.compare(&s2, Confidence::P98)));
 }
 
-criterion_group!(benches, summarize, compare);
+criterion_group!(benches, summarize, summarize_slice, compare);
 criterion_main!(benches);
@@ -0,0 +1,262 @@
+//! Tests for differences between the means of more than two groups.
+
+use statrs::distribution::{ContinuousCDF, FisherSnedecor};
+
+use crate::{Confidence, Error, Summary};
+
+/// The result of an omnibus test for differences between the means of several groups.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Anova {
+    /// The F statistic for the test.
+    pub f: f64,
+
+    /// The numerator degrees of freedom.
+    pub df_between: f64,
+
+    /// The denominator degrees of freedom.
+    pub df_within: f64,
+
+    /// The p-value for the test: the probability of an F statistic at least this large if all the
+    /// groups had the same mean.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl Anova {
+    /// Whether or not any of the groups' means differ significantly.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Test whether any of the given groups have different means using Welch's one-way ANOVA, which
+/// does not assume the groups have equal variances. The confidence level must be in the range
+/// `(0, 100)`.
+///
+/// This is an omnibus test: a significant result indicates that at least one group differs, but
+/// not which one. Use [crate::posthoc::games_howell] to find out.
+///
+/// ```
+/// use nanostat::{anova, Confidence, Summary};
+///
+/// let a: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+/// let b: Summary = [2.0, 4.0, 6.0, 8.0, 10.0].iter().collect();
+/// let c: Summary = [5.0, 6.0, 7.0, 8.0, 9.5].iter().collect();
+/// assert!(anova(&[a, b, c], Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn anova(groups: &[Summary], confidence: Confidence) -> Result<Anova, Error> {
+    // Calculate the significance level.
+    let alpha = confidence.checked_alpha()?;
+
+    if groups.len() < 2 {
+        return Err(Error::InsufficientSamples);
+    }
+    for g in groups {
+        g.check()?;
+        if g.variance == 0.0 {
+            return Err(Error::ZeroVariance);
+        }
+    }
+
+    // Weight each group by the inverse of its squared standard error.
+    let k = groups.len() as f64;
+    let weights: Vec<f64> = groups.iter().map(|g| g.n / g.variance).collect();
+    let w: f64 = weights.iter().sum();
+
+    // Calculate the weighted grand mean.
+    let mean = groups.iter().zip(weights.iter()).map(|(g, w_i)| w_i * g.mean).sum::<f64>() / w;
+
+    // Calculate the weighted between-group variation.
+    let a = groups
+        .iter()
+        .zip(weights.iter())
+        .map(|(g, w_i)| w_i * (g.mean - mean).powf(2.0))
+        .sum::<f64>()
+        / (k - 1.0);
+
+    // Calculate Welch's correction for unequal variances.
+    let lambda = groups
+        .iter()
+        .zip(weights.iter())
+        .map(|(g, w_i)| (1.0 - w_i / w).powf(2.0) / (g.n - 1.0))
+        .sum::<f64>();
+    let b = 1.0 + 2.0 * (k - 2.0) / (k.powf(2.0) - 1.0) * lambda;
+
+    // Calculate the F statistic and its degrees of freedom.
+    let f = a / b;
+    let df_between = k - 1.0;
+    let df_within = (k.powf(2.0) - 1.0) / (3.0 * lambda);
+
+    // Calculate the p-value given the F statistic.
+    let dist_f = FisherSnedecor::new(df_between, df_within).map_err(|_| Error::NonFinite)?;
+    let p_value = 1.0 - dist_f.cdf(f);
+
+    Ok(Anova { f, df_between, df_within, p_value, alpha })
+}
+
+/// Test whether any of the given treatments have different means using a repeated-measures ANOVA,
+/// in which every treatment was measured once in each of several blocks, e.g. the same benchmark
+/// suite run on several machines under each of several configurations. The confidence level must
+/// be in the range `(0, 100)`.
+///
+/// Each row of `blocks` holds the measurements from one block, with one column per treatment. The
+/// variation between blocks is removed before testing the treatments, so machine-to-machine
+/// differences don't swamp the effect of the configurations. The degrees of freedom are corrected
+/// with the Greenhouse-Geisser estimate of sphericity, which does not assume that every pair of
+/// treatments has equally variable differences.
+///
+/// ```
+/// use nanostat::{repeated_measures_anova, Confidence};
+///
+/// // Four machines, each running three configurations.
+/// let blocks: [&[f64]; 4] =
+///     [&[10.0, 10.6, 11.1], &[14.2, 14.7, 15.5], &[8.1, 8.9, 9.2], &[12.0, 12.4, 13.1]];
+/// assert!(repeated_measures_anova(&blocks, Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn repeated_measures_anova(blocks: &[&[f64]], confidence: Confidence) -> Result<Anova, Error> {
+    // Calculate the significance level.
+    let alpha = confidence.checked_alpha()?;
+
+    if blocks.len() < 2 || blocks[0].len() < 2 {
+        return Err(Error::InsufficientSamples);
+    }
+    if blocks.iter().any(|row| row.len() != blocks[0].len()) {
+        return Err(Error::MismatchedLengths);
+    }
+    if blocks.iter().flat_map(|row| row.iter()).any(|v| !v.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    // Calculate the block, treatment, and grand means.
+    let (n, k) = (blocks.len(), blocks[0].len());
+    let block_means: Vec<f64> =
+        blocks.iter().map(|row| row.iter().sum::<f64>() / k as f64).collect();
+    let treatment_means: Vec<f64> =
+        (0..k).map(|j| blocks.iter().map(|row| row[j]).sum::<f64>() / n as f64).collect();
+    let mean = block_means.iter().sum::<f64>() / n as f64;
+
+    // Partition the variation into treatment and residual components, discarding the variation
+    // between blocks.
+    let ss_treatment = n as f64 * treatment_means.iter().map(|m| (m - mean).powf(2.0)).sum::<f64>();
+    let ss_error = blocks
+        .iter()
+        .zip(block_means.iter())
+        .flat_map(|(row, b)| {
+            row.iter().zip(treatment_means.iter()).map(move |(v, t)| (v - b - t + mean).powf(2.0))
+        })
+        .sum::<f64>();
+    if ss_error == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Calculate the F statistic using the uncorrected degrees of freedom.
+    let df_treatment = (k - 1) as f64;
+    let df_error = ((n - 1) * (k - 1)) as f64;
+    let f = (ss_treatment / df_treatment) / (ss_error / df_error);
+
+    // Estimate the Greenhouse-Geisser epsilon from the double-centered covariance matrix of the
+    // treatments.
+    let cov = |i: usize, j: usize| {
+        blocks
+            .iter()
+            .map(|row| (row[i] - treatment_means[i]) * (row[j] - treatment_means[j]))
+            .sum::<f64>()
+            / (n - 1) as f64
+    };
+    let s: Vec<Vec<f64>> = (0..k).map(|i| (0..k).map(|j| cov(i, j)).collect()).collect();
+    let row_means: Vec<f64> = s.iter().map(|row| row.iter().sum::<f64>() / k as f64).collect();
+    let s_mean = row_means.iter().sum::<f64>() / k as f64;
+    let (mut trace, mut sum_sq) = (0.0, 0.0);
+    for i in 0..k {
+        for j in 0..k {
+            let c = s[i][j] - row_means[i] - row_means[j] + s_mean;
+            if i == j {
+                trace += c;
+            }
+            sum_sq += c * c;
+        }
+    }
+    let epsilon = (trace * trace / (df_treatment * sum_sq)).clamp(1.0 / df_treatment, 1.0);
+
+    // Calculate the p-value given the F statistic and the corrected degrees of freedom.
+    let df_between = epsilon * df_treatment;
+    let df_within = epsilon * df_error;
+    let dist_f = FisherSnedecor::new(df_between, df_within).map_err(|_| Error::NonFinite)?;
+    let p_value = 1.0 - dist_f.cdf(f);
+
+    Ok(Anova { f, df_between, df_within, p_value, alpha })
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn welch_anova() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+        let b: Summary = [2.0, 4.0, 6.0, 8.0, 10.0].iter().collect();
+        let c: Summary = [5.0, 6.0, 7.0, 8.0, 9.5].iter().collect();
+        let result = anova(&[a, b, c], Confidence::P95).unwrap();
+
+        assert_relative_eq!(result.f, 7.229560486648965, epsilon = 1e-12);
+        assert_relative_eq!(result.df_between, 2.0);
+        assert_relative_eq!(result.df_within, 7.592529534463542, epsilon = 1e-12);
+        assert_relative_eq!(result.p_value, 0.01746313034331241, epsilon = 1e-9);
+        assert!(result.is_significant());
+    }
+
+    #[test]
+    fn welch_anova_similar() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let b: Summary = [1.5, 2.5, 3.5, 4.5].iter().collect();
+        let result = anova(&[a, b, a], Confidence::P95).unwrap();
+
+        assert!(!result.is_significant());
+    }
+
+    #[test]
+    fn welch_anova_errors() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let flat: Summary = [1.0, 1.0].iter().collect();
+
+        assert_eq!(anova(&[a], Confidence::P95).unwrap_err(), Error::InsufficientSamples);
+        assert_eq!(anova(&[a, flat], Confidence::P95).unwrap_err(), Error::ZeroVariance);
+    }
+
+    #[test]
+    fn repeated_measures() {
+        let blocks: [&[f64]; 4] =
+            [&[10.0, 10.6, 11.1], &[14.2, 14.7, 15.5], &[8.1, 8.9, 9.2], &[12.0, 12.4, 13.1]];
+        let result = repeated_measures_anova(&blocks, Confidence::P95).unwrap();
+
+        assert_relative_eq!(result.f, 89.83018867924493, epsilon = 1e-12);
+        assert_relative_eq!(result.df_between, 1.257948947604058, epsilon = 1e-12);
+        assert_relative_eq!(result.df_within, 3.7738468428121736, epsilon = 1e-12);
+        assert_relative_eq!(result.p_value, 0.0008097305172441598, epsilon = 1e-9);
+        assert!(result.is_significant());
+
+        // The same measurements, ignoring the blocks, are swamped by the machine-to-machine
+        // variation.
+        let groups: Vec<Summary> =
+            (0..3).map(|j| blocks.iter().map(|row| row[j]).collect()).collect();
+        assert!(!anova(&groups, Confidence::P95).unwrap().is_significant());
+    }
+
+    #[test]
+    fn repeated_measures_errors() {
+        let rm = |blocks: &[&[f64]]| repeated_measures_anova(blocks, Confidence::P95).unwrap_err();
+
+        assert_eq!(rm(&[&[1.0, 2.0]]), Error::InsufficientSamples);
+        assert_eq!(rm(&[&[1.0, 2.0], &[1.0]]), Error::MismatchedLengths);
+        assert_eq!(rm(&[&[1.0, 2.0], &[1.0, f64::NAN]]), Error::NonFinite);
+        assert_eq!(rm(&[&[1.0, 2.0], &[3.0, 4.0]]), Error::ZeroVariance);
+    }
+}
@@ -0,0 +1,217 @@
+//! Tests for categorical outcomes, which compare tables of counts rather than measurements.
+
+use statrs::distribution::{ChiSquared, ContinuousCDF};
+use statrs::function::factorial::ln_binomial;
+
+use crate::{Confidence, Error};
+
+/// The result of Pearson's chi-squared test of independence on a contingency table.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChiSquare {
+    /// The chi-squared statistic for the test.
+    pub statistic: f64,
+
+    /// The degrees of freedom of the chi-squared distribution used by the test.
+    pub df: f64,
+
+    /// The p-value for the test.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl ChiSquare {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Test whether the rows and columns of the given contingency table of counts are independent,
+/// e.g. whether the distribution of outcomes (columns) differs between groups (rows), using
+/// Pearson's chi-squared test. The table must have at least two rows and two columns, and every
+/// row must have the same length.
+///
+/// The chi-squared approximation is unreliable if any expected count is below about five; for 2×2
+/// tables, use [fisher_exact] instead.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::categorical::chi_square;
+///
+/// // Hits, misses, and evictions for three cache configurations.
+/// let table: [&[u64]; 3] = [&[12, 5, 9], &[7, 15, 3], &[4, 6, 14]];
+/// assert!(chi_square(&table, Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn chi_square(table: &[&[u64]], confidence: Confidence) -> Result<ChiSquare, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if table.len() < 2 || table[0].len() < 2 {
+        return Err(Error::InsufficientSamples);
+    }
+    if table.iter().any(|row| row.len() != table[0].len()) {
+        return Err(Error::MismatchedLengths);
+    }
+
+    // Calculate the row, column, and grand totals.
+    let rows: Vec<f64> = table.iter().map(|row| row.iter().sum::<u64>() as f64).collect();
+    let cols: Vec<f64> =
+        (0..table[0].len()).map(|j| table.iter().map(|row| row[j]).sum::<u64>() as f64).collect();
+    let n: f64 = rows.iter().sum();
+    if rows.iter().chain(cols.iter()).any(|&t| t == 0.0) {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Sum the squared differences between the observed and expected counts.
+    let statistic: f64 = table
+        .iter()
+        .zip(rows.iter())
+        .flat_map(|(row, r)| {
+            row.iter().zip(cols.iter()).map(move |(&o, c)| {
+                let e = r * c / n;
+                (o as f64 - e).powf(2.0) / e
+            })
+        })
+        .sum();
+
+    // Calculate the p-value using the chi-squared distribution.
+    let df = ((rows.len() - 1) * (cols.len() - 1)) as f64;
+    let p_value = 1.0 - ChiSquared::new(df).unwrap().cdf(statistic);
+
+    Ok(ChiSquare { statistic, df, p_value, alpha })
+}
+
+/// The result of Fisher's exact test on a 2×2 contingency table.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FisherExact {
+    /// The sample odds ratio of the table, `(a * d) / (b * c)` for the table `[[a, b], [c, d]]`. It
+    /// is infinite if `b * c` is zero, or NaN if `a * d` is zero too.
+    pub odds_ratio: f64,
+
+    /// The exact two-tailed p-value for the test.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl FisherExact {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Test whether the rows and columns of the given 2×2 contingency table of counts are independent
+/// using Fisher's exact test, which is valid for any counts, however small.
+///
+/// The two-tailed p-value is the total probability of the tables with the same margins which are
+/// no more likely than the observed one. Returns [Error::CountOverflow] if the total of the counts
+/// does not fit in a `u64`.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::categorical::fisher_exact;
+///
+/// // Failed and successful runs before and after a change.
+/// let f = fisher_exact([[1, 9], [11, 3]], Confidence::P95)?;
+/// assert!(f.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn fisher_exact(table: [[u64; 2]; 2], confidence: Confidence) -> Result<FisherExact, Error> {
+    let alpha = confidence.checked_alpha()?;
+    let [[a, b], [c, d]] = table;
+    let total = |x: u64, y: u64| x.checked_add(y).ok_or(Error::CountOverflow);
+    let (row, col, n) = (total(a, b)?, total(a, c)?, total(total(a, b)?, total(c, d)?)?);
+    if n == 0 {
+        return Err(Error::InsufficientSamples);
+    }
+
+    // Calculate the hypergeometric probability of each table with the same margins, identified by
+    // its top-left count, and sum those no more likely than the observed one, allowing for
+    // rounding error.
+    let ln_p = |x: u64| ln_binomial(row, x) + ln_binomial(n - row, col - x) - ln_binomial(n, col);
+    let observed = ln_p(a) + 1e-7;
+    let p_value: f64 = ((col.saturating_sub(n - row))..=row.min(col))
+        .map(ln_p)
+        .filter(|&l| l <= observed)
+        .map(f64::exp)
+        .sum();
+
+    let odds_ratio = (a as f64 * d as f64) / (b as f64 * c as f64);
+    Ok(FisherExact { odds_ratio, p_value: p_value.min(1.0), alpha })
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn chi_square_test() {
+        let table: [&[u64]; 3] = [&[12, 5, 9], &[7, 15, 3], &[4, 6, 14]];
+        let chi = chi_square(&table, Confidence::P95).unwrap();
+
+        assert_relative_eq!(chi.statistic, 18.270388474401855, epsilon = 1e-12);
+        assert_relative_eq!(chi.df, 4.0);
+        assert_relative_eq!(chi.p_value, 0.0010926161809956187, epsilon = 1e-9);
+        assert!(chi.is_significant());
+
+        let chi = chi_square(&[&[10, 20], &[30, 25]], Confidence::P95).unwrap();
+        assert_relative_eq!(chi.statistic, 3.505892255892255, epsilon = 1e-12);
+        assert_relative_eq!(chi.p_value, 0.06115089757606771, epsilon = 1e-9);
+        assert!(!chi.is_significant());
+    }
+
+    #[test]
+    fn chi_square_errors() {
+        assert_eq!(
+            chi_square(&[&[1, 2]], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+        assert_eq!(
+            chi_square(&[&[1, 2], &[3]], Confidence::P95).unwrap_err(),
+            Error::MismatchedLengths
+        );
+        assert_eq!(
+            chi_square(&[&[1, 0], &[3, 0]], Confidence::P95).unwrap_err(),
+            Error::ZeroVariance
+        );
+    }
+
+    #[test]
+    fn fisher_exact_test() {
+        let f = fisher_exact([[10, 20], [30, 25]], Confidence::P95).unwrap();
+        assert_relative_eq!(f.odds_ratio, 250.0 / 600.0);
+        assert_relative_eq!(f.p_value, 0.0722865209419854, epsilon = 1e-9);
+        assert!(!f.is_significant());
+
+        // A table with no association at all.
+        let f = fisher_exact([[5, 5], [5, 5]], Confidence::P95).unwrap();
+        assert_relative_eq!(f.odds_ratio, 1.0);
+        assert_relative_eq!(f.p_value, 1.0);
+
+        // The odds ratio is undefined when b * c is zero.
+        assert!(fisher_exact([[1, 2], [0, 3]], Confidence::P95).unwrap().odds_ratio.is_infinite());
+        assert!(fisher_exact([[0, 2], [0, 3]], Confidence::P95).unwrap().odds_ratio.is_nan());
+
+        assert_eq!(
+            fisher_exact([[0, 0], [0, 0]], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+        assert_eq!(
+            fisher_exact([[u64::MAX, 0], [1, 0]], Confidence::P95).unwrap_err(),
+            Error::CountOverflow
+        );
+        assert_eq!(
+            fisher_exact([[1, 0], [u64::MAX, 0]], Confidence::P95).unwrap_err(),
+            Error::CountOverflow
+        );
+    }
+}
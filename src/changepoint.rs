@@ -0,0 +1,155 @@
+//! Detection of changes in the mean of an ordered series of measurements, such as the history of
+//! nightly benchmark results.
+
+use crate::Error;
+
+/// The penalty for each changepoint, which trades off sensitivity against false positives.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Penalty {
+    /// The Bayesian information criterion, `2 ln(n)`.
+    #[default]
+    Bic,
+    /// A custom penalty, in units of the series' variance. Larger penalties detect fewer changes.
+    Custom(f64),
+}
+
+/// Find the points at which the mean of the given series of measurements changes, using the
+/// pruned exact linear time (PELT) algorithm of Killick et al. Returns the indexes of the first
+/// measurement of each segment after the first, in ascending order.
+///
+/// The segmentation minimizes the total squared deviation of the measurements from their
+/// segments' means, scaled by the variance of the series' noise, plus the penalty for each
+/// changepoint. The noise is estimated from the differences between successive measurements, so
+/// it isn't inflated by the changes themselves. Each segment has at least two measurements.
+///
+/// ```
+/// use nanostat::changepoint::{pelt, Penalty};
+///
+/// let nightly = [10.0, 11.0, 10.5, 10.2, 10.8, 15.1, 14.8, 15.3, 15.0, 14.9];
+/// assert_eq!(pelt(&nightly, Penalty::Bic)?, vec![5]);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn pelt(values: &[f64], penalty: Penalty) -> Result<Vec<usize>, Error> {
+    const MIN_LEN: usize = 2;
+
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+    let n = values.len();
+    if n < 2 * MIN_LEN {
+        return Ok(vec![]);
+    }
+    let beta = match penalty {
+        Penalty::Bic => 2.0 * (n as f64).ln(),
+        Penalty::Custom(beta) => beta,
+    };
+    let variance = noise_variance(values);
+    if variance == 0.0 {
+        return Ok(vec![]);
+    }
+
+    // Calculate the cumulative sums of the centered measurements and their squares, so the cost
+    // of any segment can be calculated in constant time.
+    let mean = values.iter().sum::<f64>() / n as f64;
+    let (mut s1, mut s2) = (vec![0.0; n + 1], vec![0.0; n + 1]);
+    for (i, v) in values.iter().enumerate() {
+        s1[i + 1] = s1[i] + (v - mean);
+        s2[i + 1] = s2[i] + (v - mean).powf(2.0);
+    }
+    let cost = |s: usize, t: usize| {
+        let sum = s1[t] - s1[s];
+        ((s2[t] - s2[s]) - sum * sum / (t - s) as f64) / variance
+    };
+
+    // Find the optimal segmentation of each prefix of the series, discarding candidate
+    // changepoints which can never be optimal.
+    let mut best = vec![f64::INFINITY; n + 1];
+    let mut last = vec![0; n + 1];
+    best[0] = -beta;
+    let mut candidates = vec![0];
+    for t in MIN_LEN..=n {
+        let (s, f) = candidates
+            .iter()
+            .filter(|&&s| t - s >= MIN_LEN)
+            .map(|&s| (s, best[s] + cost(s, t) + beta))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+        best[t] = f;
+        last[t] = s;
+        candidates.retain(|&s| t - s < MIN_LEN || best[s] + cost(s, t) <= f);
+        candidates.push(t + 1 - MIN_LEN);
+    }
+
+    // Trace the changepoints back from the end of the series.
+    let mut changes = vec![];
+    let mut t = last[n];
+    while t > 0 {
+        changes.push(t);
+        t = last[t];
+    }
+    changes.reverse();
+    Ok(changes)
+}
+
+/// Estimate the variance of the noise in the series from the median absolute difference between
+/// successive measurements, which is robust to changes in the mean, falling back to the mean
+/// squared difference if most of the differences are zero.
+fn noise_variance(values: &[f64]) -> f64 {
+    let mut diffs: Vec<f64> = values.windows(2).map(|w| (w[1] - w[0]).abs()).collect();
+    diffs.sort_by(f64::total_cmp);
+    let median = diffs[diffs.len() / 2];
+
+    // The absolute difference of two normal variables has a median of 0.6745√2σ.
+    if median > 0.0 {
+        (median / (0.674_489_750_196_081_7 * 2f64.sqrt())).powf(2.0)
+    } else {
+        diffs.iter().map(|d| d * d).sum::<f64>() / diffs.len() as f64 / 2.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn single_change() {
+        let v = [10.0, 11.0, 10.5, 10.2, 10.8, 15.1, 14.8, 15.3, 15.0, 14.9];
+        assert_eq!(pelt(&v, Penalty::Bic).unwrap(), vec![5]);
+
+        // A large enough penalty suppresses the change.
+        assert_eq!(pelt(&v, Penalty::Custom(1e6)).unwrap(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn multiple_changes() {
+        let v: Vec<f64> = (0..60)
+            .map(|i| {
+                let noise = [0.3, -0.2, 0.1, -0.4, 0.2][i % 5];
+                let level = match i {
+                    0..=19 => 10.0,
+                    20..=39 => 13.0,
+                    _ => 11.0,
+                };
+                level + noise
+            })
+            .collect();
+        assert_eq!(pelt(&v, Penalty::Bic).unwrap(), vec![20, 40]);
+    }
+
+    #[test]
+    fn no_change() {
+        let v: Vec<f64> = (0..40).map(|i| 10.0 + [0.3, -0.2, 0.1, -0.4, 0.2][i % 5]).collect();
+        assert_eq!(pelt(&v, Penalty::Bic).unwrap(), Vec::<usize>::new());
+        assert_eq!(pelt(&[1.0; 10], Penalty::Bic).unwrap(), Vec::<usize>::new());
+        assert_eq!(pelt(&[1.0, 5.0], Penalty::Bic).unwrap(), Vec::<usize>::new());
+        assert_eq!(pelt(&[1.0, f64::NAN], Penalty::Bic).unwrap_err(), Error::NonFinite);
+    }
+
+    #[test]
+    fn step_function() {
+        // Noiseless segments fall back to the mean squared difference for the variance.
+        let v = [1.0, 1.0, 1.0, 1.0, 1.0, 5.0, 5.0, 5.0, 5.0, 5.0];
+        assert_eq!(pelt(&v, Penalty::Bic).unwrap(), vec![5]);
+    }
+}
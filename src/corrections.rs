@@ -0,0 +1,173 @@
+//! Corrections for multiple comparisons, which keep the error rate of a batch of tests (e.g. one
+//! control compared with many experiments) under control.
+
+use crate::Difference;
+
+/// A correction for multiple comparisons.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Correction {
+    /// The Bonferroni correction; see [bonferroni].
+    Bonferroni,
+    /// Holm's step-down method; see [holm].
+    Holm,
+    /// The Benjamini-Hochberg procedure; see [benjamini_hochberg].
+    BenjaminiHochberg,
+}
+
+impl Correction {
+    /// Adjust the p-values of the given differences using this correction.
+    #[must_use]
+    pub fn adjust(&self, diffs: &[Difference]) -> Vec<Difference> {
+        match self {
+            Correction::Bonferroni => bonferroni(diffs),
+            Correction::Holm => holm(diffs),
+            Correction::BenjaminiHochberg => benjamini_hochberg(diffs),
+        }
+    }
+}
+
+/// Adjust the p-values of the given differences using the Bonferroni correction, which controls
+/// the family-wise error rate: the probability of any of the differences being a false positive.
+///
+/// Each p-value is multiplied by the number of differences, so [Difference::is_significant]
+/// reports whether each difference survives the correction. The confidence intervals are not
+/// adjusted.
+///
+/// ```
+/// use nanostat::corrections::bonferroni;
+/// use nanostat::{Confidence, Summary};
+///
+/// let control: Summary = [10.0, 11.0, 12.0, 11.0, 10.0].iter().collect();
+/// let a: Summary = [11.0, 12.0, 13.0, 12.0, 11.0].iter().collect();
+/// let b: Summary = [10.0, 11.0, 12.0, 11.5, 10.5].iter().collect();
+/// let diffs = [control.compare(&a, Confidence::P90), control.compare(&b, Confidence::P90)];
+/// assert!(diffs[0].is_significant());
+/// assert!(!bonferroni(&diffs)[0].is_significant());
+/// ```
+#[must_use]
+pub fn bonferroni(diffs: &[Difference]) -> Vec<Difference> {
+    let m = diffs.len() as f64;
+    diffs.iter().map(|d| Difference { p_value: (d.p_value * m).min(1.0), ..*d }).collect()
+}
+
+/// Adjust the p-values of the given differences using Holm's step-down method, which controls the
+/// family-wise error rate like [bonferroni] but is uniformly more powerful.
+///
+/// The smallest p-value is multiplied by the number of differences, the next smallest by one
+/// fewer, and so on, so [Difference::is_significant] reports whether each difference survives the
+/// correction. The confidence intervals are not adjusted.
+#[must_use]
+pub fn holm(diffs: &[Difference]) -> Vec<Difference> {
+    let m = diffs.len();
+
+    // Scale each p-value by the number of tests of equal or higher rank, keeping the adjusted
+    // p-values monotonic by taking the maximum of those of lower rank.
+    let mut adjusted = diffs.to_vec();
+    let mut p = 0.0f64;
+    for (rank, i) in ranked(diffs).into_iter().enumerate() {
+        p = p.max((diffs[i].p_value * (m - rank) as f64).min(1.0));
+        adjusted[i].p_value = p;
+    }
+    adjusted
+}
+
+/// Adjust the p-values of the given differences using the Benjamini-Hochberg procedure, which
+/// controls the false discovery rate: the expected proportion of significant differences which are
+/// false positives. This is much more powerful than [bonferroni] for large batches of tests.
+///
+/// Each p-value is replaced with its q-value, so [Difference::is_significant] reports whether each
+/// difference survives at a false discovery rate of the difference's significance level. The
+/// confidence intervals are not adjusted.
+#[must_use]
+pub fn benjamini_hochberg(diffs: &[Difference]) -> Vec<Difference> {
+    let m = diffs.len() as f64;
+
+    // Scale each p-value by the number of tests over its rank, keeping the q-values monotonic by
+    // taking the minimum of those of higher rank.
+    let mut adjusted = diffs.to_vec();
+    let mut q = 1.0f64;
+    for (rank, i) in ranked(diffs).into_iter().enumerate().rev() {
+        q = q.min(diffs[i].p_value * m / (rank + 1) as f64);
+        adjusted[i].p_value = q;
+    }
+    adjusted
+}
+
+/// The indexes of the given differences, in ascending order of p-value.
+fn ranked(diffs: &[Difference]) -> Vec<usize> {
+    let mut ranked: Vec<usize> = (0..diffs.len()).collect();
+    ranked.sort_by(|&i, &j| diffs[i].p_value.total_cmp(&diffs[j].p_value));
+    ranked
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{Confidence, Summary};
+
+    use super::*;
+
+    fn diffs() -> Vec<Difference> {
+        let control: Summary = [10.0, 11.0, 12.0, 11.0, 10.0].iter().collect();
+        [
+            [11.0, 12.0, 13.0, 12.0, 11.0],
+            [10.0, 11.0, 12.0, 11.5, 10.5],
+            [14.0, 15.0, 16.0, 15.0, 14.0],
+        ]
+        .iter()
+        .map(|exp| control.compare(&exp.iter().collect(), Confidence::P95))
+        .collect()
+    }
+
+    fn with_p_values(p_values: &[f64]) -> Vec<Difference> {
+        let d = diffs()[0];
+        p_values.iter().map(|&p_value| Difference { p_value, ..d }).collect()
+    }
+
+    #[test]
+    fn bonferroni_correction() {
+        let diffs = diffs();
+        let adjusted = bonferroni(&diffs);
+
+        for (d, a) in diffs.iter().zip(adjusted.iter()) {
+            assert_relative_eq!(a.p_value, (d.p_value * 3.0).min(1.0));
+            assert_relative_eq!(a.alpha, d.alpha);
+        }
+        assert_relative_eq!(adjusted[1].p_value, 1.0);
+        assert!(adjusted[2].is_significant());
+        assert!(bonferroni(&[]).is_empty());
+    }
+
+    #[test]
+    fn holm_correction() {
+        let diffs = with_p_values(&[0.01, 0.04, 0.03, 0.005, 0.9]);
+        let p: Vec<f64> = holm(&diffs).iter().map(|d| d.p_value).collect();
+
+        assert_relative_eq!(p[0], 0.04);
+        assert_relative_eq!(p[1], 0.09);
+        assert_relative_eq!(p[2], 0.09);
+        assert_relative_eq!(p[3], 0.025);
+        assert_relative_eq!(p[4], 0.9);
+
+        // Holm's method is never more conservative than Bonferroni's.
+        let b = Correction::Bonferroni.adjust(&diffs);
+        for (h, b) in Correction::Holm.adjust(&diffs).iter().zip(b.iter()) {
+            assert!(h.p_value <= b.p_value);
+        }
+    }
+
+    #[test]
+    fn benjamini_hochberg_correction() {
+        let diffs = with_p_values(&[0.01, 0.04, 0.03, 0.005, 0.9]);
+        let q: Vec<f64> = benjamini_hochberg(&diffs).iter().map(|d| d.p_value).collect();
+
+        assert_relative_eq!(q[0], 0.025);
+        assert_relative_eq!(q[1], 0.05);
+        assert_relative_eq!(q[2], 0.05);
+        assert_relative_eq!(q[3], 0.025);
+        assert_relative_eq!(q[4], 0.9);
+        assert!(benjamini_hochberg(&[]).is_empty());
+    }
+}
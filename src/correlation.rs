@@ -0,0 +1,243 @@
+//! Correlations between paired measurements, such as between the duration of a run and the size
+//! of its input, and between successive measurements of a series.
+
+use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
+
+use crate::nonparametric::rank;
+use crate::{Confidence, Error};
+
+/// The correlation between two paired data sets.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Correlation {
+    /// The correlation coefficient, in the range `[-1, 1]`.
+    pub r: f64,
+
+    /// The lower bound of the confidence interval for the correlation coefficient, calculated
+    /// using the Fisher transformation.
+    pub ci_low: f64,
+
+    /// The upper bound of the confidence interval for the correlation coefficient, calculated
+    /// using the Fisher transformation.
+    pub ci_high: f64,
+
+    /// The two-tailed p-value for the null hypothesis that the data sets are uncorrelated.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl Correlation {
+    /// Whether or not the correlation is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Calculate Pearson's correlation coefficient between the paired data sets, which measures the
+/// strength of a linear relationship between them. Both data sets must have the same length, of
+/// at least four measurements.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::correlation::pearson;
+///
+/// let size = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+/// let duration = [1.2, 0.8, 2.5, 1.9, 3.8, 2.7, 4.1, 3.0];
+/// assert!(pearson(&size, &duration, Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn pearson(x: &[f64], y: &[f64], confidence: Confidence) -> Result<Correlation, Error> {
+    let alpha = confidence.checked_alpha()?;
+    check(x, y)?;
+    let r = coefficient(x, y)?;
+    let n = x.len() as f64;
+    Ok(infer(r, n, 1.0 / (n - 3.0).sqrt(), alpha))
+}
+
+/// Calculate Spearman's rank correlation coefficient between the paired data sets, which measures
+/// the strength of a monotonic relationship between them and is robust to outliers. Both data
+/// sets must have the same length, of at least four measurements.
+///
+/// The p-value uses a t-distribution approximation, and the confidence interval uses the Bonett
+/// and Wright standard error for the Fisher transformation of the coefficient.
+pub fn spearman(x: &[f64], y: &[f64], confidence: Confidence) -> Result<Correlation, Error> {
+    let alpha = confidence.checked_alpha()?;
+    check(x, y)?;
+    let (rx, _) = rank(x)?;
+    let (ry, _) = rank(y)?;
+    let r = coefficient(&rx, &ry)?;
+    let n = x.len() as f64;
+    Ok(infer(r, n, ((1.0 + r * r / 2.0) / (n - 3.0)).sqrt(), alpha))
+}
+
+/// Estimate the autocorrelation of the given series of measurements at the given lag: the
+/// correlation between each measurement and the one `lag` places after it. Benchmark iterations
+/// are often positively autocorrelated, e.g. due to warm caches or frequency scaling, which
+/// violates the assumption that measurements are independent.
+///
+/// Returns NaN if the lag is not less than the number of measurements or if the measurements have
+/// zero variance.
+///
+/// ```
+/// use nanostat::correlation::autocorrelation;
+///
+/// let drifting = [1.0, 2.0, 3.0, 2.0, 1.0, 2.0, 3.0, 4.0, 5.0, 4.0, 3.0, 4.0, 5.0, 6.0];
+/// assert!(autocorrelation(&drifting, 1) > 0.5);
+/// ```
+#[must_use]
+pub fn autocorrelation(values: &[f64], lag: usize) -> f64 {
+    if lag >= values.len() {
+        return f64::NAN;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let c0: f64 = values.iter().map(|v| (v - mean).powf(2.0)).sum();
+    let ck: f64 =
+        values.iter().zip(values[lag..].iter()).map(|(a, b)| (a - mean) * (b - mean)).sum();
+    ck / c0
+}
+
+/// Estimate the effective sample size of the given series of measurements: the number of
+/// independent measurements which would estimate the mean as precisely. It is never greater than
+/// the number of measurements.
+///
+/// This uses Geyer's initial positive sequence estimator, which sums the autocorrelations in
+/// adjacent pairs until a pair's sum is no longer positive. See [crate::Summary::autocorrelated]
+/// to use it in comparisons.
+#[must_use]
+pub fn effective_sample_size(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let rho = |k: usize| if k == 0 { 1.0 } else { autocorrelation(values, k) };
+
+    // Calculate the integrated autocorrelation time, which is how many measurements it takes to
+    // produce one independent one.
+    let gammas: f64 = (0..)
+        .take_while(|m| 2 * m + 1 < values.len())
+        .map(|m| rho(2 * m) + rho(2 * m + 1))
+        .take_while(|&gamma| gamma > 0.0)
+        .sum();
+    let tau = 2.0 * gammas - 1.0;
+    if tau > 1.0 {
+        n / tau
+    } else {
+        n
+    }
+}
+
+/// Check that the paired data sets can be correlated.
+fn check(x: &[f64], y: &[f64]) -> Result<(), Error> {
+    if x.len() != y.len() {
+        return Err(Error::MismatchedLengths);
+    }
+    if x.len() < 4 {
+        return Err(Error::InsufficientSamples);
+    }
+    if x.iter().chain(y.iter()).any(|v| !v.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+    Ok(())
+}
+
+/// Calculate Pearson's correlation coefficient between the paired values.
+fn coefficient(x: &[f64], y: &[f64]) -> Result<f64, Error> {
+    let n = x.len() as f64;
+    let (mean_x, mean_y) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+    let (mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(y.iter()) {
+        let (dx, dy) = (a - mean_x, b - mean_y);
+        sxx += dx * dx;
+        syy += dy * dy;
+        sxy += dx * dy;
+    }
+    if sxx == 0.0 || syy == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+    Ok((sxy / (sxx * syy).sqrt()).clamp(-1.0, 1.0))
+}
+
+/// Calculate the p-value and confidence interval for the given correlation coefficient, given the
+/// standard error of its Fisher transformation.
+fn infer(r: f64, n: f64, std_err: f64, alpha: f64) -> Correlation {
+    // Calculate the p-value using the t-statistic of the coefficient.
+    let dist_st = StudentsT::new(0.0, 1.0, n - 2.0).unwrap();
+    let t = r * ((n - 2.0) / (1.0 - r * r)).sqrt();
+    let p_value = dist_st.cdf(-t.abs()) * 2.0;
+
+    // Calculate the confidence interval on the Fisher-transformed scale and transform it back.
+    let z = Normal::new(0.0, 1.0).unwrap().inverse_cdf(1.0 - alpha / 2.0);
+    let ci_low = (r.atanh() - z * std_err).tanh();
+    let ci_high = (r.atanh() + z * std_err).tanh();
+
+    Correlation { r, ci_low, ci_high, p_value, alpha }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    const X: [f64; 8] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+    const Y: [f64; 8] = [1.2, 0.8, 2.5, 1.9, 3.8, 2.7, 4.1, 3.0];
+
+    #[test]
+    fn pearson_correlation() {
+        let c = pearson(&X, &Y, Confidence::P95).unwrap();
+
+        assert_relative_eq!(c.r, 0.7918232927769473, epsilon = 1e-12);
+        assert_relative_eq!(c.p_value, 0.019179769073226406, epsilon = 1e-9);
+        assert_relative_eq!(c.ci_low, 0.19716225997135828, epsilon = 1e-9);
+        assert_relative_eq!(c.ci_high, 0.9605384165050377, epsilon = 1e-9);
+        assert!(c.is_significant());
+
+        // A perfect negative correlation.
+        let neg: Vec<f64> = X.iter().map(|x| 10.0 - 2.0 * x).collect();
+        let c = pearson(&X, &neg, Confidence::P95).unwrap();
+        assert_relative_eq!(c.r, -1.0);
+        assert_relative_eq!(c.p_value, 0.0);
+    }
+
+    #[test]
+    fn spearman_correlation() {
+        let c = spearman(&X, &Y, Confidence::P95).unwrap();
+
+        assert_relative_eq!(c.r, 0.8333333333333334, epsilon = 1e-12);
+        assert_relative_eq!(c.p_value, 0.01017554012345679, epsilon = 1e-9);
+        assert_relative_eq!(c.ci_low, 0.17959947205361488, epsilon = 1e-9);
+        assert_relative_eq!(c.ci_high, 0.9765132460961133, epsilon = 1e-9);
+
+        // A monotonic but non-linear relationship is perfectly rank-correlated.
+        let exp: Vec<f64> = X.iter().map(|x| x.exp()).collect();
+        assert_relative_eq!(spearman(&X, &exp, Confidence::P95).unwrap().r, 1.0);
+        assert!(pearson(&X, &exp, Confidence::P95).unwrap().r < 1.0);
+    }
+
+    #[test]
+    fn serial_correlation() {
+        let series =
+            [1.0, 2.0, 3.0, 2.0, 1.0, 2.0, 3.0, 4.0, 5.0, 4.0, 3.0, 4.0, 5.0, 6.0, 5.0, 4.0];
+
+        assert_relative_eq!(autocorrelation(&series, 1), 0.6884259259259259, epsilon = 1e-12);
+        assert_relative_eq!(autocorrelation(&series, 2), 0.3101851851851852, epsilon = 1e-12);
+        assert!(autocorrelation(&series, 16).is_nan());
+        assert_relative_eq!(effective_sample_size(&series), 3.86317907444668, epsilon = 1e-12);
+
+        // Alternating measurements are negatively correlated, but the effective sample size is not
+        // inflated.
+        let alternating: Vec<f64> = (0..10).map(|i| f64::from(i % 2)).collect();
+        assert!(autocorrelation(&alternating, 1) < 0.0);
+        assert_relative_eq!(effective_sample_size(&alternating), 10.0);
+    }
+
+    #[test]
+    fn correlation_errors() {
+        assert_eq!(pearson(&X, &Y[..4], Confidence::P95).unwrap_err(), Error::MismatchedLengths);
+        assert_eq!(
+            pearson(&X[..3], &Y[..3], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+        assert_eq!(pearson(&X, &[1.0; 8], Confidence::P95).unwrap_err(), Error::ZeroVariance);
+    }
+}
@@ -0,0 +1,201 @@
+//! Data sets which retain their raw measurements.
+
+use crate::nonparametric::{
+    brunner_munzel, cliffs_delta, kolmogorov_smirnov, mann_whitney, BrunnerMunzel, CliffsDelta,
+    KolmogorovSmirnov, MannWhitney,
+};
+use crate::normality::{shapiro_wilk, ShapiroWilk};
+use crate::outliers::tukey;
+use crate::quantiles::Quantiles;
+use crate::resampling::{compare_bootstrap, permutation, Bootstrap, Permutation, Resampling};
+use crate::{Confidence, Difference, Error, Summary};
+
+/// A data set which retains all of its measurements, unlike a [Summary]. It is the entry point
+/// for the order statistics, outlier filtering, and the non-parametric and resampling-based tests
+/// which require raw measurements.
+///
+/// ```
+/// use nanostat::{Confidence, Dataset};
+///
+/// let control = Dataset::new(vec![10.1, 10.3, 9.8, 10.0, 10.2, 9.9, 10.4, 15.7, 10.1, 9.7])?;
+/// let experiment = Dataset::new(vec![11.2, 11.0, 11.4, 10.9, 11.3, 11.1, 11.5, 10.8])?;
+/// let control = control.without_outliers(1.5)?;
+/// assert_eq!(control.len(), 9);
+/// assert!(control.try_compare(&experiment, Confidence::P95)?.is_significant());
+/// assert!(control.mann_whitney(&experiment, Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dataset {
+    values: Vec<f64>,
+}
+
+impl Dataset {
+    /// Create a data set of the given measurements, returning [Error::NonFinite] if any of them
+    /// are NaN or infinite.
+    pub fn new(values: Vec<f64>) -> Result<Dataset, Error> {
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err(Error::NonFinite);
+        }
+        Ok(Dataset { values })
+    }
+
+    /// The number of measurements in the set.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether or not the set has no measurements.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The measurements in the set, in their original order.
+    #[must_use]
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Consume the set, returning its measurements.
+    #[must_use]
+    pub fn into_values(self) -> Vec<f64> {
+        self.values
+    }
+
+    /// A summary of the measurements.
+    #[must_use]
+    pub fn summary(&self) -> Summary {
+        Summary::of(&self.values)
+    }
+
+    /// The exact quantiles of the measurements.
+    #[must_use]
+    pub fn quantiles(&self) -> Quantiles {
+        self.values.iter().collect()
+    }
+
+    /// A copy of the set without the measurements outside of Tukey's fences; see
+    /// [crate::outliers::tukey]. Use the [crate::outliers] module directly for other methods.
+    pub fn without_outliers(&self, k: f64) -> Result<Dataset, Error> {
+        Ok(Dataset { values: tukey(&self.values, k)?.kept })
+    }
+
+    /// Test whether the measurements are normally distributed; see
+    /// [crate::normality::shapiro_wilk].
+    pub fn shapiro_wilk(&self, confidence: Confidence) -> Result<ShapiroWilk, Error> {
+        shapiro_wilk(&self.values, confidence)
+    }
+
+    /// Compare the means of the two sets using Welch's t-test; see [Summary::try_compare].
+    pub fn try_compare(
+        &self,
+        other: &Dataset,
+        confidence: Confidence,
+    ) -> Result<Difference, Error> {
+        self.summary().try_compare(&other.summary(), confidence)
+    }
+
+    /// Compare the two sets using the Mann-Whitney U test; see
+    /// [crate::nonparametric::mann_whitney].
+    pub fn mann_whitney(
+        &self,
+        other: &Dataset,
+        confidence: Confidence,
+    ) -> Result<MannWhitney, Error> {
+        mann_whitney(&self.values, &other.values, confidence)
+    }
+
+    /// Compare the two sets using the Brunner-Munzel test; see
+    /// [crate::nonparametric::brunner_munzel].
+    pub fn brunner_munzel(
+        &self,
+        other: &Dataset,
+        confidence: Confidence,
+    ) -> Result<BrunnerMunzel, Error> {
+        brunner_munzel(&self.values, &other.values, confidence)
+    }
+
+    /// Compare the distributions of the two sets using the Kolmogorov-Smirnov test; see
+    /// [crate::nonparametric::kolmogorov_smirnov].
+    pub fn kolmogorov_smirnov(
+        &self,
+        other: &Dataset,
+        confidence: Confidence,
+    ) -> Result<KolmogorovSmirnov, Error> {
+        kolmogorov_smirnov(&self.values, &other.values, confidence)
+    }
+
+    /// Calculate Cliff's delta between the two sets; see [crate::nonparametric::cliffs_delta].
+    pub fn cliffs_delta(&self, other: &Dataset) -> Result<CliffsDelta, Error> {
+        cliffs_delta(&self.values, &other.values)
+    }
+
+    /// Compare the means of the two sets using a permutation test; see
+    /// [crate::resampling::permutation].
+    pub fn permutation(
+        &self,
+        other: &Dataset,
+        confidence: Confidence,
+        resampling: Resampling,
+    ) -> Result<Permutation, Error> {
+        permutation(&self.values, &other.values, confidence, resampling)
+    }
+
+    /// Estimate confidence intervals for the difference between the means of the two sets by
+    /// bootstrapping; see [crate::resampling::compare_bootstrap].
+    pub fn bootstrap(
+        &self,
+        other: &Dataset,
+        confidence: Confidence,
+        resampling: Resampling,
+    ) -> Result<Bootstrap, Error> {
+        compare_bootstrap(&self.values, &other.values, confidence, resampling)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn raw_dataset() {
+        let d =
+            Dataset::new(vec![12.0, 15.0, 11.0, 14.0, 90.0, 13.0, 12.0, 16.0, 13.0, 14.0]).unwrap();
+
+        assert_eq!(d.len(), 10);
+        assert_relative_eq!(d.summary().mean, 21.0);
+        assert_relative_eq!(d.quantiles().median(), 13.5);
+        assert_eq!(d.without_outliers(1.5).unwrap().len(), 9);
+        assert_eq!(d.clone().into_values()[0], 12.0);
+        assert!(!d.shapiro_wilk(Confidence::P95).unwrap().is_normal());
+    }
+
+    #[test]
+    fn compare_datasets() {
+        let a = Dataset::new((1..=10).map(f64::from).collect()).unwrap();
+        let b = Dataset::new((6..=15).map(f64::from).collect()).unwrap();
+
+        let diff = a.try_compare(&b, Confidence::P95).unwrap();
+        assert_relative_eq!(diff.delta, 5.0);
+        assert!(diff.is_significant());
+        assert!(a.mann_whitney(&b, Confidence::P95).unwrap().is_significant());
+        assert!(a.brunner_munzel(&b, Confidence::P95).unwrap().is_significant());
+        assert_relative_eq!(a.kolmogorov_smirnov(&b, Confidence::P95).unwrap().d, 0.5);
+        assert_relative_eq!(a.cliffs_delta(&b).unwrap().delta, -0.75);
+
+        let resampling = Resampling::new(1_000, 0xDEADBEEF);
+        assert!(a.permutation(&b, Confidence::P95, resampling).unwrap().is_significant());
+        assert!(a.bootstrap(&b, Confidence::P95, resampling).unwrap().is_significant());
+    }
+
+    #[test]
+    fn dataset_errors() {
+        assert_eq!(Dataset::new(vec![1.0, f64::NAN]).unwrap_err(), Error::NonFinite);
+        assert!(Dataset::default().is_empty());
+    }
+}
@@ -0,0 +1,276 @@
+//! Measures of how different the distributions of two data sets are, which complement the verdict
+//! of a test with a description of the difference in shape.
+
+use std::f64::consts::PI;
+
+use crate::histogram::{Binning, Histogram};
+use crate::quantiles::quantile;
+use crate::{Error, Summary};
+
+/// Calculate the Kullback-Leibler divergence, in nats, of the distribution of the measurements `q`
+/// from that of the measurements `p`: the information lost when `q` is used to approximate `p`.
+/// It is zero for identical distributions, but it is not symmetric.
+///
+/// Both data sets are binned using the same edges, chosen by the given binning strategy from the
+/// pooled measurements. Each bin's count is incremented by one half (the Jeffreys prior) so that
+/// bins which are empty in `q` don't make the divergence infinite.
+///
+/// ```
+/// use nanostat::distance::kl_divergence;
+/// use nanostat::histogram::Binning;
+///
+/// let a = [1.0, 2.0, 3.0, 4.0];
+/// assert_eq!(kl_divergence(&a, &a, Binning::Fixed(5))?, 0.0);
+/// assert!(kl_divergence(&a, &[3.0, 4.0, 5.0, 6.0], Binning::Fixed(5))? > 0.0);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn kl_divergence(p: &[f64], q: &[f64], binning: Binning) -> Result<f64, Error> {
+    let (p, q) = distributions(p, q, binning, 0.5)?;
+    Ok(p.iter().zip(q.iter()).map(|(a, b)| a * (a / b).ln()).sum())
+}
+
+/// Calculate the Jensen-Shannon distance between the distributions of the two data sets: the square
+/// root of the mean of their Kullback-Leibler divergences, in bits, from their mixture. Unlike
+/// [kl_divergence], it is symmetric, is always finite, and ranges from zero for identical
+/// distributions to one for distributions with no overlap.
+///
+/// Both data sets are binned using the same edges, chosen by the given binning strategy from the
+/// pooled measurements.
+///
+/// ```
+/// use nanostat::distance::jensen_shannon;
+/// use nanostat::histogram::Binning;
+///
+/// let a = [1.0, 2.0, 3.0, 4.0];
+/// assert_eq!(jensen_shannon(&a, &[11.0, 12.0, 13.0, 14.0], Binning::Fixed(2))?, 1.0);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn jensen_shannon(p: &[f64], q: &[f64], binning: Binning) -> Result<f64, Error> {
+    let (p, q) = distributions(p, q, binning, 0.0)?;
+    let divergence: f64 = p
+        .iter()
+        .zip(q.iter())
+        .map(|(&a, &b)| {
+            let m = (a + b) / 2.0;
+            let kl = |x: f64| if x > 0.0 { x * (x / m).log2() } else { 0.0 };
+            (kl(a) + kl(b)) / 2.0
+        })
+        .sum();
+
+    // Clamp away any rounding error for identical distributions.
+    Ok(divergence.max(0.0).sqrt())
+}
+
+/// Calculate the Wasserstein-1 (earth mover's) distance between the two data sets: the area
+/// between their empirical cumulative distribution functions. It is in the same units as the
+/// measurements and can be read as the average distance each measurement had to move to turn one
+/// data set into the other; for data sets of the same size it is the mean absolute difference
+/// between their sorted measurements.
+///
+/// ```
+/// use nanostat::distance::wasserstein;
+///
+/// let d = wasserstein(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0])?;
+/// assert!((d - 3.0).abs() < 1e-12);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn wasserstein(a: &[f64], b: &[f64]) -> Result<f64, Error> {
+    if a.is_empty() || b.is_empty() {
+        return Err(Error::InsufficientSamples);
+    }
+    if a.iter().chain(b.iter()).any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    let (mut a, mut b) = (a.to_vec(), b.to_vec());
+    a.sort_by(f64::total_cmp);
+    b.sort_by(f64::total_cmp);
+    let mut pooled: Vec<f64> = a.iter().chain(b.iter()).copied().collect();
+    pooled.sort_by(f64::total_cmp);
+
+    // Integrate the absolute difference between the step functions between each pair of
+    // successive measurements.
+    let (na, nb) = (a.len() as f64, b.len() as f64);
+    let (mut i, mut j) = (0, 0);
+    let mut distance = 0.0;
+    for w in pooled.windows(2) {
+        while i < a.len() && a[i] <= w[0] {
+            i += 1;
+        }
+        while j < b.len() && b[j] <= w[0] {
+            j += 1;
+        }
+        distance += (i as f64 / na - j as f64 / nb).abs() * (w[1] - w[0]);
+    }
+    Ok(distance)
+}
+
+/// Estimate the overlapping coefficient of the two data sets: the area shared by their densities,
+/// which ranges from zero for completely separate distributions to one for identical ones. Unlike
+/// Cohen's d, it makes no assumptions about the shape of the distributions, so it remains
+/// meaningful for skewed or multimodal measurements.
+///
+/// Each density is estimated using a Gaussian kernel with the bandwidth given by Silverman's rule
+/// of thumb, and the minimum of the two is integrated numerically.
+///
+/// ```
+/// use nanostat::distance::overlap;
+///
+/// let a = [10.0, 11.0, 12.0, 11.5, 10.5, 11.2];
+/// let b = [10.8, 11.8, 12.5, 12.2, 11.4, 12.0];
+/// let ovl = overlap(&a, &b)?;
+/// assert!(0.3 < ovl && ovl < 0.9);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn overlap(a: &[f64], b: &[f64]) -> Result<f64, Error> {
+    const STEPS: usize = 1_000;
+
+    let (a, b) = (sorted(a)?, sorted(b)?);
+    let (ha, hb) = (bandwidth(&a)?, bandwidth(&b)?);
+    let density = |values: &[f64], h: f64, x: f64| {
+        let sum: f64 = values.iter().map(|v| (-((x - v) / h).powi(2) / 2.0).exp()).sum();
+        sum / (values.len() as f64 * h * (2.0 * PI).sqrt())
+    };
+
+    // Integrate the smaller density using the trapezoidal rule over the range in which either is
+    // non-negligible.
+    let low = (a[0] - 6.0 * ha).min(b[0] - 6.0 * hb);
+    let high = (a[a.len() - 1] + 6.0 * ha).max(b[b.len() - 1] + 6.0 * hb);
+    let step = (high - low) / STEPS as f64;
+    let area: f64 = (0..=STEPS)
+        .map(|i| {
+            let x = low + step * i as f64;
+            let weight = if i == 0 || i == STEPS { 0.5 } else { 1.0 };
+            weight * density(&a, ha, x).min(density(&b, hb, x))
+        })
+        .sum();
+    Ok((area * step).min(1.0))
+}
+
+/// Sort the given measurements, returning an error if there are too few or any are NaN or
+/// infinite.
+fn sorted(values: &[f64]) -> Result<Vec<f64>, Error> {
+    if values.len() < 2 {
+        return Err(Error::InsufficientSamples);
+    }
+    if values.iter().any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    Ok(sorted)
+}
+
+/// Calculate the bandwidth of a Gaussian kernel density estimate for the given sorted measurements
+/// using Silverman's rule of thumb, falling back to the standard deviation alone if the
+/// interquartile range is zero.
+fn bandwidth(sorted: &[f64]) -> Result<f64, Error> {
+    let sd = Summary::of(sorted).std_dev();
+    if sd == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+    let iqr = quantile(sorted, 0.75) - quantile(sorted, 0.25);
+    let spread = if iqr > 0.0 { sd.min(iqr / 1.34) } else { sd };
+    Ok(0.9 * spread * (sorted.len() as f64).powf(-0.2))
+}
+
+/// Bin both data sets using the same edges, returning the proportion of each in each bin after
+/// adding the given pseudo-count to each bin.
+fn distributions(
+    p: &[f64],
+    q: &[f64],
+    binning: Binning,
+    pseudo_count: f64,
+) -> Result<(Vec<f64>, Vec<f64>), Error> {
+    if p.is_empty() || q.is_empty() {
+        return Err(Error::InsufficientSamples);
+    }
+    let pooled: Vec<f64> = p.iter().chain(q.iter()).copied().collect();
+    let edges = Histogram::new(&pooled, binning)?.edges;
+    let bins = edges.len() - 1;
+
+    let distribution = |values: &[f64]| {
+        let mut counts = vec![pseudo_count; bins];
+        for &x in values {
+            // Include the maximum in the last bin.
+            let i = edges.partition_point(|&e| e <= x).saturating_sub(1);
+            counts[i.min(bins - 1)] += 1.0;
+        }
+        let total: f64 = counts.iter().sum();
+        counts.iter().map(|c| c / total).collect::<Vec<f64>>()
+    };
+    Ok((distribution(p), distribution(q)))
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn kullback_leibler() {
+        let (p, q) = ([1.0, 2.0, 3.0, 4.0], [3.0, 4.0, 5.0, 6.0]);
+
+        assert_relative_eq!(
+            kl_divergence(&p, &q, Binning::Fixed(5)).unwrap(),
+            0.383248909198043,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(kl_divergence(&p, &p, Binning::Fixed(5)).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn jensen_shannon_distance() {
+        let (p, q) = ([1.0, 2.0, 3.0, 4.0], [3.0, 4.0, 5.0, 6.0]);
+
+        let d = jensen_shannon(&p, &q, Binning::Fixed(5)).unwrap();
+        assert_relative_eq!(d, std::f64::consts::FRAC_1_SQRT_2, epsilon = 1e-12);
+        assert_relative_eq!(jensen_shannon(&q, &p, Binning::Fixed(5)).unwrap(), d);
+        assert_relative_eq!(jensen_shannon(&p, &p, Binning::default()).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn earth_movers_distance() {
+        let (a, b) = ([1.0, 2.0, 3.0], [4.0, 5.0, 6.0]);
+        assert_relative_eq!(wasserstein(&a, &b).unwrap(), 3.0, epsilon = 1e-12);
+        assert_relative_eq!(wasserstein(&b, &a).unwrap(), 3.0, epsilon = 1e-12);
+        assert_relative_eq!(wasserstein(&[0.0], &[1.0, 3.0]).unwrap(), 2.0);
+        assert_relative_eq!(wasserstein(&[0.0, 1.0], &[1.0, 0.0, 1.0, 0.0]).unwrap(), 0.0);
+        assert_relative_eq!(
+            wasserstein(&[1.0, 2.0, 2.0, 7.0], &[3.0, 1.0]).unwrap(),
+            1.5,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn overlapping_coefficient() {
+        let a = [10.0, 11.0, 12.0, 11.5, 10.5, 11.2];
+        let b = [10.8, 11.8, 12.5, 12.2, 11.4, 12.0];
+        assert_relative_eq!(overlap(&a, &b).unwrap(), 0.592644968171531, epsilon = 1e-6);
+        assert_relative_eq!(overlap(&b, &a).unwrap(), 0.592644968171531, epsilon = 1e-6);
+        assert_relative_eq!(overlap(&a, &a).unwrap(), 1.0, epsilon = 1e-6);
+
+        let c: Vec<f64> = a.iter().map(|x| x + 5.0).collect();
+        assert_relative_eq!(overlap(&a, &c).unwrap(), 1.08087745277087e-5, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn distance_errors() {
+        let p = [1.0, 2.0, 3.0];
+        assert_eq!(
+            kl_divergence(&p, &[], Binning::Fixed(5)).unwrap_err(),
+            Error::InsufficientSamples
+        );
+        assert_eq!(
+            jensen_shannon(&p, &[f64::NAN], Binning::Fixed(5)).unwrap_err(),
+            Error::NonFinite
+        );
+        assert_eq!(jensen_shannon(&p, &[-1.0], Binning::Log(5)).unwrap_err(), Error::NonPositive);
+        assert_eq!(wasserstein(&p, &[]).unwrap_err(), Error::InsufficientSamples);
+        assert_eq!(wasserstein(&p, &[f64::INFINITY]).unwrap_err(), Error::NonFinite);
+        assert_eq!(overlap(&p, &[1.0]).unwrap_err(), Error::InsufficientSamples);
+        assert_eq!(overlap(&p, &[1.0, 1.0]).unwrap_err(), Error::ZeroVariance);
+    }
+}
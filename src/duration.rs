@@ -0,0 +1,178 @@
+//! Parsing of durations with units, such as `12.3ms` or `450µs`, so timings from benchmark logs
+//! can be summarized without preprocessing.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Error;
+
+/// A unit of time.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unit {
+    /// Nanoseconds (`ns`).
+    #[default]
+    Nanoseconds,
+    /// Microseconds (`µs`, `μs`, or `us`).
+    Microseconds,
+    /// Milliseconds (`ms`).
+    Milliseconds,
+    /// Seconds (`s`).
+    Seconds,
+    /// Minutes (`m` or `min`).
+    Minutes,
+    /// Hours (`h`).
+    Hours,
+}
+
+impl Unit {
+    /// The suffixes of each unit, ordered so that no suffix is a suffix of one that follows it.
+    const SUFFIXES: [(&'static str, Unit); 9] = [
+        ("ns", Unit::Nanoseconds),
+        ("µs", Unit::Microseconds),
+        ("μs", Unit::Microseconds),
+        ("us", Unit::Microseconds),
+        ("ms", Unit::Milliseconds),
+        ("min", Unit::Minutes),
+        ("s", Unit::Seconds),
+        ("m", Unit::Minutes),
+        ("h", Unit::Hours),
+    ];
+
+    /// The number of nanoseconds in the unit.
+    #[must_use]
+    pub const fn nanos(&self) -> f64 {
+        match self {
+            Unit::Nanoseconds => 1.0,
+            Unit::Microseconds => 1e3,
+            Unit::Milliseconds => 1e6,
+            Unit::Seconds => 1e9,
+            Unit::Minutes => 60e9,
+            Unit::Hours => 3600e9,
+        }
+    }
+}
+
+/// Formats the unit's conventional suffix, e.g. `ms`.
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = match self {
+            Unit::Nanoseconds => "ns",
+            Unit::Microseconds => "µs",
+            Unit::Milliseconds => "ms",
+            Unit::Seconds => "s",
+            Unit::Minutes => "m",
+            Unit::Hours => "h",
+        };
+        f.write_str(suffix)
+    }
+}
+
+/// Parses any of the unit's suffixes.
+impl FromStr for Unit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unit::SUFFIXES
+            .iter()
+            .find(|(suffix, _)| *suffix == s.trim())
+            .map(|&(_, unit)| unit)
+            .ok_or(Error::InvalidDuration)
+    }
+}
+
+/// A span of time, which may be negative or fractional, unlike [std::time::Duration].
+///
+/// ```
+/// use nanostat::duration::{Duration, Unit};
+///
+/// let d: Duration = "450µs".parse()?;
+/// assert_eq!(d.as_unit(Unit::Milliseconds), 0.45);
+/// assert_eq!("1.2s".parse::<Duration>()?.as_unit(Unit::Milliseconds), 1200.0);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Duration {
+    nanos: f64,
+}
+
+impl Duration {
+    /// Create a duration of the given value in the given unit.
+    #[must_use]
+    pub fn new(value: f64, unit: Unit) -> Duration {
+        Duration { nanos: value * unit.nanos() }
+    }
+
+    /// The duration in nanoseconds.
+    #[must_use]
+    pub const fn as_nanos(&self) -> f64 {
+        self.nanos
+    }
+
+    /// The duration in the given unit.
+    #[must_use]
+    pub fn as_unit(&self, unit: Unit) -> f64 {
+        self.nanos / unit.nanos()
+    }
+}
+
+/// Parses a finite number followed by a unit suffix, optionally separated by whitespace, e.g.
+/// `12.3ms`, `450 us`, or `1e3ns`, returning [Error::InvalidDuration] if either is missing or
+/// invalid.
+impl FromStr for Duration {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (value, unit) = Unit::SUFFIXES
+            .iter()
+            .find_map(|&(suffix, unit)| s.strip_suffix(suffix).map(|value| (value, unit)))
+            .ok_or(Error::InvalidDuration)?;
+        match value.trim_end().parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(Duration::new(value, unit)),
+            _ => Err(Error::InvalidDuration),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn parse_durations() {
+        let nanos = |s: &str| s.parse::<Duration>().unwrap().as_nanos();
+
+        assert_relative_eq!(nanos("12ns"), 12.0);
+        assert_relative_eq!(nanos("450µs"), 450e3);
+        assert_relative_eq!(nanos("450μs"), 450e3);
+        assert_relative_eq!(nanos("450us"), 450e3);
+        assert_relative_eq!(nanos("12.3ms"), 12.3e6);
+        assert_relative_eq!(nanos("1.2s"), 1.2e9);
+        assert_relative_eq!(nanos("2m"), 120e9);
+        assert_relative_eq!(nanos("2min"), 120e9);
+        assert_relative_eq!(nanos("1h"), 3600e9);
+        assert_relative_eq!(nanos(" 1e3 ns "), 1e3);
+        assert_relative_eq!(nanos("-5ms"), -5e6);
+    }
+
+    #[test]
+    fn convert_durations() {
+        let d = Duration::new(1.5, Unit::Seconds);
+        assert_relative_eq!(d.as_unit(Unit::Milliseconds), 1500.0);
+        assert_relative_eq!(d.as_unit(Unit::Minutes), 0.025);
+        assert_eq!("ms".parse::<Unit>(), Ok(Unit::Milliseconds));
+        assert_eq!(Unit::Microseconds.to_string(), "µs");
+    }
+
+    #[test]
+    fn duration_errors() {
+        for s in ["", "ms", "12", "12 parsecs", "infms", "NaNs", "1.2.3s"] {
+            assert_eq!(s.parse::<Duration>(), Err(Error::InvalidDuration), "{:?}", s);
+        }
+        assert_eq!("fortnight".parse::<Unit>(), Err(Error::InvalidDuration));
+    }
+}
@@ -0,0 +1,186 @@
+//! Histograms of raw measurements, for rendering distributions or custom reporting.
+
+use crate::quantiles::quantile;
+use crate::Error;
+
+/// A strategy for choosing the bins of a [Histogram].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Binning {
+    /// The given number of equal-width bins.
+    Fixed(usize),
+    /// Equal-width bins sized by the Freedman-Diaconis rule, `2 * IQR / n^(1/3)`, which is robust
+    /// to outliers. If the rule calls for more bins than measurements, e.g. for a data set with no
+    /// interquartile spread or with extreme outliers, Sturges' rule, `log2(n) + 1`, is used instead.
+    #[default]
+    FreedmanDiaconis,
+    /// The given number of bins of equal width on a logarithmic scale, for measurements spanning
+    /// several orders of magnitude. All measurements must be positive.
+    Log(usize),
+}
+
+/// A histogram of a data set.
+///
+/// ```
+/// use nanostat::histogram::{Binning, Histogram};
+///
+/// let h = Histogram::new(&[1.0, 2.0, 2.5, 3.0, 3.5, 4.0, 10.0], Binning::Fixed(3))?;
+/// assert_eq!(h.edges, vec![1.0, 4.0, 7.0, 10.0]);
+/// assert_eq!(h.counts, vec![5, 1, 1]);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Histogram {
+    /// The edges of the bins, in ascending order. Bin `i` covers `[edges[i], edges[i + 1])`,
+    /// except for the last bin, which also includes its upper edge.
+    pub edges: Vec<f64>,
+
+    /// The number of measurements in each bin.
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Create a histogram of the given measurements, using the given binning strategy.
+    pub fn new(values: &[f64], binning: Binning) -> Result<Histogram, Error> {
+        if values.is_empty() {
+            return Err(Error::InsufficientSamples);
+        }
+        if values.iter().any(|x| !x.is_finite()) {
+            return Err(Error::NonFinite);
+        }
+
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+
+        // Calculate the number of bins.
+        let bins = match binning {
+            Binning::Fixed(bins) => bins,
+            Binning::FreedmanDiaconis => {
+                let iqr = quantile(&sorted, 0.75) - quantile(&sorted, 0.25);
+                let width = 2.0 * iqr / (sorted.len() as f64).cbrt();
+                let bins = ((max - min) / width).ceil();
+                if bins.is_finite() && bins <= sorted.len() as f64 {
+                    bins as usize
+                } else {
+                    // Fall back to Sturges' rule for data sets with no interquartile spread or
+                    // with outliers far outside it, which would otherwise need unbounded bins.
+                    (sorted.len() as f64).log2().ceil() as usize + 1
+                }
+            }
+            Binning::Log(bins) => {
+                if min <= 0.0 {
+                    return Err(Error::NonPositive);
+                }
+                bins
+            }
+        }
+        .max(1);
+
+        // Map the measurements onto the scale on which the bins are of equal width.
+        let log = matches!(binning, Binning::Log(_));
+        let scale = |x: f64| if log { x.ln() } else { x };
+        let (low, high) = (scale(min), scale(max));
+        let width = (high - low) / bins as f64;
+
+        // Calculate the edges of the bins, pinning the outermost to the extreme measurements.
+        let mut edges: Vec<f64> = (0..=bins)
+            .map(|i| low + width * i as f64)
+            .map(|x| if log { x.exp() } else { x })
+            .collect();
+        edges[0] = min;
+        edges[bins] = max;
+
+        // Count the measurements in each bin, including the maximum in the last bin.
+        let mut counts = vec![0; bins];
+        for x in sorted {
+            let i = if width > 0.0 { ((scale(x) - low) / width).floor() as usize } else { 0 };
+            counts[i.min(bins - 1)] += 1;
+        }
+
+        Ok(Histogram { edges, counts })
+    }
+
+    /// The number of bins in the histogram.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Whether or not the histogram has no bins.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The bins of the histogram, as `(low, high, count)` tuples.
+    pub fn bins(&self) -> impl Iterator<Item = (f64, f64, usize)> + '_ {
+        self.edges.windows(2).zip(self.counts.iter()).map(|(e, &c)| (e[0], e[1], c))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn fixed_bins() {
+        let h = Histogram::new(&[1.0, 2.0, 2.5, 3.0, 3.5, 4.0, 10.0], Binning::Fixed(3)).unwrap();
+
+        assert_eq!(h.len(), 3);
+        assert_eq!(h.edges, vec![1.0, 4.0, 7.0, 10.0]);
+        assert_eq!(h.counts, vec![5, 1, 1]);
+        assert_eq!(h.bins().next(), Some((1.0, 4.0, 5)));
+    }
+
+    #[test]
+    fn freedman_diaconis_bins() {
+        let values: Vec<f64> = (0..64).map(f64::from).collect();
+        let h = Histogram::new(&values, Binning::FreedmanDiaconis).unwrap();
+
+        // The IQR is 31.5, so the bins are 15.75 wide.
+        assert_eq!(h.len(), 4);
+        assert_relative_eq!(h.edges[1], 15.75);
+        assert_eq!(h.counts, vec![16, 16, 16, 16]);
+
+        let h = Histogram::new(&[1.0, 1.0, 1.0, 1.0], Binning::FreedmanDiaconis).unwrap();
+        assert_eq!(h.counts.iter().sum::<usize>(), 4);
+    }
+
+    #[test]
+    fn freedman_diaconis_outliers() {
+        // A tiny IQR and one extreme outlier would call for trillions of bins.
+        let mut values: Vec<f64> = (0..1_000).map(|i| 1.0 + f64::from(i) * 1e-9).collect();
+        values.push(1e6);
+        let h = Histogram::new(&values, Binning::FreedmanDiaconis).unwrap();
+
+        // Sturges' rule: log2(1001) + 1, rounded up.
+        assert_eq!(h.len(), 11);
+        assert_eq!(h.counts[0], 1_000);
+        assert_eq!(h.counts[10], 1);
+    }
+
+    #[test]
+    fn log_bins() {
+        let h =
+            Histogram::new(&[1.0, 5.0, 10.0, 50.0, 100.0, 500.0, 1000.0], Binning::Log(3)).unwrap();
+
+        assert_relative_eq!(h.edges[1], 10.0, epsilon = 1e-9);
+        assert_relative_eq!(h.edges[2], 100.0, epsilon = 1e-9);
+        assert_eq!(h.counts, vec![2, 2, 3]);
+
+        assert_eq!(Histogram::new(&[0.0, 1.0], Binning::Log(3)).unwrap_err(), Error::NonPositive);
+    }
+
+    #[test]
+    fn histogram_errors() {
+        assert_eq!(Histogram::new(&[], Binning::Fixed(3)).unwrap_err(), Error::InsufficientSamples);
+        assert_eq!(
+            Histogram::new(&[1.0, f64::NAN], Binning::Fixed(3)).unwrap_err(),
+            Error::NonFinite
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! A common interface to the two-sample hypothesis tests, so tests can be selected at runtime, e.g.
+//! by name, and custom tests can be used wherever the built-in tests are.
+//!
+//! ```
+//! use nanostat::hypothesis::{by_name, StatisticalTest};
+//! use nanostat::{Dataset, Options};
+//!
+//! let a = Dataset::new((1..=10).map(f64::from).collect())?;
+//! let b = Dataset::new((6..=15).map(f64::from).collect())?;
+//! for name in ["welch", "mann-whitney", "permutation"] {
+//!     let test = by_name(name).unwrap();
+//!     assert!(test.test(&a, &b, Options::default())?.is_significant(), "{}", test.name());
+//! }
+//! # Ok::<(), nanostat::Error>(())
+//! ```
+
+use crate::resampling::Resampling;
+use crate::{Alternative, Confidence, Dataset, Error, Options};
+
+/// A test for whether two data sets differ significantly.
+pub trait StatisticalTest {
+    /// The human-readable name of the test, e.g. `Welch's t-test`.
+    fn name(&self) -> &str;
+
+    /// Test whether the two data sets differ at the options' confidence level and with respect to
+    /// their alternative hypothesis. Tests which only support two-sided alternatives return
+    /// [Error::UnsupportedAlternative] for one-sided ones.
+    fn test(&self, a: &Dataset, b: &Dataset, options: Options) -> Result<Outcome, Error>;
+}
+
+/// The outcome of a [StatisticalTest]. Unlike a [crate::Difference], it has only the results which
+/// every test has, since rank and distribution tests have no difference in means, t-statistic, or
+/// degrees of freedom. Use the test-specific functions directly for their full results, e.g.
+/// effect sizes and confidence intervals.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The test statistic, e.g. t for Welch's t-test or U for the Mann-Whitney U test.
+    pub statistic: f64,
+
+    /// The p-value for the test.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl Outcome {
+    /// Whether or not the data sets differ significantly.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Welch's t-test for a difference in means; see [crate::Summary::try_compare_with].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WelchTest;
+
+impl StatisticalTest for WelchTest {
+    fn name(&self) -> &str {
+        "Welch's t-test"
+    }
+
+    fn test(&self, a: &Dataset, b: &Dataset, options: Options) -> Result<Outcome, Error> {
+        let diff = a.summary().try_compare_with(&b.summary(), options)?;
+        Ok(Outcome { statistic: diff.t_statistic, p_value: diff.p_value, alpha: diff.alpha })
+    }
+}
+
+/// The Mann-Whitney U test for stochastic dominance; see [crate::nonparametric::mann_whitney].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MannWhitneyTest;
+
+impl StatisticalTest for MannWhitneyTest {
+    fn name(&self) -> &str {
+        "Mann-Whitney U test"
+    }
+
+    fn test(&self, a: &Dataset, b: &Dataset, options: Options) -> Result<Outcome, Error> {
+        let mw = a.mann_whitney(b, two_sided(options)?)?;
+        Ok(Outcome { statistic: mw.u, p_value: mw.p_value, alpha: mw.alpha })
+    }
+}
+
+/// The Brunner-Munzel test for stochastic dominance; see
+/// [crate::nonparametric::brunner_munzel].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BrunnerMunzelTest;
+
+impl StatisticalTest for BrunnerMunzelTest {
+    fn name(&self) -> &str {
+        "Brunner-Munzel test"
+    }
+
+    fn test(&self, a: &Dataset, b: &Dataset, options: Options) -> Result<Outcome, Error> {
+        let bm = a.brunner_munzel(b, two_sided(options)?)?;
+        Ok(Outcome { statistic: bm.w, p_value: bm.p_value, alpha: bm.alpha })
+    }
+}
+
+/// The Kolmogorov-Smirnov test for a difference in distributions; see
+/// [crate::nonparametric::kolmogorov_smirnov].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct KolmogorovSmirnovTest;
+
+impl StatisticalTest for KolmogorovSmirnovTest {
+    fn name(&self) -> &str {
+        "Kolmogorov-Smirnov test"
+    }
+
+    fn test(&self, a: &Dataset, b: &Dataset, options: Options) -> Result<Outcome, Error> {
+        let ks = a.kolmogorov_smirnov(b, two_sided(options)?)?;
+        Ok(Outcome { statistic: ks.d, p_value: ks.p_value, alpha: ks.alpha })
+    }
+}
+
+/// A permutation test for a difference in means; see [crate::resampling::permutation]. The
+/// statistic is the difference between the means.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PermutationTest {
+    /// The number of permutations and the seed used to generate them.
+    pub resampling: Resampling,
+}
+
+impl StatisticalTest for PermutationTest {
+    fn name(&self) -> &str {
+        "Permutation test"
+    }
+
+    fn test(&self, a: &Dataset, b: &Dataset, options: Options) -> Result<Outcome, Error> {
+        let p = a.permutation(b, two_sided(options)?, self.resampling)?;
+        Ok(Outcome { statistic: p.effect, p_value: p.p_value, alpha: p.alpha })
+    }
+}
+
+/// Return the confidence level of the options, or [Error::UnsupportedAlternative] if they have a
+/// one-sided alternative hypothesis.
+const fn two_sided(options: Options) -> Result<Confidence, Error> {
+    match options.alternative {
+        Alternative::TwoSided => Ok(options.confidence),
+        _ => Err(Error::UnsupportedAlternative),
+    }
+}
+
+/// The names of the built-in tests, as accepted by [by_name].
+pub const NAMES: [&str; 5] =
+    ["welch", "mann-whitney", "brunner-munzel", "kolmogorov-smirnov", "permutation"];
+
+/// Look up one of the built-in tests by name, using the default settings for tests which have
+/// them. Returns `None` if the name is unknown; see [NAMES].
+#[must_use]
+pub fn by_name(name: &str) -> Option<Box<dyn StatisticalTest>> {
+    match name {
+        "welch" => Some(Box::new(WelchTest)),
+        "mann-whitney" => Some(Box::new(MannWhitneyTest)),
+        "brunner-munzel" => Some(Box::new(BrunnerMunzelTest)),
+        "kolmogorov-smirnov" => Some(Box::new(KolmogorovSmirnovTest)),
+        "permutation" => Some(Box::new(PermutationTest::default())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn built_in_tests() {
+        let a = Dataset::new((1..=10).map(f64::from).collect()).unwrap();
+        let b = Dataset::new((9..=18).map(f64::from).collect()).unwrap();
+
+        for name in NAMES {
+            let outcome = by_name(name).unwrap().test(&a, &b, Options::default()).unwrap();
+            assert!(outcome.is_significant(), "{}", name);
+            assert_relative_eq!(outcome.alpha, 0.05, epsilon = 1e-12);
+        }
+
+        let opts = Options::default();
+        let welch = WelchTest.test(&a, &b, opts).unwrap();
+        assert_relative_eq!(welch.p_value, a.try_compare(&b, Confidence::P95).unwrap().p_value);
+        assert_relative_eq!(MannWhitneyTest.test(&a, &b, opts).unwrap().statistic, 2.0);
+        assert_relative_eq!(KolmogorovSmirnovTest.test(&a, &b, opts).unwrap().statistic, 0.8);
+        assert!(by_name("t-test").is_none());
+    }
+
+    #[test]
+    fn one_sided_tests() {
+        let a = Dataset::new((1..=10).map(f64::from).collect()).unwrap();
+        let b = Dataset::new((9..=18).map(f64::from).collect()).unwrap();
+        let greater = Options { alternative: Alternative::Greater, ..Options::default() };
+        let less = Options { alternative: Alternative::Less, ..Options::default() };
+
+        let two_sided = WelchTest.test(&a, &b, Options::default()).unwrap();
+        assert_relative_eq!(
+            WelchTest.test(&a, &b, greater).unwrap().p_value,
+            two_sided.p_value / 2.0
+        );
+        assert!(!WelchTest.test(&a, &b, less).unwrap().is_significant());
+
+        for name in &NAMES[1..] {
+            let err = by_name(name).unwrap().test(&a, &b, greater).unwrap_err();
+            assert_eq!(err, Error::UnsupportedAlternative, "{}", name);
+        }
+    }
+
+    #[test]
+    fn custom_tests() {
+        struct Always;
+
+        impl StatisticalTest for Always {
+            fn name(&self) -> &str {
+                "always significant"
+            }
+
+            fn test(&self, _: &Dataset, _: &Dataset, o: Options) -> Result<Outcome, Error> {
+                let alpha = 1.0 - o.confidence.percent() / 100.0;
+                Ok(Outcome { statistic: 0.0, p_value: 0.0, alpha })
+            }
+        }
+
+        let tests: Vec<Box<dyn StatisticalTest>> = vec![Box::new(WelchTest), Box::new(Always)];
+        let a = Dataset::new(vec![1.0, 2.0, 3.0]).unwrap();
+        let significant: Vec<bool> = tests
+            .iter()
+            .map(|t| t.test(&a, &a, Options::default()).unwrap().is_significant())
+            .collect();
+        assert_eq!(significant, vec![false, true]);
+    }
+}
@@ -1,5 +1,10 @@
 //! nanostat compares data sets using Welch's t-test at various levels of confidence.
+//!
+//! Without the default `std` feature, the crate is `no_std` and provides only the construction and
+//! merging of [Summary] instances, so measurements can be summarized on embedded targets and
+//! compared elsewhere.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![forbid(unsafe_code)]
 #![warn(
     missing_docs,
@@ -14,23 +19,238 @@
     clippy::needless_borrow
 )]
 
-use std::iter::FromIterator;
+#[cfg(not(feature = "std"))]
+extern crate alloc;
 
-use statrs::distribution::{ContinuousCDF, Normal, StudentsT};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FromIterator;
+
+#[cfg(feature = "std")]
+use statrs::distribution::{ChiSquared, ContinuousCDF, FisherSnedecor, Normal, StudentsT};
+
+#[cfg(feature = "std")]
+pub use anova::{anova, repeated_measures_anova, Anova};
+#[cfg(feature = "std")]
+pub use dataset::Dataset;
+#[cfg(feature = "std")]
+use rates::quantile;
+#[cfg(feature = "std")]
+use variance::{f_quantile, VarianceRatio};
+
+#[cfg(feature = "std")]
+mod anova;
+#[cfg(feature = "std")]
+pub mod bayesian;
+#[cfg(feature = "std")]
+pub mod categorical;
+#[cfg(feature = "std")]
+pub mod changepoint;
+#[cfg(feature = "std")]
+pub mod corrections;
+#[cfg(feature = "std")]
+pub mod correlation;
+#[cfg(feature = "std")]
+mod dataset;
+#[cfg(feature = "std")]
+pub mod distance;
+#[cfg(feature = "std")]
+pub mod duration;
+#[cfg(feature = "std")]
+pub mod histogram;
+#[cfg(feature = "std")]
+pub mod hypothesis;
+#[cfg(feature = "std")]
+pub mod lognormal;
+#[cfg(feature = "std")]
+pub mod meta;
+#[cfg(feature = "std")]
+pub mod monitoring;
+#[cfg(feature = "std")]
+pub mod nonparametric;
+#[cfg(feature = "std")]
+pub mod normality;
+#[cfg(feature = "std")]
+pub mod outliers;
+#[cfg(feature = "std")]
+pub mod posthoc;
+#[cfg(feature = "std")]
+pub mod power;
+#[cfg(feature = "std")]
+pub mod proportions;
+#[cfg(feature = "std")]
+pub mod quantiles;
+#[cfg(feature = "std")]
+pub mod rates;
+#[cfg(feature = "std")]
+pub mod regression;
+#[cfg(feature = "std")]
+pub mod resampling;
+#[cfg(feature = "std")]
+pub mod sequential;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod variance;
+
+/// A confidence level for a statistical test.
+///
+/// The standard levels cover most uses; [Confidence::Custom] accepts any percentage in the range
+/// `(0, 100)`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Confidence {
+    /// 80% confidence.
+    P80,
+    /// 90% confidence.
+    P90,
+    /// 95% confidence.
+    #[default]
+    P95,
+    /// 98% confidence.
+    P98,
+    /// 99% confidence.
+    P99,
+    /// 99.9% confidence.
+    P999,
+    /// A custom confidence level, expressed as a percentage in the range `(0, 100)`.
+    Custom(f64),
+}
+
+impl Confidence {
+    /// The confidence level as a percentage.
+    #[must_use]
+    pub const fn percent(&self) -> f64 {
+        match *self {
+            Confidence::P80 => 80.0,
+            Confidence::P90 => 90.0,
+            Confidence::P95 => 95.0,
+            Confidence::P98 => 98.0,
+            Confidence::P99 => 99.0,
+            Confidence::P999 => 99.9,
+            Confidence::Custom(p) => p,
+        }
+    }
+
+    /// The significance level corresponding to the confidence level.
+    #[must_use]
+    pub fn alpha(&self) -> f64 {
+        1.0 - (self.percent() / 100.0)
+    }
+
+    /// The significance level, or an error if the confidence level is outside `(0, 100)`.
+    #[cfg(feature = "std")]
+    pub(crate) fn checked_alpha(&self) -> Result<f64, Error> {
+        let p = self.percent();
+        if !(0.0 < p && p < 100.0) {
+            return Err(Error::InvalidConfidence(p));
+        }
+        Ok(self.alpha())
+    }
+
+    /// The standard confidence levels, in ascending order.
+    pub const STANDARD: [Confidence; 6] = [
+        Confidence::P80,
+        Confidence::P90,
+        Confidence::P95,
+        Confidence::P98,
+        Confidence::P99,
+        Confidence::P999,
+    ];
+
+    /// The highest standard confidence level at which a test with the given p-value is
+    /// significant, or `None` if it isn't significant even at 80% confidence.
+    ///
+    /// ```
+    /// use nanostat::Confidence;
+    ///
+    /// assert_eq!(Confidence::highest(0.004), Some(Confidence::P99));
+    /// assert_eq!(Confidence::highest(0.5), None);
+    /// ```
+    #[must_use]
+    pub fn highest(p_value: f64) -> Option<Confidence> {
+        Confidence::STANDARD.into_iter().rev().find(|c| p_value < c.alpha())
+    }
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.percent())
+    }
+}
 
 /// The statistical difference between two [Summary] instances.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Difference {
     /// The absolute difference between the samples' means.
     pub effect: f64,
 
-    /// The difference in means between the two samples, normalized for variance. Technically, this
-    /// is Cohen's d.
+    /// The signed difference between the samples' means: the second sample's mean minus the
+    /// first's.
+    pub delta: f64,
+
+    /// The difference in means between the two samples, normalized for variance. By default, this
+    /// is Cohen's d using the unweighted mean of the variances; see [EffectSize] for alternatives.
+    /// For paired data, this is Cohen's d for the per-pair differences (d_z).
     pub effect_size: f64,
 
+    /// The effect size corrected for its upward bias in small samples. For Cohen's d, this is
+    /// Hedges' g.
+    pub hedges_g: f64,
+
+    /// The common-language effect size, or probability of superiority: the probability that a
+    /// random measurement from the second sample is larger than a random measurement from the
+    /// first, assuming both are normally distributed. For paired data, this is the probability
+    /// that the second measurement of a random pair is larger than the first.
+    pub probability_of_superiority: f64,
+
+    /// The lower bound of the two-sided confidence interval for [Difference::effect_size] at the
+    /// given confidence level, calculated by inverting the noncentral t-distribution of the
+    /// pooled-variance t-statistic. For standardizers other than [EffectSize::PooledCohensD], the
+    /// interval for the pooled effect size is rescaled to the chosen standardizer.
+    pub effect_size_ci_low: f64,
+
+    /// The upper bound of the two-sided confidence interval for [Difference::effect_size] at the
+    /// given confidence level, calculated by inverting the noncentral t-distribution of the
+    /// pooled-variance t-statistic.
+    pub effect_size_ci_high: f64,
+
     /// The minimum allowed effect at the given confidence level.
     pub critical_value: f64,
 
+    /// The experimental t-statistic: [Difference::delta] divided by its standard error.
+    pub t_statistic: f64,
+
+    /// The degrees of freedom of the t-distribution used by the test.
+    pub df: f64,
+
+    /// The standard error of the difference in means.
+    pub std_err: f64,
+
+    /// The signed difference between the samples' means as a percentage of the first sample's
+    /// mean, e.g. `4.2` for a 4.2% increase.
+    pub relative_delta: f64,
+
+    /// The lower bound of the confidence interval for [Difference::relative_delta], calculated
+    /// using Fieller's theorem. It is unbounded if the first sample's mean is not significantly
+    /// different from zero.
+    pub relative_ci_low: f64,
+
+    /// The upper bound of the confidence interval for [Difference::relative_delta], calculated
+    /// using Fieller's theorem. It is unbounded if the first sample's mean is not significantly
+    /// different from zero.
+    pub relative_ci_high: f64,
+
+    /// The lower bound of the confidence interval for the difference in means (the second
+    /// sample's mean minus the first's) at the given confidence level.
+    pub ci_low: f64,
+
+    /// The upper bound of the confidence interval for the difference in means (the second
+    /// sample's mean minus the first's) at the given confidence level.
+    pub ci_high: f64,
+
     /// The p-value for the test: the probability that accepting the results of this test will be a
     /// Type 1 error, in which the null hypothesis (i.e. there is no difference between the means of
     /// the two samples) will be rejected when it is in fact true.
@@ -39,27 +259,393 @@ pub struct Difference {
     /// The significance level of the test. It is the maximum allowed value of the p-value.
     pub alpha: f64,
 
+    /// The confidence level of the test.
+    pub confidence: Confidence,
+
+    /// The JZS Bayes factor for the test, BF₁₀: the ratio of the likelihood of the data if the
+    /// means differ to its likelihood if they don't. Values above 1 are evidence of a difference,
+    /// and values below 1 are evidence of no difference, which a p-value cannot express. The prior
+    /// on the standardized effect is a Cauchy distribution with a scale of √2/2.
+    pub bayes_factor: f64,
+
     /// The probability of a Type 2 error: the probability that the null hypothesis will be retained
     /// despite it not being true.
     pub beta: f64,
+
+    /// The comparison of the samples' variances, i.e. whether the second sample is more or less
+    /// consistent than the first, if requested with [Options::spread]; see
+    /// [Summary::variance_ratio]. It is `None` if the first sample's variance is zero.
+    #[cfg(feature = "std")]
+    pub spread: Option<VarianceRatio>,
 }
 
 impl Difference {
     /// Whether or not the difference is statistically significant.
     #[must_use]
     pub fn is_significant(&self) -> bool {
-        self.effect > self.critical_value
+        self.p_value < self.alpha
+    }
+
+    /// The direction of the change from the first sample to the second, assuming that lower
+    /// measurements (e.g. timings) are better.
+    #[must_use]
+    pub fn direction(&self) -> Direction {
+        if !self.is_significant() {
+            Direction::Indistinguishable
+        } else if self.delta < 0.0 {
+            Direction::Improved
+        } else {
+            Direction::Regressed
+        }
+    }
+
+    /// The qualitative magnitude of [Difference::effect_size], using Cohen's thresholds of 0.2,
+    /// 0.5, and 0.8 for small, medium, and large effects.
+    ///
+    /// ```
+    /// use nanostat::{Confidence, EffectMagnitude, Summary};
+    ///
+    /// let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+    /// let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+    /// assert_eq!(a.compare(&b, Confidence::P95).magnitude(), EffectMagnitude::Large);
+    /// ```
+    #[must_use]
+    pub fn magnitude(&self) -> EffectMagnitude {
+        match self.effect_size.abs() {
+            d if d < 0.2 => EffectMagnitude::Negligible,
+            d if d < 0.5 => EffectMagnitude::Small,
+            d if d < 0.8 => EffectMagnitude::Medium,
+            _ => EffectMagnitude::Large,
+        }
+    }
+
+    /// The conventional annotation of the difference's p-value: `***` if it is less than 0.001,
+    /// `**` if less than 0.01, `*` if less than 0.05, and `ns` (not significant) otherwise. This
+    /// ignores the confidence level of the test.
+    ///
+    /// ```
+    /// use nanostat::{Confidence, Summary};
+    ///
+    /// let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+    /// let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+    /// assert_eq!(a.compare(&b, Confidence::P95).stars(), "*");
+    /// ```
+    #[must_use]
+    pub fn stars(&self) -> &'static str {
+        match self.p_value {
+            p if p < 0.001 => "***",
+            p if p < 0.01 => "**",
+            p if p < 0.05 => "*",
+            _ => "ns",
+        }
+    }
+
+    /// The highest standard confidence level at which the difference is significant; see
+    /// [Confidence::highest].
+    #[must_use]
+    pub fn highest_confidence(&self) -> Option<Confidence> {
+        Confidence::highest(self.p_value)
+    }
+
+    /// A human-readable verdict on the difference between the given control and experiment, in
+    /// the same wording used by the command-line tool. Significant differences are followed by
+    /// indented lines giving the means, relative and multiplicative changes, and effect size. If
+    /// the variances were compared, a line comparing them follows either verdict.
+    ///
+    /// ```
+    /// use nanostat::{Confidence, Summary};
+    ///
+    /// let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+    /// let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+    /// let report = a.compare(&b, Confidence::P80).report(&a, &b);
+    /// assert!(report.starts_with("Difference at 80% confidence!\n\t25.00 > 2.50 ± 10.57, p = .039"));
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn report(&self, control: &Summary, experiment: &Summary) -> String {
+        let confidence = self.confidence.percent();
+        let mut lines = if self.is_significant() {
+            self.mean_report(control, experiment, confidence)
+        } else {
+            vec![format!("No difference at {}% confidence.", confidence)]
+        };
+        if let Some(vr) = self.spread {
+            let verdict = match (vr.is_significant(), vr.ratio < 1.0) {
+                (false, _) => "no difference in variance",
+                (true, true) => "less variable",
+                (true, false) => "more variable",
+            };
+            lines.push(format!(
+                "\tvariance {:.2}x the control's (CI [{:.2}x, {:.2}x]), p = {}: {}",
+                vr.ratio,
+                vr.ci_low,
+                vr.ci_high,
+                format_p(vr.p_value),
+                verdict,
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// The lines of [Difference::report] for a significant difference in means.
+    #[cfg(feature = "std")]
+    fn mean_report(&self, control: &Summary, experiment: &Summary, confidence: f64) -> Vec<String> {
+        let op = if self.delta < 0.0 { "<" } else { ">" };
+        let mut lines = vec![
+            format!("Difference at {}% confidence!", confidence),
+            format!(
+                "\t{:.2} {} {:.2} ± {:.2}, p = {}",
+                experiment.mean,
+                op,
+                control.mean,
+                self.critical_value,
+                format_p(self.p_value),
+            ),
+            format!(
+                "\t{:+.2}% (CI [{:+.2}%, {:+.2}%])",
+                self.relative_delta, self.relative_ci_low, self.relative_ci_high,
+            ),
+        ];
+        if let Ok(r) = control.ratio(experiment, Confidence::Custom(confidence)) {
+            lines.push(format!("\t{:.2}x (CI [{:.2}x, {:.2}x])", r.ratio, r.ci_low, r.ci_high));
+        }
+        lines.push(format!("\td = {:.2} ({} effect)", self.effect_size, self.magnitude()));
+        lines.push(format!(
+            "\t{:.0}% of runs are larger than the control's",
+            self.probability_of_superiority * 100.0,
+        ));
+        lines
+    }
+}
+
+/// The direction of a [Difference], assuming that lower measurements are better.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    /// The second sample's mean is significantly lower than the first's.
+    Improved,
+    /// The second sample's mean is significantly higher than the first's.
+    Regressed,
+    /// There is no statistically significant difference between the samples' means.
+    Indistinguishable,
+}
+
+/// Formats the verdict, confidence interval, and p-value of the difference, using the formatter's
+/// precision (2 by default) for the bounds. The alternate form (`{:#}`) is a compact summary of
+/// the effect and critical value.
+///
+/// ```
+/// use nanostat::{Confidence, Summary};
+///
+/// let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+/// let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+/// let diff = a.compare(&b, Confidence::P80);
+/// assert_eq!(diff.to_string(), "Difference at 80% confidence: CI [11.93, 33.07], p = .039");
+/// assert_eq!(format!("{:#.1}", diff), "22.5 ± 10.6, p = .039 (significant)");
+/// ```
+#[cfg(feature = "std")]
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prec = f.precision().unwrap_or(2);
+        let p = format_p(self.p_value);
+        if f.alternate() {
+            let verdict = if self.is_significant() { "significant" } else { "not significant" };
+            write!(
+                f,
+                "{:.*} ± {:.*}, p = {} ({})",
+                prec, self.effect, prec, self.critical_value, p, verdict
+            )
+        } else {
+            let verdict = if self.is_significant() { "Difference" } else { "No difference" };
+            let confidence = self.confidence.percent();
+            write!(
+                f,
+                "{} at {}% confidence: CI [{:.*}, {:.*}], p = {}",
+                verdict, confidence, prec, self.ci_low, prec, self.ci_high, p
+            )
+        }
+    }
+}
+
+/// The alternative hypothesis of a test, relative to the null hypothesis that there is no
+/// difference between the means of the two samples.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Alternative {
+    /// The means of the two samples are different.
+    #[default]
+    TwoSided,
+    /// The mean of the second sample is less than the mean of the first.
+    Less,
+    /// The mean of the second sample is greater than the mean of the first.
+    Greater,
+}
+
+impl Alternative {
+    /// The number of distribution tails used to determine significance.
+    #[cfg(feature = "std")]
+    const fn tails(&self) -> f64 {
+        match self {
+            Alternative::TwoSided => 2.0,
+            Alternative::Less | Alternative::Greater => 1.0,
+        }
+    }
+}
+
+/// The standardizer used to calculate [Difference::effect_size].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EffectSize {
+    /// Cohen's d, which normalizes the difference in means by the square root of the unweighted
+    /// mean of the two samples' variances. This matches [EffectSize::PooledCohensD] when the
+    /// samples are the same size, but gives a smaller sample's variance more weight otherwise.
+    #[default]
+    CohensD,
+    /// Cohen's d, normalized by the pooled standard deviation: the square root of the mean of the
+    /// two samples' variances, weighted by their degrees of freedom. This is the textbook
+    /// definition, and the one for which [Difference::effect_size_ci_low] and
+    /// [Difference::effect_size_ci_high] are exact.
+    PooledCohensD,
+    /// Glass's delta, which normalizes the difference in means by the first sample's standard
+    /// deviation only. This is more meaningful when the experiment changes the variance as well as
+    /// the mean.
+    GlassDelta,
+}
+
+/// The qualitative magnitude of an effect size.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EffectMagnitude {
+    /// An effect too small to be of practical interest.
+    Negligible,
+    /// A small effect.
+    Small,
+    /// A medium effect.
+    Medium,
+    /// A large effect.
+    Large,
+}
+
+impl fmt::Display for EffectMagnitude {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EffectMagnitude::Negligible => "negligible",
+            EffectMagnitude::Small => "small",
+            EffectMagnitude::Medium => "medium",
+            EffectMagnitude::Large => "large",
+        })
+    }
+}
+
+/// Options for [Summary::compare_with].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options {
+    /// The confidence level of the test.
+    pub confidence: Confidence,
+
+    /// The alternative hypothesis of the test.
+    pub alternative: Alternative,
+
+    /// The standardizer used to calculate the effect size.
+    pub effect_size: EffectSize,
+
+    /// Whether or not to also compare the samples' variances; see [Difference::spread].
+    pub spread: bool,
+}
+
+/// An equivalence margin for [Summary::equivalence].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Margin {
+    /// A margin in the same units as the measurements.
+    Absolute(f64),
+    /// A margin expressed as a percentage of the first summary's mean.
+    Relative(f64),
+}
+
+/// The result of an equivalence test between two [Summary] instances.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Equivalence {
+    /// The absolute equivalence margin. The means are equivalent if their difference is within
+    /// `(-margin, margin)`.
+    pub margin: f64,
+
+    /// The p-value of the one-sided test that the difference is no more than `-margin`.
+    pub p_lower: f64,
+
+    /// The p-value of the one-sided test that the difference is no less than `margin`.
+    pub p_upper: f64,
+
+    /// The p-value for the equivalence test, which is the larger of the two one-sided p-values.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl Equivalence {
+    /// Whether or not the means were shown to be equivalent. If not, they may still be equivalent,
+    /// but the data could not prove it.
+    #[must_use]
+    pub fn is_equivalent(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// The ratio between two samples' central values, such as the speedup factor of an experiment
+/// relative to a control.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ratio {
+    /// The ratio: the second sample's value divided by the first's.
+    pub ratio: f64,
+
+    /// The lower bound of the confidence interval for the ratio at the given confidence level.
+    pub ci_low: f64,
+
+    /// The upper bound of the confidence interval for the ratio at the given confidence level.
+    pub ci_high: f64,
+
+    /// The two-tailed p-value for the null hypothesis that the ratio is one.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl Ratio {
+    /// Whether or not the ratio is statistically significantly different from one.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
     }
 }
 
+/// How NaNs and infinities are handled when summarizing measurements with [Summary::screened].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NonFinitePolicy {
+    /// Return [Error::NonFinite] if any measurement is NaN or infinite.
+    #[default]
+    Strict,
+    /// Discard NaNs and infinities.
+    Skip,
+    /// Discard NaNs and infinities, counting how many were discarded.
+    Count,
+}
+
 /// A statistical summary of a normally distributed data set.
 ///
-/// Created from an iterable of `f64`s:
+/// Created from an iterable of `f64`s or `&f64`s:
 ///
 /// ```
-/// let summary: nanostat::Summary = vec![0.1, 0.45, 0.42].iter().collect();
+/// let summary: nanostat::Summary = [0.1, 0.45, 0.42].iter().collect();
+/// let scaled: nanostat::Summary = [0.1, 0.45, 0.42].iter().map(|x| x * 1000.0).collect();
 /// ```
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Summary {
     /// The number of measurements in the set.
     pub n: f64,
@@ -67,145 +653,2153 @@ pub struct Summary {
     pub mean: f64,
     /// The sample variance of the data set.
     pub variance: f64,
+    /// The third central moment of the data set: the mean of the cubed deviations from the mean.
+    pub m3: f64,
+    /// The fourth central moment of the data set: the mean of the deviations from the mean raised
+    /// to the fourth power.
+    pub m4: f64,
+    /// The smallest measurement in the set.
+    pub min: f64,
+    /// The largest measurement in the set.
+    pub max: f64,
+}
+
+impl FromIterator<f64> for Summary {
+    fn from_iter<T: IntoIterator<Item = f64>>(iter: T) -> Self {
+        let mut summary = Summary::EMPTY;
+        summary.extend(iter);
+        summary
+    }
 }
 
 impl<'a> FromIterator<&'a f64> for Summary {
     fn from_iter<T: IntoIterator<Item = &'a f64>>(iter: T) -> Self {
-        // Welford's one-pass algorithm for corrected variance
-        let (mut mean, mut s, mut n) = (0.0, 0.0, 0.0);
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl Extend<f64> for Summary {
+    fn extend<T: IntoIterator<Item = f64>>(&mut self, iter: T) {
+        // Welford's one-pass algorithm for corrected variance, extended to the third and fourth
+        // moments by Terriberry and resumed from the current state, with Neumaier's compensated
+        // summation of both the mean and the sum of squares
+        let n = self.n;
+        let (s, mut s3, mut s4) = self.sums();
+        let (mut n, mut mean, mut s) = (n, Neumaier::new(self.mean), Neumaier::new(s));
+        let (mut min, mut max) = (self.min, self.max);
         for x in iter {
+            (min, max) = (min.min(x), max.max(x));
+            let n1 = n;
             n += 1.0;
-            let delta = x - mean;
-            mean += delta / n;
-            s += delta * (x - mean);
+            let delta = (x - mean.sum) - mean.c;
+            let (delta_n, s2) = (delta / n, s.total());
+            let term = delta * delta_n * n1;
+            s4 += term * delta_n * delta_n * (n * n - 3.0 * n + 3.0) + 6.0 * delta_n * delta_n * s2
+                - 4.0 * delta_n * s3;
+            s3 += term * delta_n * (n - 2.0) - 3.0 * delta_n * s2;
+            mean.add(delta_n);
+            s.add(delta * ((x - mean.sum) - mean.c));
+        }
+        let variance = s.total() / (n - 1.0); // Bessel's correction
+        *self = Summary { n, mean: mean.total(), variance, m3: s3 / n, m4: s4 / n, min, max };
+    }
+}
+
+impl<'a> Extend<&'a f64> for Summary {
+    fn extend<T: IntoIterator<Item = &'a f64>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+/// Summarizes weighted measurements, given as `(value, weight)` pairs; see [Summary::push_weighted].
+/// Panics if a weight is negative or not finite.
+///
+/// ```
+/// // Latencies which have already been counted into buckets.
+/// let s: nanostat::Summary = [(10.0, 3.0), (20.0, 1.0)].into_iter().collect();
+/// assert_eq!((s.n, s.mean), (4.0, 12.5));
+/// ```
+impl FromIterator<(f64, f64)> for Summary {
+    fn from_iter<T: IntoIterator<Item = (f64, f64)>>(iter: T) -> Self {
+        let mut summary = Summary::EMPTY;
+        summary.extend(iter);
+        summary
+    }
+}
+
+impl Extend<(f64, f64)> for Summary {
+    fn extend<T: IntoIterator<Item = (f64, f64)>>(&mut self, iter: T) {
+        for (value, weight) in iter {
+            self.push_weighted(value, weight);
+        }
+    }
+}
+
+/// Formats the size, mean, and standard deviation of the summary, using the formatter's precision
+/// (2 by default) for the mean and standard deviation. The alternate form (`{:#}`) is more compact.
+///
+/// ```
+/// let s: nanostat::Summary = [90.0, 95.0, 100.0, 105.0, 110.0].iter().collect();
+/// assert_eq!(s.to_string(), "n = 5, mean = 100.00, std dev = 7.91");
+/// assert_eq!(format!("{:#.1}", s), "100.0 ± 7.9 (n = 5)");
+/// ```
+#[cfg(feature = "std")]
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prec = f.precision().unwrap_or(2);
+        if f.alternate() {
+            write!(f, "{:.*} ± {:.*} (n = {})", prec, self.mean, prec, self.std_dev(), self.n)
+        } else {
+            write!(
+                f,
+                "n = {}, mean = {:.*}, std dev = {:.*}",
+                self.n,
+                prec,
+                self.mean,
+                prec,
+                self.std_dev()
+            )
         }
-        let variance = s / (n - 1.0); // Bessel's correction
-        Summary { n, mean, variance }
     }
 }
 
 impl Summary {
+    /// A summary of no measurements.
+    pub(crate) const EMPTY: Summary = Summary {
+        n: 0.0,
+        mean: 0.0,
+        variance: 0.0,
+        m3: 0.0,
+        m4: 0.0,
+        min: f64::INFINITY,
+        max: f64::NEG_INFINITY,
+    };
+
+    /// Create a summary from aggregate statistics, such as those exported by a metrics system,
+    /// returning an [Error] if there are fewer than two measurements, the mean is not finite, or
+    /// the variance is not a finite, non-negative number.
+    ///
+    /// The higher moments and range of the measurements are unknown, so they are NaN.
+    ///
+    /// ```
+    /// use nanostat::{Confidence, Summary};
+    ///
+    /// let a = Summary::from_parts(20.0, 105.2, 16.0)?;
+    /// let b = Summary::from_parts(20.0, 99.7, 12.25)?;
+    /// assert!(a.compare(&b, Confidence::P95).is_significant());
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    pub fn from_parts(n: f64, mean: f64, variance: f64) -> Result<Summary, Error> {
+        if !n.is_finite() || !mean.is_finite() {
+            return Err(Error::NonFinite);
+        }
+        if n < 2.0 {
+            return Err(Error::InsufficientSamples);
+        }
+        if !(variance.is_finite() && variance >= 0.0) {
+            return Err(Error::InvalidVariance(variance));
+        }
+        Ok(Summary { n, mean, variance, m3: f64::NAN, m4: f64::NAN, min: f64::NAN, max: f64::NAN })
+    }
+
+    /// Summarize the given measurements using their trimmed mean, which discards the given
+    /// proportion of the smallest and largest measurements, making it robust to outliers. The
+    /// proportion must be in the range `[0, 0.5)`.
+    ///
+    /// The variance and size of the summary are adjusted using the winsorized variance of the
+    /// measurements, so that comparing two trimmed summaries with [Summary::compare] performs
+    /// Yuen's test for the difference between trimmed means.
+    ///
+    /// ```
+    /// use nanostat::{Confidence, Summary};
+    ///
+    /// let a = Summary::trimmed(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0], 0.1)?;
+    /// let b = Summary::trimmed(&[3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, -50.0], 0.1)?;
+    /// assert!(!a.compare(&b, Confidence::P95).is_significant());
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    pub fn trimmed(values: &[f64], proportion: f64) -> Result<Summary, Error> {
+        let (sorted, g) = trim(values, proportion)?;
+        let n = sorted.len() as f64;
+        let h = n - 2.0 * g as f64;
+
+        // Calculate the mean of the measurements which remain after trimming.
+        let mean = sorted[g..sorted.len() - g].iter().sum::<f64>() / h;
+
+        // Scale the winsorized variance so that variance / n is the squared standard error of the
+        // trimmed mean and n - 1 is its degrees of freedom.
+        let winsorized = winsorize(&sorted, g);
+        let variance = (n - 1.0) * winsorized.variance / (h - 1.0);
+
+        // Describe the shape of the distribution using the winsorized moments, and its range using
+        // the measurements which remain.
+        Ok(Summary { n: h, mean, variance, ..winsorized })
+    }
+
+    /// Summarize the given measurements after winsorizing them, which replaces the given
+    /// proportion of the smallest and largest measurements with the smallest and largest of those
+    /// which remain. The proportion must be in the range `[0, 0.5)`.
+    ///
+    /// ```
+    /// use nanostat::Summary;
+    ///
+    /// let s = Summary::winsorized(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0], 0.1)?;
+    /// assert_eq!(s.mean, 5.5);
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    pub fn winsorized(values: &[f64], proportion: f64) -> Result<Summary, Error> {
+        let (sorted, g) = trim(values, proportion)?;
+        Ok(winsorize(&sorted, g))
+    }
+
+    /// Summarize the given measurements, handling any NaNs and infinities according to the given
+    /// policy rather than letting them poison the mean and variance. Returns the summary and the
+    /// number of measurements which were counted and discarded, which is only non-zero for
+    /// [NonFinitePolicy::Count].
+    ///
+    /// ```
+    /// use nanostat::{NonFinitePolicy, Summary};
+    ///
+    /// let timings = [10.0, f64::NAN, 12.0, f64::INFINITY, 11.0];
+    /// let (s, skipped) = Summary::screened(timings, NonFinitePolicy::Count)?;
+    /// assert_eq!((s.n, s.mean, skipped), (3.0, 11.0, 2));
+    /// assert!(Summary::screened(timings, NonFinitePolicy::Strict).is_err());
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    pub fn screened<T: IntoIterator<Item = f64>>(
+        values: T,
+        policy: NonFinitePolicy,
+    ) -> Result<(Summary, usize), Error> {
+        let (mut skipped, mut strict) = (0, false);
+        let summary = values
+            .into_iter()
+            .filter(|x| {
+                if !x.is_finite() {
+                    match policy {
+                        NonFinitePolicy::Strict => strict = true,
+                        NonFinitePolicy::Skip => {}
+                        NonFinitePolicy::Count => skipped += 1,
+                    }
+                }
+                x.is_finite()
+            })
+            .collect();
+        if strict {
+            return Err(Error::NonFinite);
+        }
+        Ok((summary, skipped))
+    }
+
+    /// Summarize the given series of measurements, which may be autocorrelated, e.g. successive
+    /// iterations of a benchmark. The size of the summary is the series' effective sample size
+    /// (see [correlation::effective_sample_size]), so comparisons using it account for the
+    /// reduced information in correlated measurements rather than overstating their significance.
+    ///
+    /// ```
+    /// use nanostat::correlation::autocorrelation;
+    /// use nanostat::Summary;
+    ///
+    /// let series = [1.0, 2.0, 3.0, 2.0, 1.0, 2.0, 3.0, 4.0, 5.0, 4.0, 3.0, 4.0, 5.0, 6.0];
+    /// let s = Summary::autocorrelated(&series)?;
+    /// assert!(s.n < 14.0);
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn autocorrelated(values: &[f64]) -> Result<Summary, Error> {
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err(Error::NonFinite);
+        }
+        let summary: Summary = values.iter().collect();
+        Ok(Summary { n: correlation::effective_sample_size(values), ..summary })
+    }
+
+    /// Summarize the per-pair differences `a[i] - b[i]` of two paired data sets, e.g. the same
+    /// workloads run before and after a change, returning an [Error] if the data sets have
+    /// different lengths or contain NaNs or infinities. See [compare_paired] for a paired t-test.
+    ///
+    /// ```
+    /// use nanostat::Summary;
+    ///
+    /// let d = Summary::of_paired_differences(&[10.0, 12.0, 14.0], &[9.0, 10.5, 13.5])?;
+    /// assert_eq!(d.mean, 1.0);
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    pub fn of_paired_differences(a: &[f64], b: &[f64]) -> Result<Summary, Error> {
+        if a.len() != b.len() {
+            return Err(Error::MismatchedLengths);
+        }
+        if a.iter().chain(b.iter()).any(|v| !v.is_finite()) {
+            return Err(Error::NonFinite);
+        }
+        Ok(a.iter().zip(b.iter()).map(|(x, y)| x - y).collect())
+    }
+
+    /// Summarize the given slice of measurements. This is equivalent to collecting them into a
+    /// summary, but uses a two-pass algorithm with independent accumulator lanes, which the compiler
+    /// can vectorize, making it several times faster for large slices.
+    ///
+    /// ```
+    /// use nanostat::Summary;
+    ///
+    /// let values: Vec<f64> = (0..1_000).map(f64::from).collect();
+    /// let s = Summary::of(&values);
+    /// assert_eq!(s.mean, 499.5);
+    /// ```
+    #[must_use]
+    pub fn of(values: &[f64]) -> Summary {
+        // Fall back to the scalar path for slices too short to fill the lanes.
+        if values.len() < LANES {
+            return values.iter().collect();
+        }
+        let n = values.len() as f64;
+
+        // Calculate the mean.
+        let [mean] = sum_lanes(values, |x| [x]);
+        let mean = mean / n;
+
+        // Calculate the sums of powers of the deviations from the mean, correcting the sum of squares
+        // by the sum of deviations to cancel out the rounding error in the mean.
+        let [sum, s2, s3, s4] = sum_lanes(values, |x| {
+            let d = x - mean;
+            [d, d * d, d * d * d, d * d * d * d]
+        });
+        let variance = (s2 - sum * sum / n) / (n - 1.0);
+
+        // Find the range of the measurements.
+        let (min, max) = values
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &x| (min.min(x), max.max(x)));
+
+        Summary { n, mean: mean + sum / n, variance, m3: s3 / n, m4: s4 / n, min, max }
+    }
+
+    /// Summarize the given slice of measurements in parallel, by summarizing chunks of it on
+    /// Rayon's thread pool and merging the results. This is only faster than [Summary::of] for
+    /// very large slices.
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn par_of(values: &[f64]) -> Summary {
+        use rayon::prelude::*;
+
+        const CHUNK: usize = 1 << 16;
+        if values.len() <= CHUNK {
+            return Summary::of(values);
+        }
+        values.par_chunks(CHUNK).map(Summary::of).reduce(|| Summary::EMPTY, |a, b| a.merge(&b))
+    }
+
     /// The standard deviation of the sample.
+    #[cfg(feature = "std")]
     #[must_use]
     pub fn std_dev(&self) -> f64 {
         self.variance.sqrt()
     }
 
     /// The standard error of the sample.
+    #[cfg(feature = "std")]
     #[must_use]
     pub fn std_err(&self) -> f64 {
         self.std_dev() / self.n.sqrt()
     }
 
-    /// Calculate the statistical difference between the two summaries using a two-tailed Welch's
-    /// t-test. The confidence level must be in the range `(0, 100)`.
+    /// The coefficient of variation of the sample: its standard deviation as a proportion of the
+    /// magnitude of its mean. A value of 0.05 means the measurements typically vary by 5%.
+    #[cfg(feature = "std")]
     #[must_use]
-    pub fn compare(&self, other: &Summary, confidence: f64) -> Difference {
-        assert!(0.0 < confidence && confidence < 100.0, "confidence must be (0,100)");
+    pub fn coefficient_of_variation(&self) -> f64 {
+        self.std_dev() / self.mean.abs()
+    }
 
-        let (a, b) = (self, other);
+    /// The relative standard error of the sample: its standard error as a proportion of the
+    /// magnitude of its mean.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn relative_std_err(&self) -> f64 {
+        self.std_err() / self.mean.abs()
+    }
 
-        // Calculate the significance level.
-        let alpha = 1.0 - (confidence / 100.0);
+    /// The sample skewness of the data set, adjusted for bias (G1). Positive values indicate a
+    /// long right tail, e.g. from occasional slow measurements; a magnitude greater than 1 is a
+    /// sign that the data set is not normally distributed.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn skewness(&self) -> f64 {
+        let n = self.n;
+        let m2 = self.variance * (n - 1.0) / n;
+        let g1 = self.m3 / m2.powf(1.5);
+        g1 * (n * (n - 1.0)).sqrt() / (n - 2.0)
+    }
 
-        // Calculate the degrees of freedom.
-        let nu = (a.variance / a.n + b.variance / b.n).powf(2.0)
-            / ((a.variance).powf(2.0) / ((a.n).powf(2.0) * (a.n - 1.0))
-                + (b.variance).powf(2.0) / ((b.n).powf(2.0) * (b.n - 1.0)));
+    /// The sample excess kurtosis of the data set, adjusted for bias (G2). It is zero for a normal
+    /// distribution, and positive for distributions with heavier tails.
+    #[must_use]
+    pub fn kurtosis(&self) -> f64 {
+        let n = self.n;
+        let m2 = self.variance * (n - 1.0) / n;
+        let g2 = self.m4 / (m2 * m2) - 3.0;
+        ((n + 1.0) * g2 + 6.0) * (n - 1.0) / ((n - 2.0) * (n - 3.0))
+    }
 
-        // Create a Student's T distribution with location of 0, a scale of 1, and the same number
-        // of degrees of freedom as in the test.
-        let dist_st = StudentsT::new(0.0, 1.0, nu).unwrap();
+    /// The confidence interval for the mean of the population from which the sample was drawn,
+    /// using a Student's T distribution with `n - 1` degrees of freedom. The confidence level must
+    /// be in the range `(0, 100)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the confidence level is out of range or if the summary has fewer than two
+    /// measurements or non-finite values.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn confidence_interval(&self, confidence: Confidence) -> (f64, f64) {
+        let alpha = unwrap(confidence.checked_alpha());
+        unwrap(self.check());
 
-        // Calculate the hypothetical two-tailed t-value for the given significance level.
-        let t_hyp = dist_st.inverse_cdf(1.0 - (alpha / TAILS));
+        // Calculate the half-width of the interval using the two-tailed t-value.
+        let dist_st = StudentsT::new(0.0, 1.0, self.n - 1.0).unwrap();
+        let t = dist_st.inverse_cdf(1.0 - alpha / 2.0);
+        let h = t * self.std_err();
+        (self.mean - h, self.mean + h)
+    }
 
-        // Calculate the absolute difference between the means of the two samples.
-        let effect = (a.mean - b.mean).abs();
+    /// The prediction interval for a single future measurement from the population from which the
+    /// sample was drawn, assuming it is normally distributed. Unlike the confidence interval for the
+    /// mean, this accounts for the variation of individual measurements as well as the uncertainty
+    /// of the mean. The confidence level must be in the range `(0, 100)`.
+    ///
+    /// ```
+    /// use nanostat::{Confidence, Summary};
+    ///
+    /// let s: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+    /// let (low, high) = s.prediction_interval(Confidence::P95);
+    /// let (ci_low, ci_high) = s.confidence_interval(Confidence::P95);
+    /// assert!(low < ci_low && ci_high < high);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the confidence level is out of range or if the summary has fewer than two
+    /// measurements or non-finite values.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn prediction_interval(&self, confidence: Confidence) -> (f64, f64) {
+        let alpha = unwrap(confidence.checked_alpha());
+        unwrap(self.check());
 
-        // Calculate the standard error.
-        let std_err = (a.variance / a.n + b.variance / b.n).sqrt();
+        // Calculate the half-width of the interval using the two-tailed t-value and the standard
+        // error of the difference between a new measurement and the mean.
+        let dist_st = StudentsT::new(0.0, 1.0, self.n - 1.0).unwrap();
+        let t = dist_st.inverse_cdf(1.0 - alpha / 2.0);
+        let h = t * self.std_dev() * (1.0 + 1.0 / self.n).sqrt();
+        (self.mean - h, self.mean + h)
+    }
 
-        // Calculate the experimental t-value.
-        let t_exp = effect / std_err;
+    /// The tolerance interval which contains the given proportion of the population from which the
+    /// sample was drawn, at the given confidence level, assuming it is normally distributed. For
+    /// example, a coverage of `0.95` and a confidence level of 99% gives the range in which 95% of
+    /// all measurements fall, with 99% confidence. The coverage must be in the range `(0, 1)` and
+    /// the confidence level must be in the range `(0, 100)`.
+    ///
+    /// The interval uses Howe's approximation of the tolerance factor, which is accurate to within
+    /// about 1% even for small samples. For data which isn't normally distributed, use
+    /// [quantiles::Quantiles::tolerance_interval] instead.
+    ///
+    /// ```
+    /// use nanostat::{Confidence, Summary};
+    ///
+    /// let s: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+    /// let (low, high) = s.tolerance_interval(0.9, Confidence::P95);
+    /// let (pi_low, pi_high) = s.prediction_interval(Confidence::P95);
+    /// assert!(low < pi_low && pi_high < high);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the coverage or confidence level is out of range or if the summary has fewer than
+    /// two measurements or non-finite values.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn tolerance_interval(&self, coverage: f64, confidence: Confidence) -> (f64, f64) {
+        let alpha = unwrap(confidence.checked_alpha());
+        if !(coverage > 0.0 && coverage < 1.0) {
+            panic!("{}", Error::InvalidCoverage(coverage));
+        }
+        unwrap(self.check());
 
-        // Calculate the p-value given the experimental t-value.
-        let p_value = dist_st.cdf(-t_exp) * TAILS;
+        // Calculate the tolerance factor from the two-tailed z-value for the coverage and the lower
+        // tail of the chi-squared distribution of the sample variance.
+        let z = Normal::new(0.0, 1.0).unwrap().inverse_cdf((1.0 + coverage) / 2.0);
+        let chi = quantile(&ChiSquared::new(self.n - 1.0).unwrap(), alpha);
+        let k = z * ((self.n - 1.0) * (1.0 + 1.0 / self.n) / chi).sqrt();
+        let h = k * self.std_dev();
+        (self.mean - h, self.mean + h)
+    }
 
-        // Calculate the critical value.
-        let critical_value = t_hyp * std_err;
+    /// Add a measurement with the given non-negative weight to the summary, as if it had been
+    /// measured `weight` times. This allows data which has already been aggregated, e.g. into the
+    /// buckets of a histogram, to be summarized without expanding it. Measurements with a weight
+    /// of zero are ignored.
+    ///
+    /// Only frequency weights are supported: the summary's size is the sum of the weights, and its
+    /// variance is corrected for that many measurements. Reliability weights, e.g. the inverse
+    /// variances of the measurements, give the correct mean but a biased variance.
+    ///
+    /// ```
+    /// use nanostat::Summary;
+    ///
+    /// let mut s: Summary = [1.0, 2.0].iter().collect();
+    /// s.push_weighted(4.0, 2.0);
+    /// let t: Summary = [1.0, 2.0, 4.0, 4.0].iter().collect();
+    /// assert_eq!((s.n, s.mean), (t.n, t.mean));
+    /// assert!((s.variance - t.variance).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weight is negative or not finite.
+    pub fn push_weighted(&mut self, value: f64, weight: f64) {
+        if let Err(e) = self.try_push_weighted(value, weight) {
+            panic!("{}", e);
+        }
+    }
 
-        // Calculate the standard deviation using mean variance.
-        let std_dev = ((a.variance + b.variance) / 2.0).sqrt();
+    /// Add a measurement with the given non-negative weight to the summary; see
+    /// [Summary::push_weighted]. Returns [Error::InvalidWeight] if the weight is negative or not
+    /// finite, leaving the summary unchanged.
+    pub fn try_push_weighted(&mut self, value: f64, weight: f64) -> Result<(), Error> {
+        if !(weight.is_finite() && weight >= 0.0) {
+            return Err(Error::InvalidWeight(weight));
+        } else if weight == 0.0 {
+            return Ok(());
+        }
 
-        // Calculate Cohen's d for the effect size.
-        let effect_size = effect / std_dev;
+        let point = Summary {
+            n: weight,
+            mean: value,
+            variance: 0.0,
+            m3: 0.0,
+            m4: 0.0,
+            min: value,
+            max: value,
+        };
+        *self = self.merge(&point);
+        Ok(())
+    }
 
-        // Calculate the statistical power.
-        let z = effect / (std_dev * (1.0 / a.n + 1.0 / b.n).sqrt());
-        let dist_norm = Normal::new(0.0, 1.0).unwrap();
-        let za = dist_norm.inverse_cdf(1.0 - alpha / TAILS);
-        let beta = dist_norm.cdf(z - za) - dist_norm.cdf(-z - za);
+    /// Combine the two summaries into a summary of all their measurements, using Chan et al.'s
+    /// parallel algorithm. This allows data sets to be summarized in pieces, e.g. on different
+    /// threads or machines.
+    ///
+    /// ```
+    /// use nanostat::Summary;
+    ///
+    /// let a: Summary = [1.0, 2.0].iter().collect();
+    /// let b: Summary = [3.0, 4.0, 5.0].iter().collect();
+    /// let s = a.merge(&b);
+    /// assert_eq!((s.n, s.mean, s.variance), (5.0, 3.0, 2.5));
+    /// ```
+    #[must_use]
+    pub fn merge(&self, other: &Summary) -> Summary {
+        let (a, b) = (self, other);
+        if a.n == 0.0 {
+            return *b;
+        } else if b.n == 0.0 {
+            return *a;
+        }
 
-        Difference { effect, effect_size, critical_value, p_value, alpha, beta }
+        // Combine the sums of powers of the deviations from each summary's mean.
+        let n = a.n + b.n;
+        let delta = b.mean - a.mean;
+        let ((s2_a, s3_a, s4_a), (s2_b, s3_b, s4_b)) = (a.sums(), b.sums());
+        let s2 = s2_a + s2_b + delta * delta * a.n * b.n / n;
+        let s3 = s3_a
+            + s3_b
+            + delta * delta * delta * a.n * b.n * (a.n - b.n) / (n * n)
+            + 3.0 * delta * (a.n * s2_b - b.n * s2_a) / n;
+        let s4 = s4_a
+            + s4_b
+            + delta * delta * delta * delta * a.n * b.n * (a.n * a.n - a.n * b.n + b.n * b.n)
+                / (n * n * n)
+            + 6.0 * delta * delta * (a.n * a.n * s2_b + b.n * b.n * s2_a) / (n * n)
+            + 4.0 * delta * (a.n * s3_b - b.n * s3_a) / n;
+        Summary {
+            n,
+            mean: a.mean + delta * b.n / n,
+            variance: s2 / (n - 1.0),
+            m3: s3 / n,
+            m4: s4 / n,
+            min: a.min.min(b.min),
+            max: a.max.max(b.max),
+        }
     }
-}
 
-/// The number of distribution tails used to determine significance. In this case, we always use a
-/// two-tailed test because our null hypothesis is that the samples are not different.
-const TAILS: f64 = 2.0;
+    /// The sums of the second, third, and fourth powers of the deviations from the mean.
+    fn sums(&self) -> (f64, f64, f64) {
+        if self.n == 0.0 {
+            return (0.0, 0.0, 0.0);
+        }
+        let s2 = if self.n > 1.0 { self.variance * (self.n - 1.0) } else { 0.0 };
+        (s2, self.m3 * self.n, self.m4 * self.n)
+    }
 
-#[cfg(test)]
-mod test {
-    use approx::assert_relative_eq;
+    /// Check that the summary can be used to estimate a variance and is free of NaNs and
+    /// infinities.
+    #[cfg(feature = "std")]
+    fn check(&self) -> Result<(), Error> {
+        if self.n < 2.0 {
+            return Err(Error::InsufficientSamples);
+        }
+        if !(self.n.is_finite() && self.mean.is_finite() && self.variance.is_finite()) {
+            return Err(Error::NonFinite);
+        }
+        Ok(())
+    }
 
-    use super::*;
+    /// Calculate the statistical difference between the two summaries using a two-tailed Welch's
+    /// t-test. The confidence level must be in the range `(0, 100)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the confidence level is out of range or if the summaries cannot be compared. Use
+    /// [Summary::try_compare] to handle those cases.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn compare(&self, other: &Summary, confidence: Confidence) -> Difference {
+        self.compare_with(other, Options { confidence, ..Options::default() })
+    }
 
-    #[test]
-    fn summarize_odd() {
-        let s: Summary = vec![1.0, 2.0, 3.0].iter().collect();
+    /// Calculate the statistical difference between the two summaries using a two-tailed Welch's
+    /// t-test, returning an [Error] if the confidence level is out of range or if either summary
+    /// is unsuitable for comparison.
+    #[cfg(feature = "std")]
+    pub fn try_compare(
+        &self,
+        other: &Summary,
+        confidence: Confidence,
+    ) -> Result<Difference, Error> {
+        self.try_compare_with(other, Options { confidence, ..Options::default() })
+    }
 
-        assert_relative_eq!(s.n, 3.0);
-        assert_relative_eq!(s.mean, 2.0);
-        assert_relative_eq!(s.variance, 1.0);
+    /// Compare each of the given summaries with this one, e.g. several experiments with a control,
+    /// using Welch's t-tests with the given options, and adjust the p-values of the differences
+    /// using the given correction for multiple comparisons, if any. The confidence level must be in
+    /// the range `(0, 100)`.
+    ///
+    /// ```
+    /// use nanostat::corrections::Correction;
+    /// use nanostat::{Confidence, Options, Summary};
+    ///
+    /// let control: Summary = [10.0, 11.0, 12.0, 11.0, 10.0].iter().collect();
+    /// let a: Summary = [11.0, 12.0, 13.0, 12.0, 11.0].iter().collect();
+    /// let b: Summary = [10.0, 11.0, 12.0, 11.5, 10.5].iter().collect();
+    /// let options = Options { confidence: Confidence::P90, ..Options::default() };
+    /// let diffs = control.compare_many(&[a, b], options, Some(Correction::Bonferroni));
+    /// assert!(!diffs[0].is_significant());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the confidence level is out of range or if any of the summaries cannot be
+    /// compared. Use [Summary::try_compare_many] to handle those cases.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn compare_many(
+        &self,
+        others: &[Summary],
+        options: Options,
+        correction: Option<corrections::Correction>,
+    ) -> Vec<Difference> {
+        unwrap(self.try_compare_many(others, options, correction))
     }
 
-    #[test]
-    fn summarize_even() {
-        let s: Summary = vec![1.0, 2.0, 3.0, 4.0].iter().collect();
+    /// Compare each of the given summaries with this one, as [Summary::compare_many] does,
+    /// returning an [Error] if the confidence level is out of range or if any of the summaries is
+    /// unsuitable for comparison.
+    #[cfg(feature = "std")]
+    pub fn try_compare_many(
+        &self,
+        others: &[Summary],
+        options: Options,
+        correction: Option<corrections::Correction>,
+    ) -> Result<Vec<Difference>, Error> {
+        let diffs = others
+            .iter()
+            .map(|o| self.try_compare_with(o, options))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(match correction {
+            Some(correction) => correction.adjust(&diffs),
+            None => diffs,
+        })
+    }
 
-        assert_relative_eq!(s.n, 4.0);
-        assert_relative_eq!(s.mean, 2.5);
+    /// Calculate the statistical difference between the two summaries using a Welch's t-test with
+    /// the given options. The confidence level must be in the range `(0, 100)`.
+    ///
+    /// ```
+    /// use nanostat::{Alternative, Confidence, Options, Summary};
+    ///
+    /// let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+    /// let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+    /// let options = Options {
+    ///     confidence: Confidence::P95,
+    ///     alternative: Alternative::Greater,
+    ///     ..Options::default()
+    /// };
+    /// assert!(a.compare_with(&b, options).is_significant());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the confidence level is out of range or if the summaries cannot be compared. Use
+    /// [Summary::try_compare_with] to handle those cases.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn compare_with(&self, other: &Summary, options: Options) -> Difference {
+        unwrap(self.try_compare_with(other, options))
+    }
+
+    /// Calculate the statistical difference between the two summaries using a Welch's t-test with
+    /// the given options, returning an [Error] if the confidence level is out of range or if either
+    /// summary is unsuitable for comparison.
+    #[cfg(feature = "std")]
+    pub fn try_compare_with(&self, other: &Summary, options: Options) -> Result<Difference, Error> {
+        let Options { confidence, alternative, effect_size: standardizer, spread } = options;
+
+        // Calculate the significance level.
+        let alpha = confidence.checked_alpha()?;
+
+        let (a, b) = (self, other);
+
+        // Calculate the standard error and the distribution of the t-statistic.
+        let (std_err, dist_st) = a.welch(b)?;
+
+        // Calculate the hypothetical t-value for the given significance level.
+        let tails = alternative.tails();
+        let t_hyp = dist_st.inverse_cdf(1.0 - (alpha / tails));
+
+        // Calculate the absolute difference between the means of the two samples.
+        let effect = (a.mean - b.mean).abs();
+
+        // Calculate the experimental t-value.
+        let t_exp = effect / std_err;
+
+        // Calculate the p-value given the experimental t-value and the alternative hypothesis.
+        let sign = (b.mean - a.mean).signum();
+        let p_value = match alternative {
+            Alternative::TwoSided => dist_st.cdf(-t_exp) * tails,
+            Alternative::Less => dist_st.cdf(sign * t_exp),
+            Alternative::Greater => dist_st.cdf(-sign * t_exp),
+        };
+
+        // Calculate the critical value.
+        let critical_value = t_hyp * std_err;
+
+        // Calculate the confidence interval for the difference in means, which is unbounded on
+        // one side for one-sided alternatives.
+        let delta = b.mean - a.mean;
+        let (ci_low, ci_high) = match alternative {
+            Alternative::TwoSided => (delta - critical_value, delta + critical_value),
+            Alternative::Less => (f64::NEG_INFINITY, delta + critical_value),
+            Alternative::Greater => (delta - critical_value, f64::INFINITY),
+        };
+
+        // Calculate the confidence interval for the relative difference in means. A higher ratio
+        // means a lower second mean if the first mean is negative.
+        let relative_delta = 100.0 * delta / a.mean;
+        let (low, high) = fieller(a.mean, b.mean, a.variance / a.n, b.variance / b.n, 0.0, t_hyp);
+        let (relative_ci_low, relative_ci_high) = match (alternative, a.mean < 0.0) {
+            (Alternative::TwoSided, _) => (low, high),
+            (Alternative::Less, false) | (Alternative::Greater, true) => (f64::NEG_INFINITY, high),
+            (Alternative::Less, true) | (Alternative::Greater, false) => (low, f64::INFINITY),
+        };
+
+        // Calculate the standard deviation using mean variance, and the pooled variance weighted
+        // by each sample's degrees of freedom.
+        let std_dev = ((a.variance + b.variance) / 2.0).sqrt();
+        let nu = a.n + b.n - 2.0;
+        let pooled = ((a.n - 1.0) * a.variance + (b.n - 1.0) * b.variance) / nu;
+
+        // Calculate the effect size, standardized by the mean variance (Cohen's d), the pooled
+        // variance, or the first sample's variance (Glass's delta).
+        let (standardizer, df) = match standardizer {
+            EffectSize::CohensD => (std_dev, nu),
+            EffectSize::PooledCohensD => (pooled.sqrt(), nu),
+            EffectSize::GlassDelta => (a.std_dev(), a.n - 1.0),
+        };
+        let effect_size = effect / standardizer;
+
+        // Calculate Hedges' g by applying the small-sample correction to the effect size.
+        let hedges_g = effect_size * hedges_correction(df);
+
+        // Calculate the confidence interval for the effect size from the confidence interval for
+        // the noncentrality parameter of the pooled-variance t-statistic, which is proportional to
+        // the pooled effect size, rescaled to the chosen standardizer.
+        let t_pooled = delta / (pooled * (1.0 / a.n + 1.0 / b.n)).sqrt();
+        let (low, high) = noncentrality_interval(t_pooled.abs(), nu, alpha);
+        let scale = (1.0 / a.n + 1.0 / b.n).sqrt() * pooled.sqrt() / standardizer;
+        let (effect_size_ci_low, effect_size_ci_high) = (low * scale, high * scale);
+
+        // Calculate the probability of superiority from the distribution of the difference between
+        // random measurements from each sample.
+        let dist_norm = Normal::new(0.0, 1.0).unwrap();
+        let probability_of_superiority = dist_norm.cdf(delta / (a.variance + b.variance).sqrt());
+
+        // Calculate the Bayes factor using the pooled-variance t-statistic.
+        let bayes_factor = jzs_bayes_factor(t_pooled, a.n * b.n / (a.n + b.n), nu);
+
+        // Calculate the statistical power.
+        let z = effect / (std_dev * (1.0 / a.n + 1.0 / b.n).sqrt());
+        let za = dist_norm.inverse_cdf(1.0 - alpha / tails);
+        let beta = match alternative {
+            Alternative::TwoSided => dist_norm.cdf(z - za) - dist_norm.cdf(-z - za),
+            Alternative::Less => dist_norm.cdf(-sign * z - za),
+            Alternative::Greater => dist_norm.cdf(sign * z - za),
+        };
+
+        // Compare the variances, if requested and if the first sample's variance is non-zero.
+        let spread = match spread.then(|| a.variance_ratio(b, confidence)) {
+            None | Some(Err(Error::ZeroVariance)) => None,
+            Some(vr) => Some(vr?),
+        };
+
+        Ok(Difference {
+            effect,
+            delta,
+            effect_size,
+            hedges_g,
+            probability_of_superiority,
+            effect_size_ci_low,
+            effect_size_ci_high,
+            critical_value,
+            t_statistic: delta / std_err,
+            df: dist_st.freedom(),
+            std_err,
+            relative_delta,
+            relative_ci_low,
+            relative_ci_high,
+            ci_low,
+            ci_high,
+            p_value,
+            alpha,
+            confidence,
+            bayes_factor,
+            beta,
+            spread,
+        })
+    }
+
+    /// Test whether the two summaries' means are equivalent to within the given margin using two
+    /// one-sided Welch's t-tests (TOST). The confidence level must be in the range `(0, 100)`.
+    ///
+    /// Unlike [Summary::compare], which can only fail to find a difference, this can positively
+    /// establish that any difference is smaller than the margin.
+    #[cfg(feature = "std")]
+    pub fn equivalence(
+        &self,
+        other: &Summary,
+        margin: Margin,
+        confidence: Confidence,
+    ) -> Result<Equivalence, Error> {
+        // Calculate the significance level.
+        let alpha = confidence.checked_alpha()?;
+
+        // Calculate the absolute equivalence margin.
+        let margin = match margin {
+            Margin::Absolute(m) => m,
+            Margin::Relative(p) => (self.mean * p / 100.0).abs(),
+        };
+        if !(margin.is_finite() && margin > 0.0) {
+            return Err(Error::InvalidMargin(margin));
+        }
+
+        let (a, b) = (self, other);
+
+        // Calculate the standard error and the distribution of the t-statistic.
+        let (std_err, dist_st) = a.welch(b)?;
+
+        // Test whether the difference is greater than the lower bound of the margin.
+        let delta = b.mean - a.mean;
+        let p_lower = 1.0 - dist_st.cdf((delta + margin) / std_err);
+
+        // Test whether the difference is less than the upper bound of the margin.
+        let p_upper = dist_st.cdf((delta - margin) / std_err);
+
+        Ok(Equivalence { margin, p_lower, p_upper, p_value: p_lower.max(p_upper), alpha })
+    }
+
+    /// Calculate the ratio of the other summary's mean to this one's, e.g. the slowdown factor of
+    /// an experiment relative to a control, and its confidence interval using Fieller's theorem.
+    /// The confidence level must be in the range `(0, 100)`.
+    ///
+    /// Unlike the relative difference of [Summary::compare], Fieller's interval accounts for the
+    /// uncertainty of this summary's mean as the denominator, and so is asymmetric. If this
+    /// summary's mean is not significantly different from zero, the interval is unbounded, and if
+    /// it is exactly zero, the ratio is undefined and an error is returned. The p-value is that of
+    /// Welch's t-test for a difference between the means.
+    ///
+    /// ```
+    /// use nanostat::{Confidence, Summary};
+    ///
+    /// let control: Summary = [10.0, 12.0, 11.0, 13.0, 12.5].iter().collect();
+    /// let experiment: Summary = [12.0, 14.0, 13.5, 15.0, 14.2].iter().collect();
+    /// let r = control.ratio(&experiment, Confidence::P95)?;
+    /// println!("{:.2}x slower [{:.2}, {:.2}]", r.ratio, r.ci_low, r.ci_high);
+    /// assert!(r.is_significant());
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn ratio(&self, other: &Summary, confidence: Confidence) -> Result<Ratio, Error> {
+        // Calculate the significance level.
+        let alpha = confidence.checked_alpha()?;
+
+        let (a, b) = (self, other);
+
+        // Calculate the p-value of the null hypothesis that the means are equal.
+        let (std_err, dist_st) = a.welch(b)?;
+        let p_value = dist_st.cdf(-((b.mean - a.mean) / std_err).abs()) * 2.0;
+
+        // Calculate the degrees of freedom of b - ρa, where ρ is the estimated ratio.
+        let ratio = b.mean / a.mean;
+        let (va, vb) = (a.variance / a.n, b.variance / b.n);
+        let nu = (vb + ratio.powf(2.0) * va).powf(2.0)
+            / (vb.powf(2.0) / (b.n - 1.0) + ratio.powf(4.0) * va.powf(2.0) / (a.n - 1.0));
+        let dist_st = StudentsT::new(0.0, 1.0, nu).map_err(|_| Error::NonFinite)?;
+        let t = dist_st.inverse_cdf(1.0 - alpha / 2.0);
+
+        // Find the ratios for which b - ρa is not significantly different from zero, which are the
+        // roots of a quadratic in ρ. If its leading coefficient isn't positive, they're unbounded.
+        let q = a.mean.powf(2.0) - t * t * va;
+        let (ci_low, ci_high) = if q > 0.0 {
+            let ab = a.mean * b.mean;
+            let h = (ab.powf(2.0) - q * (b.mean.powf(2.0) - t * t * vb)).sqrt();
+            ((ab - h) / q, (ab + h) / q)
+        } else {
+            (f64::NEG_INFINITY, f64::INFINITY)
+        };
+
+        Ok(Ratio { ratio, ci_low, ci_high, p_value, alpha })
+    }
+
+    /// Calculate the ratio of the other summary's variance to this one's and its confidence
+    /// interval using an F-test. The confidence level must be in the range `(0, 100)`.
+    ///
+    /// ```
+    /// use nanostat::{Confidence, Summary};
+    ///
+    /// let before: Summary = [10.0, 14.0, 9.0, 15.0, 11.0, 13.0, 8.0, 16.0].iter().collect();
+    /// let after: Summary = [12.0, 12.5, 11.5, 12.0, 12.5, 11.0, 12.0, 13.0].iter().collect();
+    /// let vr = before.variance_ratio(&after, Confidence::P95)?;
+    /// assert!(vr.ci_high < 0.5); // the variance was at least halved
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn variance_ratio(
+        &self,
+        other: &Summary,
+        confidence: Confidence,
+    ) -> Result<VarianceRatio, Error> {
+        // Calculate the significance level.
+        let alpha = confidence.checked_alpha()?;
+
+        let (a, b) = (self, other);
+
+        // Check that both samples are usable and that the ratio is defined.
+        a.check()?;
+        b.check()?;
+        if a.variance == 0.0 {
+            return Err(Error::ZeroVariance);
+        }
+
+        // Calculate the ratio, which is F-distributed under the null hypothesis.
+        let ratio = b.variance / a.variance;
+        let (df_num, df_den) = (b.n - 1.0, a.n - 1.0);
+        let dist_f = FisherSnedecor::new(df_num, df_den).map_err(|_| Error::NonFinite)?;
+
+        // Calculate the confidence interval for the ratio.
+        let ci_low = ratio / f_quantile(&dist_f, 1.0 - alpha / 2.0);
+        let ci_high = ratio / f_quantile(&dist_f, alpha / 2.0);
+
+        // Calculate the two-tailed p-value given the ratio.
+        let p = dist_f.cdf(ratio);
+        let p_value = (2.0 * p.min(1.0 - p)).min(1.0);
+
+        Ok(VarianceRatio { ratio, df_num, df_den, ci_low, ci_high, p_value, alpha })
+    }
+
+    /// Calculate the standard error of the difference between the two summaries' means and the
+    /// distribution of the t-statistic, using the Welch-Satterthwaite degrees of freedom.
+    #[cfg(feature = "std")]
+    fn welch(&self, other: &Summary) -> Result<(f64, StudentsT), Error> {
+        let (a, b) = (self, other);
+
+        // Check that both samples are usable.
+        a.check()?;
+        b.check()?;
+
+        // Check that the degrees of freedom are defined.
+        if a.variance == 0.0 && b.variance == 0.0 {
+            return Err(Error::ZeroVariance);
+        }
+
+        // Calculate the degrees of freedom.
+        let nu = (a.variance / a.n + b.variance / b.n).powf(2.0)
+            / ((a.variance).powf(2.0) / ((a.n).powf(2.0) * (a.n - 1.0))
+                + (b.variance).powf(2.0) / ((b.n).powf(2.0) * (b.n - 1.0)));
+
+        // Create a Student's T distribution with location of 0, a scale of 1, and the same number
+        // of degrees of freedom as in the test.
+        let dist_st = StudentsT::new(0.0, 1.0, nu).map_err(|_| Error::NonFinite)?;
+
+        // Calculate the standard error.
+        let std_err = (a.variance / a.n + b.variance / b.n).sqrt();
+
+        Ok((std_err, dist_st))
+    }
+}
+
+/// Calculate the statistical difference between two paired data sets using a two-tailed paired
+/// t-test on the per-pair differences `a[i] - b[i]`. Both data sets must have the same length. The
+/// confidence level must be in the range `(0, 100)`.
+///
+/// When measurements are naturally paired (e.g. the same workload run before and after a change
+/// on the same machines), this is considerably more powerful than [Summary::compare], since the
+/// variation between pairs is removed from the comparison.
+#[cfg(feature = "std")]
+pub fn compare_paired(a: &[f64], b: &[f64], confidence: Confidence) -> Result<Difference, Error> {
+    // Calculate the significance level.
+    let alpha = confidence.checked_alpha()?;
+
+    // Summarize the per-pair differences.
+    let d = Summary::of_paired_differences(a, b)?;
+    d.check()?;
+    if d.variance == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Create a Student's T distribution with n-1 degrees of freedom.
+    let dist_st = StudentsT::new(0.0, 1.0, d.n - 1.0).map_err(|_| Error::NonFinite)?;
+
+    // Calculate the hypothetical two-tailed t-value for the given significance level.
+    let tails = Alternative::TwoSided.tails();
+    let t_hyp = dist_st.inverse_cdf(1.0 - (alpha / tails));
+
+    // Calculate the absolute mean difference and its standard error.
+    let effect = d.mean.abs();
+    let std_err = d.std_err();
+
+    // Calculate the experimental t-value and the p-value given it.
+    let t_exp = effect / std_err;
+    let p_value = dist_st.cdf(-t_exp) * tails;
+
+    // Calculate the critical value.
+    let critical_value = t_hyp * std_err;
+
+    // Calculate the confidence interval for the difference in means, `b - a`.
+    let (ci_low, ci_high) = (-d.mean - critical_value, -d.mean + critical_value);
+
+    // Calculate the confidence interval for the relative difference in means, accounting for the
+    // covariance between the paired measurements.
+    let (sa, sb): (Summary, Summary) = (a.iter().collect(), b.iter().collect());
+    let (var_a, var_b) = (sa.variance / sa.n, sb.variance / sb.n);
+    let cov = (var_a + var_b - d.variance / d.n) / 2.0;
+    let relative_delta = -100.0 * d.mean / sa.mean;
+    let (relative_ci_low, relative_ci_high) = fieller(sa.mean, sb.mean, var_a, var_b, cov, t_hyp);
+
+    // Calculate Cohen's d for the differences (d_z) for the effect size.
+    let effect_size = effect / d.std_dev();
+
+    // Calculate Hedges' g by applying the small-sample correction to Cohen's d.
+    let hedges_g = effect_size * hedges_correction(d.n - 1.0);
+
+    // Calculate the confidence interval for Cohen's d from the confidence interval for the
+    // noncentrality parameter of the t-statistic.
+    let (low, high) = noncentrality_interval(t_exp, d.n - 1.0, alpha);
+    let (effect_size_ci_low, effect_size_ci_high) = (low / d.n.sqrt(), high / d.n.sqrt());
+
+    // Calculate the Bayes factor for the differences.
+    let bayes_factor = jzs_bayes_factor(t_exp, d.n, d.n - 1.0);
+
+    // Calculate the probability of superiority from the distribution of the differences.
+    let dist_norm = Normal::new(0.0, 1.0).unwrap();
+    let probability_of_superiority = dist_norm.cdf(-d.mean / d.std_dev());
+
+    // Calculate the statistical power.
+    let za = dist_norm.inverse_cdf(1.0 - alpha / tails);
+    let beta = dist_norm.cdf(t_exp - za) - dist_norm.cdf(-t_exp - za);
+
+    Ok(Difference {
+        effect,
+        delta: -d.mean,
+        effect_size,
+        hedges_g,
+        probability_of_superiority,
+        effect_size_ci_low,
+        effect_size_ci_high,
+        critical_value,
+        t_statistic: -d.mean / std_err,
+        df: d.n - 1.0,
+        std_err,
+        relative_delta,
+        relative_ci_low,
+        relative_ci_high,
+        ci_low,
+        ci_high,
+        p_value,
+        alpha,
+        confidence,
+        bayes_factor,
+        beta,
+        spread: None,
+    })
+}
+
+/// Calculate the statistical difference between two data sets using Yuen's test, a two-tailed
+/// Welch's t-test on their trimmed means and winsorized variances. The given proportion of the
+/// smallest and largest measurements of each data set is trimmed, and must be in the range `[0,
+/// 0.5)`. The confidence level must be in the range `(0, 100)`.
+///
+/// This is robust to heavy tails and outliers which would swamp [Summary::compare], at the cost of
+/// some power when the measurements are normally distributed. A proportion of `0.2` is typical.
+///
+/// ```
+/// use nanostat::{compare_trimmed, Confidence};
+///
+/// let a = [10.1, 10.3, 9.8, 10.0, 10.2, 9.9, 10.4, 10.1, 30.0, 9.7];
+/// let b = [11.0, 11.3, 10.8, 11.1, 10.9, 11.2, 11.4, 10.7, -5.0, 11.0];
+/// assert!(compare_trimmed(&a, &b, 0.2, Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+#[cfg(feature = "std")]
+pub fn compare_trimmed(
+    a: &[f64],
+    b: &[f64],
+    proportion: f64,
+    confidence: Confidence,
+) -> Result<Difference, Error> {
+    Summary::trimmed(a, proportion)?.try_compare(&Summary::trimmed(b, proportion)?, confidence)
+}
+
+/// The number of independent accumulators used by [Summary::of].
+const LANES: usize = 4;
+
+/// Sum each of the `K` terms given by the function of each value using [LANES] independent
+/// accumulators, which frees the additions from depending on each other and allows them to be
+/// vectorized.
+fn sum_lanes<const K: usize>(values: &[f64], f: impl Fn(f64) -> [f64; K]) -> [f64; K] {
+    let chunks = values.chunks_exact(LANES);
+    let mut sums = [0.0; K];
+    for &x in chunks.remainder() {
+        for (s, t) in sums.iter_mut().zip(f(x)) {
+            *s += t;
+        }
+    }
+    let mut acc = [[0.0; LANES]; K];
+    for chunk in chunks {
+        for (i, &x) in chunk.iter().enumerate() {
+            for (a, t) in acc.iter_mut().zip(f(x)) {
+                a[i] += t;
+            }
+        }
+    }
+    for (s, a) in sums.iter_mut().zip(acc) {
+        *s += a.iter().sum::<f64>();
+    }
+    sums
+}
+
+/// A running sum with Neumaier's compensation for the low-order bits lost to rounding.
+#[derive(Copy, Clone, Debug)]
+struct Neumaier {
+    sum: f64,
+    c: f64,
+}
+
+impl Neumaier {
+    const fn new(sum: f64) -> Neumaier {
+        Neumaier { sum, c: 0.0 }
+    }
+
+    fn add(&mut self, x: f64) {
+        let t = self.sum + x;
+        if self.sum.abs() >= x.abs() {
+            self.c += (self.sum - t) + x;
+        } else {
+            self.c += (x - t) + self.sum;
+        }
+        self.sum = t;
+    }
+
+    fn total(&self) -> f64 {
+        self.sum + self.c
+    }
+}
+
+/// Sort the given measurements and calculate how many to trim from each end.
+fn trim(values: &[f64], proportion: f64) -> Result<(Vec<f64>, usize), Error> {
+    if !(0.0..0.5).contains(&proportion) {
+        return Err(Error::InvalidProportion(proportion));
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let g = (proportion * sorted.len() as f64) as usize; // truncation is floor for positive values
+    Ok((sorted, g))
+}
+
+/// Summarize the given sorted measurements after replacing the `g` smallest and largest.
+fn winsorize(sorted: &[f64], g: usize) -> Summary {
+    if sorted.is_empty() {
+        return sorted.iter().collect();
+    }
+    let (low, high) = (sorted[g], sorted[sorted.len() - 1 - g]);
+    sorted.iter().map(|x| x.clamp(low, high)).collect()
+}
+
+/// Format a p-value to three decimal places, without a leading zero.
+#[cfg(feature = "std")]
+fn format_p(p: f64) -> String {
+    let p = format!("{:.3}", p);
+    p.trim_start_matches('0').to_string()
+}
+
+/// Calculate the approximate correction factor for the bias of Cohen's d with the given degrees of
+/// freedom.
+#[cfg(feature = "std")]
+fn hedges_correction(df: f64) -> f64 {
+    1.0 - 3.0 / (4.0 * df - 1.0)
+}
+
+/// Calculate the confidence interval for the percentage difference between two means, `100 * (b /
+/// a - 1)`, using Fieller's theorem, given the squared standard errors of the means, their
+/// covariance, and the critical t-value. The interval is unbounded if `a` is not significantly
+/// different from zero.
+#[cfg(feature = "std")]
+fn fieller(a: f64, b: f64, var_a: f64, var_b: f64, cov: f64, t: f64) -> (f64, f64) {
+    // Solve (b - ρa)^2 = t^2 (var_b - 2ρ cov + ρ^2 var_a) for the ratio ρ.
+    let t2 = t * t;
+    let qa = a * a - t2 * var_a;
+    if qa <= 0.0 {
+        return (f64::NEG_INFINITY, f64::INFINITY);
+    }
+    let qb = a * b - t2 * cov;
+    let qc = b * b - t2 * var_b;
+    let d = (qb * qb - qa * qc).max(0.0).sqrt();
+    (100.0 * ((qb - d) / qa - 1.0), 100.0 * ((qb + d) / qa - 1.0))
+}
+
+/// Calculate the JZS Bayes factor (Rouder et al., 2009) for a t-statistic with the given effective
+/// sample size and degrees of freedom, using a Cauchy prior with a scale of √2/2 on the
+/// standardized effect.
+#[cfg(feature = "std")]
+fn jzs_bayes_factor(t: f64, n: f64, df: f64) -> f64 {
+    const R: f64 = core::f64::consts::FRAC_1_SQRT_2;
+    const STEPS: usize = 6000;
+    const LOW: f64 = -15.0;
+    const HIGH: f64 = 45.0;
+
+    // The log of the integrand of the Bayes factor as a function of u = ln(g), where g is the
+    // variance of the effect given the prior: the likelihood ratio given g, plus the log density
+    // of g's inverse-gamma prior, plus the log of the Jacobian of the change of variables.
+    let null = (df + 1.0) / 2.0 * (t * t / df).ln_1p();
+    let scale = R.ln() - 0.5 * (2.0 * core::f64::consts::PI).ln();
+    let ln_f = |u: f64| {
+        let (g, ng) = (u.exp(), n * u.exp());
+        let likelihood =
+            null - 0.5 * ng.ln_1p() - (df + 1.0) / 2.0 * (t * t / ((1.0 + ng) * df)).ln_1p();
+        let prior = scale - 1.5 * u - R * R / (2.0 * g);
+        likelihood + prior + u
+    };
+
+    // Integrate using Simpson's rule, scaling the terms to avoid overflow.
+    let h = (HIGH - LOW) / STEPS as f64;
+    let terms: Vec<f64> = (0..=STEPS).map(|i| ln_f(LOW + h * i as f64)).collect();
+    let max = terms.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let sum: f64 = terms
+        .iter()
+        .enumerate()
+        .map(|(i, l)| match i {
+            0 | STEPS => (l - max).exp(),
+            i if i % 2 == 1 => 4.0 * (l - max).exp(),
+            _ => 2.0 * (l - max).exp(),
+        })
+        .sum();
+    (max + (sum * h / 3.0).ln()).exp()
+}
+
+/// Calculate the two-sided confidence interval for the noncentrality parameter of a noncentral
+/// t-distribution with the given degrees of freedom, given an observed t-statistic.
+#[cfg(feature = "std")]
+fn noncentrality_interval(t: f64, df: f64, alpha: f64) -> (f64, f64) {
+    // A noncentral t-statistic is (Z + λ) / S, so its CDF is the expectation of Φ(tS - λ) over S.
+    let points = scale_points(df);
+    let dist_norm = Normal::new(0.0, 1.0).unwrap();
+    let cdf = |ncp: f64| points.iter().map(|(s, w)| w * dist_norm.cdf(t * s - ncp)).sum::<f64>();
+
+    // Find the noncentrality parameters which put the observed t-statistic at each quantile,
+    // widening the bracket around the statistic until it contains them and then bisecting. The
+    // CDF is decreasing in the noncentrality parameter.
+    let solve = |p: f64| {
+        let (mut low, mut high) = (t - 1.0, t + 1.0);
+        while cdf(low) < p {
+            low -= 2.0 * (t - low);
+        }
+        while cdf(high) > p {
+            high += 2.0 * (high - t);
+        }
+        for _ in 0..64 {
+            let mid = (low + high) / 2.0;
+            if cdf(mid) > p {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        (low + high) / 2.0
+    };
+    (solve(1.0 - alpha / 2.0), solve(alpha / 2.0))
+}
+
+/// Calculate the points and weights with which to integrate a function of S, where S² is an
+/// independent chi-squared variable divided by its degrees of freedom, as in the denominator of a
+/// t-statistic. The weights sum to one, so the weighted sum of the function's values at the points
+/// is its expectation.
+#[cfg(feature = "std")]
+pub(crate) fn scale_points(df: f64) -> Vec<(f64, f64)> {
+    const STEPS: usize = 500;
+
+    // Integrate over u = ln(S) using Simpson's rule, with the log density of u relative to its
+    // mode, and bounds which cover both the long lower tail for small degrees of freedom and the
+    // narrow peak for large ones.
+    let low = -(30.0 / df).max(8.0 / df.sqrt());
+    let high = 8.0 / df.sqrt();
+    let h = (high - low) / STEPS as f64;
+    let points: Vec<(f64, f64)> = (0..=STEPS)
+        .map(|i| {
+            let u = low + h * i as f64;
+            let w = (df * (u - (2.0 * u).exp() / 2.0 + 0.5)).exp();
+            let w = match i {
+                0 | STEPS => w,
+                i if i % 2 == 1 => 4.0 * w,
+                _ => 2.0 * w,
+            };
+            (u.exp(), w)
+        })
+        .collect();
+    let total: f64 = points.iter().map(|(_, w)| w).sum();
+
+    // Drop the points which would contribute nothing, to make the integrand cheaper to evaluate.
+    points.into_iter().map(|(s, w)| (s, w / total)).filter(|&(_, w)| w > 1e-18).collect()
+}
+
+/// Unwrap the result of a fallible test, panicking with the error's message.
+#[cfg(feature = "std")]
+fn unwrap<T>(result: Result<T, Error>) -> T {
+    match result {
+        Ok(v) => v,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+/// An error returned when a statistical test cannot be performed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The confidence level was outside the range `(0, 100)`.
+    InvalidConfidence(f64),
+    /// There were too few measurements for the test, e.g. an empty data set or, for most tests,
+    /// one with fewer than two measurements.
+    InsufficientSamples,
+    /// A data set contained a NaN or infinite value.
+    NonFinite,
+    /// The inputs had no variance for the test to measure, e.g. a constant data set, identical
+    /// ranks, or counts which were all zero.
+    ZeroVariance,
+    /// Paired data sets had different numbers of measurements.
+    MismatchedLengths,
+    /// An equivalence margin was not a positive, finite number.
+    InvalidMargin(f64),
+    /// A trimming proportion was outside the range `[0, 0.5)`.
+    InvalidProportion(f64),
+    /// A data set contained a zero or negative value where only positive values are allowed.
+    NonPositive,
+    /// A variance was not a finite, non-negative number.
+    InvalidVariance(f64),
+    /// An effect size was zero, not finite, or negative where only positive values are allowed.
+    InvalidEffectSize(f64),
+    /// A statistical power was outside the range `(0, 1)`.
+    InvalidPower(f64),
+    /// The information fractions of a group-sequential test were not increasing or were outside
+    /// the range `(0, 1]`.
+    InvalidFractions,
+    /// A count of successes was greater than the number of trials, or there were no trials.
+    InvalidCounts(u64, u64),
+    /// An exposure was not a positive, finite number.
+    InvalidExposure(f64),
+    /// A p-value was outside the range `(0, 1]`.
+    InvalidPValue(f64),
+    /// The coverage of a tolerance interval was outside the range `(0, 1)`.
+    InvalidCoverage(f64),
+    /// A control chart's allowance, limit, or weight was out of range.
+    InvalidLimit(f64),
+    /// A duration was not a finite number followed by a unit of time.
+    InvalidDuration,
+    /// The total of a table of counts was too large to represent.
+    CountOverflow,
+    /// A measurement's weight was negative or not finite.
+    InvalidWeight(f64),
+    /// A test which only supports two-sided alternative hypotheses was given a one-sided one.
+    UnsupportedAlternative,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidConfidence(p) => write!(f, "confidence must be (0,100), was {}", p),
+            Error::InsufficientSamples => write!(f, "too few measurements for the test"),
+            Error::NonFinite => write!(f, "measurements must be finite"),
+            Error::ZeroVariance => write!(f, "the measurements have no variance for the test"),
+            Error::MismatchedLengths => write!(f, "paired data sets must have the same length"),
+            Error::InvalidMargin(m) => write!(f, "margin must be positive, was {}", m),
+            Error::InvalidProportion(p) => write!(f, "proportion must be [0,0.5), was {}", p),
+            Error::NonPositive => write!(f, "measurements must be positive"),
+            Error::InvalidVariance(v) => write!(f, "variance must be non-negative, was {}", v),
+            Error::InvalidEffectSize(d) => write!(f, "invalid effect size: {}", d),
+            Error::InvalidPower(p) => write!(f, "power must be (0,1), was {}", p),
+            Error::InvalidFractions => write!(f, "fractions must be increasing and in (0,1]"),
+            Error::InvalidCounts(s, n) => {
+                write!(f, "invalid counts: {} successes of {} trials", s, n)
+            }
+            Error::InvalidExposure(e) => write!(f, "exposure must be positive, was {}", e),
+            Error::InvalidPValue(p) => write!(f, "p-value must be (0,1], was {}", p),
+            Error::InvalidCoverage(p) => write!(f, "coverage must be (0,1), was {}", p),
+            Error::InvalidLimit(l) => write!(f, "invalid control chart parameter: {}", l),
+            Error::InvalidDuration => write!(f, "durations must be a number and a unit of time"),
+            Error::CountOverflow => write!(f, "counts are too large to total"),
+            Error::InvalidWeight(w) => write!(f, "weight must be non-negative, was {}", w),
+            Error::UnsupportedAlternative => write!(f, "the test must be two-sided"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn summarize_odd() {
+        let s: Summary = [1.0, 2.0, 3.0].iter().collect();
+
+        assert_relative_eq!(s.n, 3.0);
+        assert_relative_eq!(s.mean, 2.0);
+        assert_relative_eq!(s.variance, 1.0);
+        assert_relative_eq!(s.min, 1.0);
+        assert_relative_eq!(s.max, 3.0);
+    }
+
+    #[test]
+    fn summarize_even() {
+        let s: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+
+        assert_relative_eq!(s.n, 4.0);
+        assert_relative_eq!(s.mean, 2.5);
         assert_relative_eq!(s.variance, 1.6666666666666667);
     }
 
+    #[test]
+    fn mean_confidence_interval() {
+        let s: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+        let (low, high) = s.confidence_interval(Confidence::P95);
+
+        assert_relative_eq!(low, 1.0367568385224393, epsilon = 1e-9);
+        assert_relative_eq!(high, 4.963243161477561, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn fieller_ratio() {
+        let a: Summary = [10.0, 12.0, 11.0, 13.0, 12.5].iter().collect();
+        let b: Summary = [12.0, 14.0, 13.5, 15.0, 14.2].iter().collect();
+        let r = a.ratio(&b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(r.ratio, 1.174358974358974, epsilon = 1e-12);
+        assert_relative_eq!(r.ci_low, 1.026355863643081, epsilon = 1e-9);
+        assert_relative_eq!(r.ci_high, 1.349651444627129, epsilon = 1e-9);
+        assert_relative_eq!(r.p_value, 0.02398434408290181, epsilon = 1e-9);
+        assert!(r.is_significant());
+
+        // A denominator indistinguishable from zero gives an unbounded interval.
+        let zero: Summary = [-1.0, 1.0, -2.0, 2.5].iter().collect();
+        let r = zero.ratio(&b, Confidence::P95).unwrap();
+        assert_eq!((r.ci_low, r.ci_high), (f64::NEG_INFINITY, f64::INFINITY));
+    }
+
+    #[test]
+    fn future_prediction_interval() {
+        let s: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+        let (low, high) = s.prediction_interval(Confidence::P95);
+
+        assert_relative_eq!(low, -1.808943986628495, epsilon = 1e-9);
+        assert_relative_eq!(high, 7.808943986628495, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn normal_tolerance_interval() {
+        let s: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+        let (low, high) = s.tolerance_interval(0.9, Confidence::P95);
+
+        assert_relative_eq!(low, -3.758770319638782, epsilon = 1e-9);
+        assert_relative_eq!(high, 9.758770319638782, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn summarize_weighted() {
+        let weighted: Summary =
+            [(1.0, 2.0), (2.0, 1.0), (4.0, 3.0), (8.0, 0.0)].into_iter().collect();
+        let repeated: Summary = [1.0, 1.0, 2.0, 4.0, 4.0, 4.0].iter().collect();
+
+        assert_relative_eq!(weighted.n, repeated.n);
+        assert_relative_eq!(weighted.mean, repeated.mean, epsilon = 1e-12);
+        assert_relative_eq!(weighted.variance, repeated.variance, epsilon = 1e-12);
+        assert_relative_eq!(weighted.m3, repeated.m3, epsilon = 1e-12);
+        assert_relative_eq!(weighted.m4, repeated.m4, epsilon = 1e-12);
+        assert_relative_eq!(weighted.min, 1.0);
+        assert_relative_eq!(weighted.max, 4.0);
+    }
+
+    #[test]
+    fn summarize_invalid_weights() {
+        let mut s: Summary = [1.0, 2.0, 3.0].iter().collect();
+        for w in [-1.0, f64::NAN, f64::INFINITY, 0.0] {
+            if w != 0.0 {
+                assert!(matches!(s.try_push_weighted(10.0, w), Err(Error::InvalidWeight(_))));
+            } else {
+                s.push_weighted(10.0, w);
+            }
+            assert_relative_eq!(s.n, 3.0);
+            assert_relative_eq!(s.mean, 2.0);
+            assert_relative_eq!(s.variance, 1.0);
+            assert_relative_eq!(s.max, 3.0);
+        }
+
+        let empty: Summary = [(10.0, 0.0)].into_iter().collect();
+        assert_relative_eq!(empty.n, 0.0);
+        assert!(empty.min.is_infinite() && empty.max.is_infinite());
+    }
+
+    #[test]
+    #[should_panic(expected = "weight must be non-negative, was -1")]
+    fn summarize_negative_weight() {
+        let _: Summary = [(1.0, 1.0), (10.0, -1.0)].into_iter().collect();
+    }
+
+    #[test]
+    fn summarize_screened() {
+        let v = [1.0, f64::NAN, 2.0, f64::NEG_INFINITY, 3.0, f64::INFINITY];
+
+        let (s, skipped) = Summary::screened(v, NonFinitePolicy::Count).unwrap();
+        assert_relative_eq!(s.n, 3.0);
+        assert_relative_eq!(s.mean, 2.0);
+        assert_relative_eq!(s.variance, 1.0);
+        assert_eq!(skipped, 3);
+
+        let (s, skipped) = Summary::screened(v, NonFinitePolicy::Skip).unwrap();
+        assert_relative_eq!(s.max, 3.0);
+        assert_eq!(skipped, 0);
+
+        assert_eq!(Summary::screened(v, NonFinitePolicy::Strict).unwrap_err(), Error::NonFinite);
+        let (s, _) = Summary::screened([1.0, 2.0], NonFinitePolicy::default()).unwrap();
+        assert_relative_eq!(s.mean, 1.5);
+    }
+
+    #[test]
+    fn summarize_autocorrelated() {
+        let series =
+            [1.0, 2.0, 3.0, 2.0, 1.0, 2.0, 3.0, 4.0, 5.0, 4.0, 3.0, 4.0, 5.0, 6.0, 5.0, 4.0];
+        let s = Summary::autocorrelated(&series).unwrap();
+        let naive: Summary = series.iter().collect();
+
+        assert_relative_eq!(s.n, 3.86317907444668, epsilon = 1e-12);
+        assert_relative_eq!(s.mean, naive.mean);
+        assert_relative_eq!(s.variance, naive.variance);
+        assert!(s.std_err() > naive.std_err());
+        assert_eq!(Summary::autocorrelated(&[1.0, f64::NAN]).unwrap_err(), Error::NonFinite);
+    }
+
+    #[test]
+    fn summarize_owned() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let b: Summary = (1..=4).map(f64::from).collect();
+
+        assert_relative_eq!(a.n, b.n);
+        assert_relative_eq!(a.mean, b.mean);
+        assert_relative_eq!(a.variance, b.variance);
+    }
+
+    #[test]
+    fn summarize_extend() {
+        let mut s: Summary = [1.0].iter().collect();
+        s.extend(&[2.0, 3.0]);
+        s.extend(vec![4.0]);
+
+        assert_relative_eq!(s.n, 4.0);
+        assert_relative_eq!(s.mean, 2.5);
+        assert_relative_eq!(s.variance, 1.6666666666666667);
+    }
+
+    #[test]
+    fn relative_variation() {
+        let s: Summary = [90.0, 95.0, 100.0, 105.0, 110.0].iter().collect();
+
+        assert_relative_eq!(s.coefficient_of_variation(), 0.07905694150420949);
+        assert_relative_eq!(s.relative_std_err(), 0.035355339059327376);
+
+        let s: Summary = [-90.0, -95.0, -100.0, -105.0, -110.0].iter().collect();
+        assert_relative_eq!(s.coefficient_of_variation(), 0.07905694150420949);
+    }
+
+    #[test]
+    fn summarize_moments() {
+        let s: Summary = [1.0, 2.0, 3.0, 4.0, 10.0].iter().collect();
+
+        assert_relative_eq!(s.m3, 36.0, epsilon = 1e-12);
+        assert_relative_eq!(s.m4, 278.8, epsilon = 1e-12);
+        assert_relative_eq!(s.skewness(), 1.697056274847714, epsilon = 1e-12);
+        assert_relative_eq!(s.kurtosis(), 3.152, epsilon = 1e-12);
+
+        // The moments are the same however they're accumulated.
+        let values = [2.1, 3.4, 1.9, 5.6, 4.4, 3.8, 2.9, 4.1, 3.3, 4.7];
+        let a: Summary = values.iter().collect();
+        let b: Summary =
+            values[..3].iter().collect::<Summary>().merge(&values[3..].iter().collect());
+        let c = Summary::of(&values);
+        for s in [a, b, c] {
+            assert_relative_eq!(s.m3, 0.0596159999999999, epsilon = 1e-12);
+            assert_relative_eq!(s.m4, 3.152595519999999, epsilon = 1e-12);
+            assert_relative_eq!(s.skewness(), 0.05448697126100084, epsilon = 1e-12);
+            assert_relative_eq!(s.kurtosis(), -0.40094295548127394, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn summarize_large_magnitudes() {
+        // Tiny deltas on a large offset lose precision without compensated summation.
+        let s: Summary = (0..1_000_000).map(|i| 1e9 + f64::from(i % 10) * 1e-3).collect();
+
+        assert_relative_eq!(s.mean - 1e9, 0.004500031471252441, epsilon = 1e-12);
+        assert_relative_eq!(s.variance, 8.249944831792637e-6, max_relative = 1e-12);
+    }
+
+    #[test]
+    fn summarize_slice() {
+        for len in [0, 1, 3, 4, 5, 17, 1000] {
+            let values: Vec<f64> = (0..len).map(|i| f64::from(i).sin() * 100.0).collect();
+            let a = Summary::of(&values);
+            let b: Summary = values.iter().collect();
+
+            assert_relative_eq!(a.n, b.n);
+            assert_relative_eq!(a.mean, b.mean, epsilon = 1e-12);
+            assert_eq!((a.min, a.max), (b.min, b.max));
+            if len > 1 {
+                assert_relative_eq!(a.variance, b.variance, max_relative = 1e-12);
+            }
+        }
+
+        let values: Vec<f64> = (0..1_000_000).map(|i| 1e9 + f64::from(i % 10) * 1e-3).collect();
+        let s = Summary::of(&values);
+        assert_relative_eq!(s.mean - 1e9, 0.004500031471252441, epsilon = 1e-6);
+        assert_relative_eq!(s.variance, 8.249944831792637e-6, max_relative = 1e-6);
+    }
+
+    #[test]
+    fn summarize_merged() {
+        let a: Summary = [1.0, 2.0, 3.0].iter().collect();
+        let b: Summary = [4.0, 5.0, 6.0, 7.0].iter().collect();
+        let one: Summary = [8.0].iter().collect();
+        let empty: Summary = std::iter::empty::<f64>().collect();
+        let all: Summary = (1..=8).map(f64::from).collect();
+        let s = a.merge(&b).merge(&one).merge(&empty);
+
+        assert_relative_eq!(s.n, all.n);
+        assert_relative_eq!(s.mean, all.mean);
+        assert_relative_eq!(s.variance, all.variance, epsilon = 1e-12);
+        assert_eq!((s.min, s.max), (1.0, 8.0));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn summarize_parallel() {
+        let values: Vec<f64> = (0..1_000_000).map(|i| f64::from(i).sin() * 100.0).collect();
+        let a = Summary::par_of(&values);
+        let b = Summary::of(&values);
+
+        assert_relative_eq!(a.n, b.n);
+        assert_relative_eq!(a.mean, b.mean, epsilon = 1e-12);
+        assert_relative_eq!(a.variance, b.variance, max_relative = 1e-12);
+    }
+
+    #[test]
+    fn summarize_parts() {
+        let a = Summary::from_parts(4.0, 2.5, 1.6666666666666667).unwrap();
+        let b: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        assert_relative_eq!(a.std_err(), b.std_err());
+        assert!(a.skewness().is_nan());
+
+        // The range is taken from the summaries which know it.
+        let s = a.merge(&b);
+        assert_relative_eq!(s.n, 8.0);
+        assert_relative_eq!(s.variance, 1.4285714285714286, epsilon = 1e-12);
+        assert_eq!((s.min, s.max), (1.0, 4.0));
+
+        assert_eq!(Summary::from_parts(1.0, 2.5, 1.0).unwrap_err(), Error::InsufficientSamples);
+        assert_eq!(Summary::from_parts(f64::NAN, 2.5, 1.0).unwrap_err(), Error::NonFinite);
+        assert_eq!(Summary::from_parts(4.0, f64::INFINITY, 1.0).unwrap_err(), Error::NonFinite);
+        assert_eq!(Summary::from_parts(4.0, 2.5, -1.0).unwrap_err(), Error::InvalidVariance(-1.0));
+    }
+
+    #[test]
+    fn summarize_trimmed() {
+        let x = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 100.0];
+        let y = [3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, -50.0];
+
+        let w = Summary::winsorized(&x, 0.1).unwrap();
+        assert_relative_eq!(w.n, 10.0);
+        assert_relative_eq!(w.mean, 5.5);
+        assert_relative_eq!(w.variance, 7.388888888888889, epsilon = 1e-12);
+
+        let a = Summary::trimmed(&x, 0.1).unwrap();
+        let b = Summary::trimmed(&y, 0.1).unwrap();
+        assert_relative_eq!(a.n, 8.0);
+        assert_relative_eq!(a.mean, 5.5);
+        assert_relative_eq!(a.variance, 9.5, epsilon = 1e-12);
+        assert_eq!((a.min, a.max), (2.0, 9.0));
+
+        // Yuen's test for the trimmed means.
+        let diff = a.compare(&b, Confidence::P95);
+        assert_relative_eq!(diff.effect, 1.0, epsilon = 1e-12);
+        assert_relative_eq!(diff.p_value, 0.5269140840765067, epsilon = 1e-9);
+
+        // Without trimming, the outliers dominate.
+        let x: Summary = x.iter().collect();
+        assert_relative_eq!(x.mean, 14.5);
+
+        assert_relative_eq!(Summary::trimmed(&y, 0.0).unwrap().mean, 1.3);
+        assert_eq!(Summary::trimmed(&y, 0.5).unwrap_err(), Error::InvalidProportion(0.5));
+        assert_eq!(
+            Summary::trimmed(&[], 0.1).unwrap().try_compare(&a, Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+    }
+
     #[test]
     fn compare_similar_data() {
-        let a: Summary = vec![1.0, 2.0, 3.0, 4.0].iter().collect();
-        let b: Summary = vec![1.0, 2.0, 3.0, 4.0].iter().collect();
-        let diff = a.compare(&b, 80.0);
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let b: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let diff = a.compare(&b, Confidence::P80);
 
         assert_relative_eq!(diff.effect, 0.0);
         assert_relative_eq!(diff.effect_size, 0.0);
+        assert_relative_eq!(diff.hedges_g, 0.0);
+        assert_relative_eq!(diff.probability_of_superiority, 0.5);
+        assert_relative_eq!(diff.effect_size_ci_low, -diff.effect_size_ci_high, epsilon = 1e-9);
         assert_relative_eq!(diff.critical_value, 1.3143111667913936);
+        assert_relative_eq!(diff.ci_low, -1.3143111667913936);
+        assert_relative_eq!(diff.ci_high, 1.3143111667913936);
         assert_relative_eq!(diff.p_value, 1.0);
         assert_relative_eq!(diff.alpha, 0.19999999999999996);
         assert_relative_eq!(diff.beta, 0.0);
+        assert_relative_eq!(diff.bayes_factor, 0.5231565837302467, epsilon = 1e-9);
         assert!(!diff.is_significant());
+        assert_eq!(diff.direction(), Direction::Indistinguishable);
+        assert_eq!(diff.magnitude(), EffectMagnitude::Negligible);
+    }
+
+    #[test]
+    fn effect_size_interval() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+        let diff = a.compare(&b, Confidence::P80);
+
+        assert_relative_eq!(diff.effect_size_ci_low, 1.0966617191371644, epsilon = 1e-6);
+        assert_relative_eq!(diff.effect_size_ci_high, 3.633030034366461, epsilon = 1e-6);
+
+        // The interval narrows with more measurements.
+        let (a, b) = (a.merge(&a), b.merge(&b));
+        let diff = a.compare(&b, Confidence::P80);
+        assert!(diff.effect_size_ci_low > 1.0966617191371644);
+        assert!(diff.effect_size_ci_high < 3.633030034366461);
+        assert!(
+            diff.effect_size_ci_low < diff.effect_size
+                && diff.effect_size < diff.effect_size_ci_high
+        );
     }
 
     #[test]
     fn compare_different_data() {
-        let a: Summary = vec![1.0, 2.0, 3.0, 4.0].iter().collect();
-        let b: Summary = vec![10.0, 20.0, 30.0, 40.0].iter().collect();
-        let diff = a.compare(&b, 80.0);
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+        let diff = a.compare(&b, Confidence::P80);
 
         assert_relative_eq!(diff.effect, 22.5);
+        assert_relative_eq!(diff.delta, 22.5);
         assert_relative_eq!(diff.effect_size, 2.452519415855564);
+        assert_relative_eq!(diff.hedges_g, 2.1326255790048383);
+        assert_relative_eq!(diff.probability_of_superiority, 0.9585580874635672, epsilon = 1e-9);
         assert_relative_eq!(diff.critical_value, 10.568344341563591);
+        assert_relative_eq!(diff.t_statistic, 3.468386219886279, epsilon = 1e-12);
+        assert_relative_eq!(diff.df, 3.05999400059994, epsilon = 1e-12);
+        assert_relative_eq!(diff.std_err, 6.4871668186761875, epsilon = 1e-12);
+        assert_relative_eq!(diff.ci_low, 11.931655658436409);
+        assert_relative_eq!(diff.ci_high, 33.06834434156359);
+        assert_relative_eq!(diff.relative_delta, 900.0);
+        assert_relative_eq!(diff.relative_ci_low, 424.93214736746745, epsilon = 1e-9);
+        assert_relative_eq!(diff.relative_ci_high, 1805.0081139343358, epsilon = 1e-9);
         assert_relative_eq!(diff.p_value, 0.03916791618893325);
         assert_relative_eq!(diff.alpha, 0.19999999999999996);
         assert_relative_eq!(diff.beta, 0.985621684277956);
+        assert_relative_eq!(diff.bayes_factor, 4.306165150572913, epsilon = 1e-9);
+        assert!(diff.is_significant());
+        assert_eq!(diff.direction(), Direction::Regressed);
+        assert_eq!(diff.magnitude(), EffectMagnitude::Large);
+        assert_eq!(diff.magnitude().to_string(), "large");
+
+        let diff = b.compare(&a, Confidence::P80);
+        assert_relative_eq!(diff.delta, -22.5);
+        assert_eq!(diff.direction(), Direction::Improved);
+    }
+
+    #[test]
+    fn compare_many_experiments() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+        let c: Summary = [1.5, 2.5, 3.5, 4.5].iter().collect();
+
+        let options = Options { confidence: Confidence::P80, ..Options::default() };
+        let diffs = a.compare_many(&[b, c], options, None);
+        assert_eq!(diffs.len(), 2);
+        assert_relative_eq!(diffs[0].p_value, 0.03916791618893325);
+        assert_relative_eq!(diffs[1].delta, 0.5);
+        assert!(diffs[0].spread.is_none());
+
+        let diffs = a.compare_many(&[b, c], options, Some(corrections::Correction::Holm));
+        assert_relative_eq!(diffs[0].p_value, 2.0 * 0.03916791618893325);
+        assert!(diffs[0].is_significant());
+        assert!(a.compare_many(&[], options, None).is_empty());
+
+        let diffs = a.compare_many(&[b, c], Options { spread: true, ..options }, None);
+        assert_relative_eq!(
+            diffs[0].spread.unwrap().ratio,
+            a.variance_ratio(&b, Confidence::P80).unwrap().ratio
+        );
+
+        let flat: Summary = [1.0, 1.0].iter().collect();
+        assert_eq!(
+            flat.try_compare_many(&[b, flat], options, None).unwrap_err(),
+            Error::ZeroVariance
+        );
+        let invalid = Options { confidence: Confidence::Custom(100.0), ..options };
+        assert_eq!(
+            a.try_compare_many(&[b], invalid, None).unwrap_err(),
+            Error::InvalidConfidence(100.0)
+        );
+    }
+
+    #[test]
+    fn compare_one_sided() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+        let two = a.compare(&b, Confidence::P80);
+        let greater = a.compare_with(
+            &b,
+            Options {
+                confidence: Confidence::P80,
+                alternative: Alternative::Greater,
+                ..Options::default()
+            },
+        );
+        let less = a.compare_with(
+            &b,
+            Options {
+                confidence: Confidence::P80,
+                alternative: Alternative::Less,
+                ..Options::default()
+            },
+        );
+
+        assert_relative_eq!(greater.p_value, two.p_value / 2.0);
+        assert_relative_eq!(less.p_value, 1.0 - two.p_value / 2.0);
+        assert!(greater.critical_value < two.critical_value);
+        assert!(greater.is_significant());
+        assert!(!less.is_significant());
+        assert_relative_eq!(greater.ci_high, f64::INFINITY);
+        assert_relative_eq!(less.ci_low, f64::NEG_INFINITY);
+        assert!(greater.ci_low > two.ci_low);
+        assert!(greater.beta > two.beta);
+        assert!(less.beta < 0.01);
+    }
+
+    #[test]
+    fn compare_glass_delta() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+        let diff = a.compare_with(
+            &b,
+            Options { effect_size: EffectSize::GlassDelta, ..Options::default() },
+        );
+
+        assert_relative_eq!(diff.effect_size, 17.428425057933378);
+        assert_relative_eq!(diff.hedges_g, 17.428425057933378 * (1.0 - 3.0 / 11.0));
+        assert_relative_eq!(diff.p_value, a.compare(&b, Confidence::P95).p_value);
+    }
+
+    #[test]
+    fn compare_pooled_cohens_d() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0].iter().collect();
+        let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+        let diff = a.compare_with(
+            &b,
+            Options { effect_size: EffectSize::PooledCohensD, ..Options::default() },
+        );
+
+        assert_relative_eq!(diff.effect_size, 2.784544852736326, epsilon = 1e-12);
+        assert_relative_eq!(diff.hedges_g, 2.784544852736326 * (1.0 - 3.0 / 39.0), epsilon = 1e-12);
+        assert_relative_eq!(diff.effect_size_ci_low, 1.051394160670224, epsilon = 1e-6);
+        assert_relative_eq!(diff.effect_size_ci_high, 4.450255689067515, epsilon = 1e-6);
+
+        // With unequal sample sizes, the unweighted variant differs.
+        let d_av = a.compare(&b, Confidence::P95);
+        assert_relative_eq!(d_av.effect_size, 2.206300210252589, epsilon = 1e-12);
+        assert_relative_eq!(d_av.p_value, diff.p_value);
+    }
+
+    #[test]
+    fn equivalent_data() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+        let b: Summary = [1.5, 2.5, 3.5, 4.5, 5.5].iter().collect();
+        let eq = a.equivalence(&b, Margin::Absolute(3.0), Confidence::P95).unwrap();
+
+        assert_relative_eq!(eq.margin, 3.0);
+        assert_relative_eq!(eq.p_lower, 0.004039541130205945, epsilon = 1e-9);
+        assert_relative_eq!(eq.p_upper, 0.01847101885681205, epsilon = 1e-9);
+        assert_relative_eq!(eq.p_value, eq.p_upper);
+        assert!(eq.is_equivalent());
+
+        let eq = a.equivalence(&b, Margin::Relative(20.0), Confidence::P95).unwrap();
+        assert_relative_eq!(eq.margin, 0.6000000000000001);
+        assert!(!eq.is_equivalent());
+
+        assert_eq!(
+            a.equivalence(&b, Margin::Absolute(-1.0), Confidence::P95).unwrap_err(),
+            Error::InvalidMargin(-1.0)
+        );
+    }
+
+    #[test]
+    fn compare_trimmed_data() {
+        let a = [10.1, 10.3, 9.8, 10.0, 10.2, 9.9, 10.4, 10.1, 30.0, 9.7];
+        let b = [11.0, 11.3, 10.8, 11.1, 10.9, 11.2, 11.4, 10.7, -5.0, 11.0];
+        let diff = compare_trimmed(&a, &b, 0.2, Confidence::P95).unwrap();
+
+        assert_relative_eq!(diff.delta, 0.9, epsilon = 1e-12);
+        assert_relative_eq!(diff.t_statistic, 6.835990727814763, epsilon = 1e-9);
+        assert_relative_eq!(diff.df, 10.0, epsilon = 1e-9);
+        assert_relative_eq!(diff.p_value, 0.00004535651657246707, epsilon = 1e-12);
         assert!(diff.is_significant());
+
+        // The outliers hide the difference from the untrimmed test.
+        let (a, b): (Summary, Summary) = (a.iter().collect(), b.iter().collect());
+        assert!(!a.compare(&b, Confidence::P95).is_significant());
+
+        assert_eq!(
+            compare_trimmed(&[1.0, 2.0], &[1.0, 2.0], 0.5, Confidence::P95).unwrap_err(),
+            Error::InvalidProportion(0.5)
+        );
+    }
+
+    #[test]
+    fn summarize_paired_differences() {
+        let d = Summary::of_paired_differences(&[1.0, 5.0, 3.0], &[0.0, 2.0, 3.0]).unwrap();
+        assert_relative_eq!(d.n, 3.0);
+        assert_relative_eq!(d.mean, 4.0 / 3.0);
+        assert_relative_eq!(d.variance, 7.0 / 3.0, epsilon = 1e-12);
+        assert_relative_eq!(d.max, 3.0);
+
+        assert_eq!(
+            Summary::of_paired_differences(&[1.0], &[1.0, 2.0]).unwrap_err(),
+            Error::MismatchedLengths
+        );
+        assert_eq!(
+            Summary::of_paired_differences(&[1.0, 2.0], &[1.0, f64::NAN]).unwrap_err(),
+            Error::NonFinite
+        );
+    }
+
+    #[test]
+    fn compare_paired_data() {
+        let a = [10.0, 12.0, 14.0, 16.0, 18.0];
+        let b = [9.0, 11.0, 12.0, 15.0, 16.0];
+        let diff = compare_paired(&a, &b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(diff.effect, 1.4, epsilon = 1e-12);
+        assert_relative_eq!(diff.delta, -1.4, epsilon = 1e-12);
+        assert_relative_eq!(diff.effect_size, 2.5560386016907755, epsilon = 1e-12);
+        assert_relative_eq!(diff.critical_value, 0.6800873806582557, epsilon = 1e-9);
+        assert_relative_eq!(diff.t_statistic, -5.715476066494082, epsilon = 1e-12);
+        assert_relative_eq!(diff.df, 4.0);
+        assert_relative_eq!(diff.probability_of_superiority, 0.005293568667028467, epsilon = 1e-9);
+        assert_relative_eq!(diff.effect_size_ci_low, 0.6300549925644433, epsilon = 1e-6);
+        assert_relative_eq!(diff.effect_size_ci_high, 4.44981770378615, epsilon = 1e-6);
+        assert_relative_eq!(diff.ci_low, -2.080087380658256, epsilon = 1e-9);
+        assert_relative_eq!(diff.ci_high, -0.7199126193417443, epsilon = 1e-9);
+        assert_relative_eq!(diff.relative_delta, -10.0, epsilon = 1e-12);
+        assert_relative_eq!(diff.relative_ci_low, -14.132196919314556, epsilon = 1e-9);
+        assert_relative_eq!(diff.relative_ci_high, -5.867803080685463, epsilon = 1e-9);
+        assert_relative_eq!(diff.p_value, 0.004635839417904411, epsilon = 1e-9);
+        assert_relative_eq!(diff.bayes_factor, 12.301291792255626, epsilon = 1e-9);
+        assert!(diff.is_significant());
+        assert_eq!(diff.direction(), Direction::Improved);
+
+        // The same data, unpaired, shows no difference.
+        let (a, b): (Summary, Summary) = (a.iter().collect(), b.iter().collect());
+        assert!(!a.compare(&b, Confidence::P95).is_significant());
+
+        assert_eq!(
+            compare_paired(&[1.0], &[1.0, 2.0], Confidence::P95).unwrap_err(),
+            Error::MismatchedLengths
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+        let diff = a.compare(&b, Confidence::P80);
+
+        let json = serde_json::to_string(&a).unwrap();
+        let a2: Summary = serde_json::from_str(&json).unwrap();
+        assert_relative_eq!(a.mean, a2.mean);
+        assert_relative_eq!(a.variance, a2.variance);
+
+        let json = serde_json::to_string(&diff).unwrap();
+        let diff2: Difference = serde_json::from_str(&json).unwrap();
+        assert_relative_eq!(diff.p_value, diff2.p_value);
+        assert_relative_eq!(diff.critical_value, diff2.critical_value);
+    }
+
+    #[test]
+    fn display_results() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let diff = a.compare(&a, Confidence::P999);
+
+        assert_eq!(a.to_string(), "n = 4, mean = 2.50, std dev = 1.29");
+        assert_eq!(
+            diff.to_string(),
+            "No difference at 99.9% confidence: CI [-5.44, 5.44], p = 1.000"
+        );
+        assert_eq!(format!("{:#}", diff), "0.00 ± 5.44, p = 1.000 (not significant)");
+    }
+
+    #[test]
+    fn report_results() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+
+        assert_eq!(
+            a.compare(&b, Confidence::P80).report(&a, &b),
+            "Difference at 80% confidence!\n\
+             \t25.00 > 2.50 ± 10.57, p = .039\n\
+             \t+900.00% (CI [+424.93%, +1805.01%])\n\
+             \t10.00x (CI [5.72x, 17.49x])\n\
+             \td = 2.45 (large effect)\n\
+             \t96% of runs are larger than the control's"
+        );
+        assert_eq!(
+            a.compare(&a, Confidence::P999).report(&a, &a),
+            "No difference at 99.9% confidence."
+        );
+
+        // Non-standard levels are reported as given, rather than rounded from alpha.
+        assert_eq!(
+            a.compare(&a, Confidence::Custom(99.95)).report(&a, &a),
+            "No difference at 99.95% confidence."
+        );
+        let report = a.compare(&b, Confidence::Custom(92.5)).report(&a, &b);
+        assert!(report.starts_with("Difference at 92.5% confidence!\n"), "{}", report);
+        assert!(report.contains("\n\t10.00x (CI ["), "{}", report);
+    }
+
+    #[test]
+    fn compare_spread() {
+        let a: Summary = [10.0, 14.0, 9.0, 15.0, 11.0, 13.0, 8.0, 16.0].iter().collect();
+        let b: Summary = [12.0, 12.5, 11.5, 12.0, 12.5, 11.0, 12.0, 13.0].iter().collect();
+        assert!(a.compare(&b, Confidence::P95).spread.is_none());
+
+        let diff = a.compare_with(&b, Options { spread: true, ..Options::default() });
+        let vr = diff.spread.unwrap();
+        assert_relative_eq!(vr.ratio, a.variance_ratio(&b, Confidence::P95).unwrap().ratio);
+        assert!(vr.is_significant());
+        assert_eq!(
+            diff.report(&a, &b),
+            "No difference at 95% confidence.\n\
+             \tvariance 0.05x the control's (CI [0.01x, 0.23x]), p = .001: less variable"
+        );
+
+        let flat: Summary = [1.0, 1.0, 1.0].iter().collect();
+        assert!(flat
+            .compare_with(&b, Options { spread: true, ..Options::default() })
+            .spread
+            .is_none());
+    }
+
+    #[test]
+    fn significance_annotations() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let b: Summary = [10.0, 20.0, 30.0, 40.0].iter().collect();
+        let diff = a.compare(&b, Confidence::P80);
+        assert_eq!(diff.stars(), "*");
+        assert_eq!(diff.highest_confidence(), Some(Confidence::P95));
+
+        let stars = |p_value| Difference { p_value, ..diff }.stars();
+        assert_eq!(stars(0.0005), "***");
+        assert_eq!(stars(0.005), "**");
+        assert_eq!(stars(0.05), "ns");
+        assert_eq!(Confidence::highest(0.0005), Some(Confidence::P999));
+        assert_eq!(Confidence::highest(0.15), Some(Confidence::P80));
+        assert_eq!(Confidence::highest(0.2), None);
+        assert_eq!(Confidence::highest(f64::NAN), None);
+    }
+
+    #[test]
+    fn confidence_levels() {
+        assert_relative_eq!(Confidence::P999.percent(), 99.9);
+        assert_relative_eq!(Confidence::P95.alpha(), 0.050000000000000044);
+        assert_relative_eq!(Confidence::Custom(75.0).alpha(), 0.25);
+        assert_eq!(Confidence::P999.to_string(), "99.9%");
+    }
+
+    #[test]
+    #[should_panic(expected = "confidence must be (0,100)")]
+    fn compare_invalid_confidence() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let _ = a.compare(&a, Confidence::Custom(0.0));
+    }
+
+    #[test]
+    fn try_compare_errors() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let one: Summary = [1.0].iter().collect();
+        let nan: Summary = [1.0, f64::NAN].iter().collect();
+        let flat: Summary = [1.0, 1.0, 1.0].iter().collect();
+
+        assert_eq!(
+            a.try_compare(&a, Confidence::Custom(100.0)).unwrap_err(),
+            Error::InvalidConfidence(100.0)
+        );
+        assert_eq!(a.try_compare(&one, Confidence::P95).unwrap_err(), Error::InsufficientSamples);
+        assert_eq!(a.try_compare(&nan, Confidence::P95).unwrap_err(), Error::NonFinite);
+        assert_eq!(flat.try_compare(&flat, Confidence::P95).unwrap_err(), Error::ZeroVariance);
+        assert!(a.try_compare(&flat, Confidence::P95).is_ok());
     }
 }
@@ -0,0 +1,135 @@
+//! Summaries and comparisons of log-normally distributed data sets, such as benchmark timings, for
+//! which ratios are more meaningful than differences.
+
+use crate::{Confidence, Error, Ratio, Summary};
+
+/// A statistical summary of a log-normally distributed data set, which summarizes the natural logs
+/// of its measurements.
+///
+/// ```
+/// use nanostat::lognormal::GeometricSummary;
+///
+/// let timings = GeometricSummary::new(&[1.0, 10.0, 100.0])?;
+/// assert!((timings.geometric_mean() - 10.0).abs() < 1e-12);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeometricSummary {
+    /// The summary of the natural logs of the measurements.
+    pub log: Summary,
+}
+
+impl GeometricSummary {
+    /// Summarize the given measurements, which must be positive and finite.
+    pub fn new(values: &[f64]) -> Result<GeometricSummary, Error> {
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err(Error::NonFinite);
+        }
+        if values.iter().any(|&v| v <= 0.0) {
+            return Err(Error::NonPositive);
+        }
+        Ok(GeometricSummary { log: values.iter().map(|v| v.ln()).collect() })
+    }
+
+    /// The geometric mean of the measurements, which is the median of a log-normal distribution.
+    #[must_use]
+    pub fn geometric_mean(&self) -> f64 {
+        self.log.mean.exp()
+    }
+
+    /// The geometric standard deviation of the measurements: the multiplicative factor by which
+    /// measurements typically differ from the geometric mean.
+    #[must_use]
+    pub fn geometric_std_dev(&self) -> f64 {
+        self.log.std_dev().exp()
+    }
+
+    /// The sample variance of the natural logs of the measurements.
+    #[must_use]
+    pub const fn log_variance(&self) -> f64 {
+        self.log.variance
+    }
+
+    /// The confidence interval for the geometric mean of the population from which the sample was
+    /// drawn. The confidence level must be in the range `(0, 100)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the confidence level is out of range or if the summary has fewer than two
+    /// measurements.
+    #[must_use]
+    pub fn confidence_interval(&self, confidence: Confidence) -> (f64, f64) {
+        let (low, high) = self.log.confidence_interval(confidence);
+        (low.exp(), high.exp())
+    }
+
+    /// Calculate the ratio of the two summaries' geometric means, e.g. the speedup or slowdown
+    /// factor of an experiment relative to a control, using a two-tailed Welch's t-test of the logs
+    /// of the measurements. The confidence level must be in the range `(0, 100)`.
+    ///
+    /// ```
+    /// use nanostat::Confidence;
+    /// use nanostat::lognormal::GeometricSummary;
+    ///
+    /// let control = GeometricSummary::new(&[10.0, 12.0, 11.0, 13.0, 12.5])?;
+    /// let experiment = GeometricSummary::new(&[12.0, 14.0, 13.5, 15.0, 14.2])?;
+    /// let speedup = control.compare(&experiment, Confidence::P95)?;
+    /// assert!(speedup.is_significant());
+    /// assert!(1.0 < speedup.ci_low && speedup.ci_high < 1.5);
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    pub fn compare(
+        &self,
+        other: &GeometricSummary,
+        confidence: Confidence,
+    ) -> Result<Ratio, Error> {
+        let diff = self.log.try_compare(&other.log, confidence)?;
+        Ok(Ratio {
+            ratio: diff.delta.exp(),
+            ci_low: diff.ci_low.exp(),
+            ci_high: diff.ci_high.exp(),
+            p_value: diff.p_value,
+            alpha: diff.alpha,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn geometric_summary() {
+        let s = GeometricSummary::new(&[10.0, 12.0, 11.0, 13.0, 12.5]).unwrap();
+
+        assert_relative_eq!(s.geometric_mean(), 11.64891454385988, epsilon = 1e-12);
+        assert_relative_eq!(s.geometric_std_dev(), 1.111207943601499, epsilon = 1e-12);
+        assert_relative_eq!(s.log_variance(), 0.011119209231861392, epsilon = 1e-12);
+
+        let (low, high) = s.confidence_interval(Confidence::P95);
+        assert_relative_eq!(low, 10.21934445104627, epsilon = 1e-9);
+        assert_relative_eq!(high, 13.27846523817458, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn speedup_ratio() {
+        let a = GeometricSummary::new(&[10.0, 12.0, 11.0, 13.0, 12.5]).unwrap();
+        let b = GeometricSummary::new(&[12.0, 14.0, 13.5, 15.0, 14.2]).unwrap();
+        let r = a.compare(&b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(r.ratio, 1.176302639216823, epsilon = 1e-12);
+        assert_relative_eq!(r.ci_low, 1.022755063908808, epsilon = 1e-9);
+        assert_relative_eq!(r.ci_high, 1.352902515818623, epsilon = 1e-9);
+        assert_relative_eq!(r.p_value, 0.02830629952581079, epsilon = 1e-9);
+        assert!(r.is_significant());
+    }
+
+    #[test]
+    fn geometric_errors() {
+        assert_eq!(GeometricSummary::new(&[1.0, 0.0]).unwrap_err(), Error::NonPositive);
+        assert_eq!(GeometricSummary::new(&[1.0, f64::NAN]).unwrap_err(), Error::NonFinite);
+    }
+}
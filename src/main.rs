@@ -1,23 +1,34 @@
 use std::error::Error;
+use std::fmt;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 
 use clap::{Parser, ValueHint};
 use plotlib::page::Page;
-use plotlib::repr::BoxPlot;
-use plotlib::view::CategoricalView;
+use plotlib::repr::{BoxPlot, HistogramBins, Plot};
+use plotlib::style::{BoxStyle, LineStyle};
+use plotlib::view::{CategoricalView, ContinuousView};
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::Value;
 
-use nanostat::Summary;
+use nanostat::corrections::Correction;
+use nanostat::duration::{Duration, Unit};
+use nanostat::histogram::{Binning, Histogram};
+use nanostat::hypothesis::{self, StatisticalTest};
+use nanostat::normality::shapiro_wilk;
+use nanostat::power::{minimum_detectable_effect, power_curve};
+use nanostat::{Confidence, Dataset, NonFinitePolicy, Options, Summary};
 
 /// Check for statistically valid differences between sets of measurements.
 #[derive(Debug, Parser)]
 struct Opt {
-    /// The path to a file with per-line floating point values.
+    /// The path to a file with per-line floating point values, or - for stdin. If the file has
+    /// several data sets, e.g. a JSON object, the first is the control and the rest experiments.
     #[clap(action, value_hint = ValueHint::FilePath)]
     control: PathBuf,
 
-    /// The paths to one or more files with per-line floating point values.
+    /// The paths to one or more files with per-line floating point values, or - for stdin.
     #[clap(action, value_hint = ValueHint::FilePath)]
     experiments: Vec<PathBuf>,
 
@@ -25,38 +36,188 @@ struct Opt {
     #[clap(action, short = 'c', long, default_value = "95.0")]
     confidence: f64,
 
+    /// Correct for comparing the control with several experiments (holm, bonferroni, or bh).
+    #[clap(action, long, value_parser = parse_correction)]
+    correction: Option<Correction>,
+
+    /// Also compare the variances of the control and each experiment.
+    #[clap(action, long)]
+    spread: bool,
+
+    /// Additional tests to run for each experiment (welch, mann-whitney, brunner-munzel,
+    /// kolmogorov-smirnov, or permutation). May be repeated.
+    #[clap(action, long = "test", value_name = "NAME", value_parser = parse_test)]
+    tests: Vec<String>,
+
+    /// How to handle NaNs and infinities in the measurements (strict, skip, or count).
+    #[clap(action, long, default_value = "strict", value_parser = parse_non_finite)]
+    non_finite: NonFinitePolicy,
+
+    /// The unit to convert measurements with units of time, e.g. 12.3ms, into (ns, us, ms, or s).
+    #[clap(action, long, default_value = "ns", value_parser = parse_unit)]
+    unit: Unit,
+
+    /// Read the measurements from a column of CSV files. Implied by --column and --delimiter, and
+    /// for files ending in .csv or .tsv.
+    #[clap(action, long)]
+    csv: bool,
+
+    /// The column of CSV files to read, by header name or by number, starting at 1 [default: 1].
+    #[clap(action, long, value_name = "NAME|INDEX", value_parser = parse_column)]
+    column: Option<Column>,
+
+    /// The field delimiter of CSV files, e.g. ; or \t [default: , or \t for .tsv files].
+    #[clap(action, long, value_parser = parse_delimiter)]
+    delimiter: Option<char>,
+
+    /// Read the measurements from JSON files, each with either an array of measurements or an
+    /// object mapping labels to arrays of measurements. Implied for files ending in .json.
+    #[clap(action, long)]
+    json: bool,
+
+    /// The output format (text, csv, or tsv). The csv and tsv formats have one row per experiment,
+    /// with columns for any --test and --spread results, and print caveats to stderr.
+    #[clap(action, long, default_value = "text", value_parser = parse_format)]
+    format: Format,
+
     /// Write an SVG box plot to the given path.
     #[clap(action, long, value_hint = ValueHint::FilePath, value_name = "PATH")]
     box_plot: Option<String>,
+
+    /// Write an SVG histogram to the given path.
+    #[clap(action, long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    histogram: Option<String>,
+
+    /// Write an SVG plot of the power of each comparison to the given path.
+    #[clap(action, long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    power_curve: Option<String>,
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let opt: Opt = Opt::parse();
+/// The format of the comparisons.
+#[derive(Copy, Clone, Debug)]
+enum Format {
+    Text,
+    Delimited(char),
+}
+
+/// A column of a CSV file.
+#[derive(Clone, Debug)]
+enum Column {
+    Index(usize),
+    Name(String),
+}
+
+fn main() {
+    if let Err(e) = run(Opt::parse()) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(opt: Opt) -> Result<(), Box<dyn Error>> {
+    // Stdin can only be read once.
+    let stdin = Path::new("-");
+    if std::iter::once(&opt.control).chain(&opt.experiments).filter(|p| *p == stdin).count() > 1 {
+        return Err("stdin (-) can only be given once".into());
+    }
 
     let mut plots = CategoricalView::new();
+    let mut all_data = vec![];
 
-    let (ctrl_data, ctrl) = read_file(&opt.control)?;
-    plots = plots.add(BoxPlot::from_vec(ctrl_data).label(opt.control.to_string_lossy()));
+    let confidence = Confidence::Custom(opt.confidence);
 
-    for path in opt.experiments {
-        let (exp_data, exp) = read_file(&path)?;
-        plots = plots.add(BoxPlot::from_vec(exp_data).label(path.to_string_lossy()));
+    let mut inputs = vec![];
+    for path in std::iter::once(&opt.control).chain(&opt.experiments) {
+        inputs.extend(read_file(path, &opt)?);
+    }
+    let mut inputs = inputs.into_iter();
+
+    let (ctrl_label, ctrl_data, ctrl) = inputs.next().ok_or("no control measurements")?;
+    let ctrl_caveat = normality_caveat(&ctrl_label, &ctrl_data, confidence);
+    all_data.push(ctrl_data.clone());
+    plots = plots.add(BoxPlot::from_vec(ctrl_data).label(&ctrl_label));
 
-        let diff = ctrl.compare(&exp, opt.confidence);
+    let mut exps = vec![];
+    for (label, exp_data, exp) in inputs {
+        let exp_caveat = normality_caveat(&label, &exp_data, confidence);
+        all_data.push(exp_data.clone());
+        plots = plots.add(BoxPlot::from_vec(exp_data).label(&label));
+        exps.push((label, exp, exp_caveat));
+    }
 
-        println!("{}:", path.to_string_lossy());
-        if diff.is_significant() {
-            let p = format!("{:.3}", diff.p_value);
-            let p = p.trim_start_matches('0');
-            let op = if exp.mean < ctrl.mean { "<" } else { ">" };
+    // Compare each experiment with the control, correcting for multiple comparisons.
+    let summaries: Vec<_> = exps.iter().map(|(_, exp, _)| *exp).collect();
+    let options = Options { confidence, spread: opt.spread, ..Options::default() };
+    let diffs = ctrl.try_compare_many(&summaries, options, opt.correction)?;
+    let tests: Vec<Box<dyn StatisticalTest>> =
+        opt.tests.iter().filter_map(|name| hypothesis::by_name(name)).collect();
+    let datasets =
+        all_data.iter().map(|d| Dataset::new(d.clone())).collect::<Result<Vec<_>, _>>()?;
 
-            println!("\tDifference at {}% confidence!", opt.confidence);
-            println!(
-                "\t\t{:.2} {} {:.2} ± {:.2}, p = {}",
-                exp.mean, op, ctrl.mean, diff.critical_value, p,
-            );
-        } else {
-            println!("\tNo difference at {}% confidence.\n", opt.confidence);
+    if let Format::Delimited(d) = opt.format {
+        let mut header: Vec<String> = [
+            "experiment",
+            "n",
+            "mean",
+            "stddev",
+            "delta",
+            "delta_percent",
+            "p_value",
+            "significant",
+        ]
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+        if opt.spread {
+            header.extend(["variance_ratio".to_string(), "variance_p_value".to_string()]);
+        }
+        header.extend(opt.tests.iter().map(|name| format!("{}_p_value", name)));
+        println!("{}", header.join(&d.to_string()));
+    }
+
+    for (((label, exp, exp_caveat), diff), data) in exps.iter().zip(diffs).zip(&datasets[1..]) {
+        if let Format::Delimited(d) = opt.format {
+            for caveat in ctrl_caveat.iter().chain(exp_caveat.iter()) {
+                eprintln!("{}: {}", label, caveat);
+            }
+            let mut row = vec![
+                quote_csv(label, d),
+                exp.n.to_string(),
+                exp.mean.to_string(),
+                exp.std_dev().to_string(),
+                diff.delta.to_string(),
+                diff.relative_delta.to_string(),
+                diff.p_value.to_string(),
+                diff.is_significant().to_string(),
+            ];
+            if opt.spread {
+                // The variance ratio is undefined if the control's variance is zero.
+                let (ratio, p_value) =
+                    diff.spread.map_or((f64::NAN, f64::NAN), |vr| (vr.ratio, vr.p_value));
+                row.extend([ratio.to_string(), p_value.to_string()]);
+            }
+            for test in &tests {
+                row.push(test.test(&datasets[0], data, options)?.p_value.to_string());
+            }
+            println!("{}", row.join(&d.to_string()));
+            continue;
+        }
+
+        println!("{}:", label);
+        for caveat in ctrl_caveat.iter().chain(exp_caveat.iter()) {
+            println!("\t{}", caveat);
+        }
+        for line in diff.report(&ctrl, exp).lines() {
+            println!("\t{}", line);
+        }
+        for test in &tests {
+            let outcome = test.test(&datasets[0], data, options)?;
+            let significance =
+                if outcome.is_significant() { "significant" } else { "not significant" };
+            println!("\t{}: {} (p = {}).", test.name(), significance, format_p(outcome.p_value));
+        }
+        if !diff.is_significant() {
+            println!();
         }
     }
 
@@ -64,14 +225,317 @@ fn main() -> Result<(), Box<dyn Error>> {
         Page::single(&plots).save(&path)?;
     }
 
+    if let Some(path) = opt.histogram {
+        save_histogram(&path, &all_data)?;
+    }
+
+    if let Some(path) = opt.power_curve {
+        save_power_curve(&path, &ctrl, &summaries, confidence)?;
+    }
+
+    Ok(())
+}
+
+fn parse_correction(s: &str) -> Result<Correction, String> {
+    match s {
+        "holm" => Ok(Correction::Holm),
+        "bonferroni" => Ok(Correction::Bonferroni),
+        "bh" => Ok(Correction::BenjaminiHochberg),
+        _ => Err(format!("unknown correction: {}", s)),
+    }
+}
+
+fn parse_format(s: &str) -> Result<Format, String> {
+    match s {
+        "text" => Ok(Format::Text),
+        "csv" => Ok(Format::Delimited(',')),
+        "tsv" => Ok(Format::Delimited('\t')),
+        _ => Err(format!("unknown format: {}", s)),
+    }
+}
+
+fn parse_non_finite(s: &str) -> Result<NonFinitePolicy, String> {
+    match s {
+        "strict" => Ok(NonFinitePolicy::Strict),
+        "skip" => Ok(NonFinitePolicy::Skip),
+        "count" => Ok(NonFinitePolicy::Count),
+        _ => Err(format!("unknown non-finite policy: {}", s)),
+    }
+}
+
+fn save_histogram(path: &str, data: &[Vec<f64>]) -> Result<(), Box<dyn Error>> {
+    // Bin all the data sets identically, so they can be overlaid.
+    let pooled: Vec<f64> = data.iter().flatten().copied().collect();
+    let edges = Histogram::new(&pooled, Binning::FreedmanDiaconis)?.edges;
+
+    let colors = ["#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b"];
+    let mut view = ContinuousView::new();
+    for (values, color) in data.iter().zip(colors.iter().cycle()) {
+        let bins = HistogramBins::Bounds(edges.clone());
+        let h = plotlib::repr::Histogram::from_slice(values, bins);
+        view = view.add(h.style(&BoxStyle::new().fill(*color)));
+    }
+    Page::single(&view).save(path)?;
+    Ok(())
+}
+
+fn save_power_curve(
+    path: &str,
+    ctrl: &Summary,
+    exps: &[Summary],
+    confidence: Confidence,
+) -> Result<(), Box<dyn Error>> {
+    // Plot all the curves over the same range, up to twice the largest minimum detectable effect.
+    let mut max = 0.0f64;
+    for exp in exps {
+        max = max.max(minimum_detectable_effect(ctrl, exp, confidence, 0.8)?.delta * 2.0);
+    }
+    let deltas: Vec<f64> = (0..=50).map(|i| max * f64::from(i) / 50.0).collect();
+
+    let colors = ["#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b"];
+    let mut view =
+        ContinuousView::new().x_label("difference in means").y_label("power").y_range(0.0, 1.0);
+    for (exp, color) in exps.iter().zip(colors.iter().cycle()) {
+        let curve = power_curve(ctrl, exp, confidence, &deltas)?;
+        view = view.add(Plot::new(curve).line_style(LineStyle::new().colour(*color)));
+    }
+    Page::single(&view).save(path)?;
     Ok(())
 }
 
-fn read_file(path: &Path) -> Result<(Vec<f64>, Summary), Box<dyn Error>> {
-    let mut values = vec![];
-    for l in BufReader::new(File::open(path)?).lines() {
-        values.push(l?.parse()?);
+fn normality_caveat(label: &str, data: &[f64], confidence: Confidence) -> Option<String> {
+    let sw = shapiro_wilk(data, confidence).ok()?;
+    if sw.is_normal() {
+        return None;
+    }
+    Some(format!(
+        "Caveat: {} may not be normally distributed (Shapiro-Wilk p = {}).",
+        label,
+        format_p(sw.p_value),
+    ))
+}
+
+fn format_p(p: f64) -> String {
+    format!("{:.3}", p).trim_start_matches('0').to_string()
+}
+
+fn parse_test(s: &str) -> Result<String, String> {
+    match hypothesis::by_name(s) {
+        Some(_) => Ok(s.to_string()),
+        None => Err(format!("unknown test: {}", s)),
+    }
+}
+
+fn parse_unit(s: &str) -> Result<Unit, String> {
+    s.parse().map_err(|_| format!("unknown unit: {}", s))
+}
+
+fn parse_column(s: &str) -> Result<Column, String> {
+    match s.parse::<usize>() {
+        Ok(0) => Err("columns are numbered starting at 1".to_string()),
+        Ok(i) => Ok(Column::Index(i - 1)),
+        Err(_) => Ok(Column::Name(s.to_string())),
+    }
+}
+
+fn parse_delimiter(s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (s, chars.next(), chars.next()) {
+        ("\\t" | "tab", _, _) => Ok('\t'),
+        (_, Some(c), None) if c != '"' => Ok(c),
+        _ => Err(format!("invalid delimiter: {}", s)),
+    }
+}
+
+/// A labeled data set and its summary.
+type DataSet = (String, Vec<f64>, Summary);
+
+/// Read the labeled data sets from the given file, which has one unless it is a JSON object.
+fn read_file(path: &Path, opt: &Opt) -> Result<Vec<DataSet>, Box<dyn Error>> {
+    let mut text = String::new();
+    if path == Path::new("-") {
+        io::stdin().read_to_string(&mut text)?;
+    } else {
+        File::open(path)?.read_to_string(&mut text)?;
+    }
+
+    let label = path.to_string_lossy();
+    let extension = path.extension().and_then(|e| e.to_str());
+    let data_sets = if opt.json || extension == Some("json") {
+        let JsonDataSets(data_sets) = serde_json::from_str(&text)?;
+        data_sets
+            .into_iter()
+            .map(|(l, values)| {
+                let values =
+                    values.iter().map(|v| parse_json(v, opt.unit)).collect::<Result<_, _>>()?;
+                Ok((l.unwrap_or_else(|| label.to_string()), values))
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?
+    } else {
+        vec![(label.to_string(), read_lines(&text, extension, opt)?)]
+    };
+
+    data_sets
+        .into_iter()
+        .map(|(label, mut values)| {
+            let (summary, skipped) = Summary::screened(values.iter().copied(), opt.non_finite)?;
+            if skipped > 0 {
+                eprintln!("{}: skipped {} NaN or infinite values", label, skipped);
+            }
+            values.retain(|v| v.is_finite());
+            Ok((label, values, summary))
+        })
+        .collect()
+}
+
+/// Read the measurements from the lines of a plain text or CSV file.
+fn read_lines(text: &str, extension: Option<&str>, opt: &Opt) -> Result<Vec<f64>, Box<dyn Error>> {
+    let lines: Vec<String> = text.lines().map(String::from).collect();
+
+    // Select the CSV delimiter, if the file is a CSV file.
+    let delimiter = match (opt.delimiter, extension) {
+        (Some(d), _) => Some(d),
+        (None, Some("tsv")) => Some('\t'),
+        (None, Some("csv")) => Some(','),
+        (None, _) if opt.csv || opt.column.is_some() => Some(','),
+        (None, _) => None,
+    };
+
+    let mut values: Vec<f64> = vec![];
+    match delimiter {
+        Some(delimiter) => {
+            for field in csv_column(&lines, delimiter, opt.column.as_ref(), opt.unit)? {
+                values.push(parse_measurement(&field, opt.unit)?);
+            }
+        }
+        None => {
+            for l in &lines {
+                values.push(parse_measurement(l, opt.unit)?);
+            }
+        }
+    }
+    Ok(values)
+}
+
+/// Parse a measurement from a JSON number or string.
+fn parse_json(value: &Value, unit: Unit) -> Result<f64, Box<dyn Error>> {
+    match value {
+        Value::Number(n) => n.as_f64().ok_or_else(|| format!("invalid measurement: {}", n).into()),
+        Value::String(s) => parse_measurement(s, unit),
+        v => Err(format!("invalid measurement: {}", v).into()),
+    }
+}
+
+/// The data sets in a JSON file: either an unlabeled array of measurements or an object mapping
+/// labels to arrays of measurements, in the order they appear in the file.
+struct JsonDataSets(Vec<(Option<String>, Vec<Value>)>);
+
+impl<'de> Deserialize<'de> for JsonDataSets {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DataSetsVisitor;
+
+        impl<'de> Visitor<'de> for DataSetsVisitor {
+            type Value = JsonDataSets;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an array of measurements or an object of labeled arrays")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut values = vec![];
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(JsonDataSets(vec![(None, values)]))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut data_sets = vec![];
+                while let Some((label, values)) = map.next_entry()? {
+                    data_sets.push((Some(label), values));
+                }
+                Ok(JsonDataSets(data_sets))
+            }
+        }
+
+        deserializer.deserialize_any(DataSetsVisitor)
+    }
+}
+
+/// Parse a measurement, accepting plain numbers as-is and converting durations with units into the
+/// common unit.
+fn parse_measurement(s: &str, unit: Unit) -> Result<f64, Box<dyn Error>> {
+    match s.parse::<f64>() {
+        Ok(v) => Ok(v),
+        Err(e) => Ok(s.parse::<Duration>().map_err(|_| e)?.as_unit(unit)),
+    }
+}
+
+/// Select the given column's fields from the non-blank lines of a CSV file. Columns selected by
+/// name are found in the header row; columns selected by number skip the first row if it is not a
+/// measurement, i.e. a header.
+fn csv_column(
+    lines: &[String],
+    delimiter: char,
+    column: Option<&Column>,
+    unit: Unit,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut rows = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| (i + 1, split_csv(l, delimiter)))
+        .peekable();
+
+    let index = match column {
+        Some(Column::Name(name)) => {
+            let (_, header) = rows.next().ok_or("missing CSV header")?;
+            header.iter().position(|h| h == name).ok_or(format!("no column named {}", name))?
+        }
+        Some(Column::Index(i)) => *i,
+        None => 0,
+    };
+    if !matches!(column, Some(Column::Name(_))) {
+        if let Some((_, header)) = rows.peek() {
+            if header.get(index).is_some_and(|f| parse_measurement(f, unit).is_err()) {
+                rows.next();
+            }
+        }
+    }
+
+    rows.map(|(line, row)| match row.get(index) {
+        Some(field) => Ok(field.clone()),
+        None => Err(format!("line {} has no column {}", line, index + 1).into()),
+    })
+    .collect()
+}
+
+/// Quote a field of a CSV file, if it contains the delimiter, quotes, or line breaks.
+fn quote_csv(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Split a line of a CSV file into its fields, removing any quotes and surrounding whitespace.
+fn split_csv(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
     }
-    let summary = values.iter().collect();
-    Ok((values, summary))
+    fields.push(field);
+    fields.iter().map(|f| f.trim().to_string()).collect()
 }
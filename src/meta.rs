@@ -0,0 +1,316 @@
+//! Combinations of the evidence from independent tests of the same hypothesis, such as the same
+//! comparison repeated on several days or machines.
+
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal};
+
+use crate::{Confidence, Difference, Error};
+
+/// The result of combining the p-values of several independent tests.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Combined {
+    /// The test statistic of the combined test: chi-squared for [fisher] and standard normal for
+    /// [stouffer].
+    pub statistic: f64,
+
+    /// The combined p-value.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl Combined {
+    /// Whether or not the combined evidence is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Combine the p-values of several independent tests using Fisher's method, which tests whether
+/// the null hypothesis is false in any of them. The confidence level must be in the range `(0,
+/// 100)`.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::meta::fisher;
+///
+/// // None of the runs is significant on its own, but together they are.
+/// assert!(fisher(&[0.08, 0.12, 0.05, 0.2], Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn fisher(p_values: &[f64], confidence: Confidence) -> Result<Combined, Error> {
+    let alpha = confidence.checked_alpha()?;
+    check(p_values)?;
+
+    // Twice the sum of the negative log p-values is chi-squared distributed with two degrees of
+    // freedom for each test.
+    let statistic = -2.0 * p_values.iter().map(|p| p.ln()).sum::<f64>();
+    let dist_chi = ChiSquared::new(2.0 * p_values.len() as f64).unwrap();
+    let p_value = 1.0 - dist_chi.cdf(statistic);
+
+    Ok(Combined { statistic, p_value, alpha })
+}
+
+/// Combine the p-values of several independent tests using Stouffer's method, which tests whether
+/// the null hypothesis is false on average. The confidence level must be in the range `(0, 100)`.
+///
+/// The p-values should be one-tailed, all in the same direction. If the sample sizes of the tests
+/// are given, each test is weighted by the square root of its sample size, so larger tests
+/// contribute more of the evidence.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::meta::stouffer;
+///
+/// let p_values = [0.08, 0.12, 0.05, 0.2];
+/// assert!(stouffer(&p_values, None, Confidence::P95)?.is_significant());
+/// assert!(stouffer(&p_values, Some(&[10.0, 20.0, 40.0, 80.0]), Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn stouffer(
+    p_values: &[f64],
+    sizes: Option<&[f64]>,
+    confidence: Confidence,
+) -> Result<Combined, Error> {
+    let alpha = confidence.checked_alpha()?;
+    check(p_values)?;
+    let weights: Vec<f64> = match sizes {
+        Some(sizes) => {
+            if sizes.len() != p_values.len() {
+                return Err(Error::MismatchedLengths);
+            }
+            if sizes.iter().any(|n| !n.is_finite()) {
+                return Err(Error::NonFinite);
+            }
+            if sizes.iter().any(|&n| n <= 0.0) {
+                return Err(Error::NonPositive);
+            }
+            sizes.iter().map(|n| n.sqrt()).collect()
+        }
+        None => vec![1.0; p_values.len()],
+    };
+
+    // Convert each p-value to a z-score and calculate their weighted sum, which is standard normal
+    // after scaling.
+    let dist_norm = Normal::new(0.0, 1.0).unwrap();
+    let z: f64 =
+        p_values.iter().zip(weights.iter()).map(|(p, w)| w * dist_norm.inverse_cdf(1.0 - p)).sum();
+    let statistic = z / weights.iter().map(|w| w * w).sum::<f64>().sqrt();
+    let p_value = 1.0 - dist_norm.cdf(statistic);
+
+    Ok(Combined { statistic, p_value, alpha })
+}
+
+/// The result of a random-effects meta-analysis of several differences.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RandomEffects {
+    /// The pooled estimate of the mean difference.
+    pub delta: f64,
+
+    /// The standard error of the pooled estimate.
+    pub std_err: f64,
+
+    /// The lower bound of the confidence interval for the mean difference.
+    pub ci_low: f64,
+
+    /// The upper bound of the confidence interval for the mean difference.
+    pub ci_high: f64,
+
+    /// The estimated variance of the true differences between the studies, e.g. between machines.
+    pub tau_squared: f64,
+
+    /// Cochran's Q statistic for heterogeneity between the studies.
+    pub q: f64,
+
+    /// The p-value for the null hypothesis that the studies have the same true difference.
+    pub heterogeneity_p_value: f64,
+
+    /// The percentage of the variation between the studies which is due to heterogeneity rather
+    /// than chance, in the range `[0, 100]`.
+    pub i_squared: f64,
+
+    /// The two-tailed p-value for the null hypothesis that the mean difference is zero.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl RandomEffects {
+    /// Whether or not the pooled difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Combine several differences, e.g. the same comparison on several machines, using the
+/// DerSimonian-Laird random-effects model, which allows the true difference to vary between them.
+/// The confidence level must be in the range `(0, 100)`.
+///
+/// Each difference is weighted by the inverse of the sum of its squared standard error and the
+/// estimated variance between the differences, so no single precise difference dominates when the
+/// differences disagree.
+///
+/// ```
+/// use nanostat::meta::random_effects;
+/// use nanostat::{Confidence, Summary};
+///
+/// let control: Summary = [10.0, 11.0, 12.0, 11.0, 10.0].iter().collect();
+/// let a: Summary = [11.0, 12.0, 13.0, 12.0, 11.5].iter().collect();
+/// let b: Summary = [11.5, 12.0, 12.5, 13.5, 11.0].iter().collect();
+/// let diffs = [control.compare(&a, Confidence::P95), control.compare(&b, Confidence::P95)];
+/// assert!(random_effects(&diffs, Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn random_effects(
+    diffs: &[Difference],
+    confidence: Confidence,
+) -> Result<RandomEffects, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if diffs.len() < 2 {
+        return Err(Error::InsufficientSamples);
+    }
+    if diffs.iter().any(|d| !(d.delta.is_finite() && d.std_err.is_finite())) {
+        return Err(Error::NonFinite);
+    }
+    if diffs.iter().any(|d| d.std_err == 0.0) {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Calculate the fixed-effect estimate and the heterogeneity around it.
+    let df = (diffs.len() - 1) as f64;
+    let weights: Vec<f64> = diffs.iter().map(|d| d.std_err.powf(-2.0)).collect();
+    let w: f64 = weights.iter().sum();
+    let fixed = diffs.iter().zip(weights.iter()).map(|(d, w_i)| w_i * d.delta).sum::<f64>() / w;
+    let q =
+        diffs.iter().zip(weights.iter()).map(|(d, w_i)| w_i * (d.delta - fixed).powf(2.0)).sum();
+    let heterogeneity_p_value = 1.0 - ChiSquared::new(df).unwrap().cdf(q);
+    let i_squared = if q > df { (q - df) / q * 100.0 } else { 0.0 };
+
+    // Estimate the variance between the differences using the method of moments.
+    let c = w - weights.iter().map(|w_i| w_i * w_i).sum::<f64>() / w;
+    let tau_squared = ((q - df) / c).max(0.0);
+
+    // Calculate the random-effects estimate and its confidence interval.
+    let weights: Vec<f64> =
+        diffs.iter().map(|d| 1.0 / (d.std_err.powf(2.0) + tau_squared)).collect();
+    let w: f64 = weights.iter().sum();
+    let delta = diffs.iter().zip(weights.iter()).map(|(d, w_i)| w_i * d.delta).sum::<f64>() / w;
+    let std_err = w.sqrt().recip();
+    let dist_norm = Normal::new(0.0, 1.0).unwrap();
+    let z = dist_norm.inverse_cdf(1.0 - alpha / 2.0);
+    let p_value = dist_norm.cdf(-(delta / std_err).abs()) * 2.0;
+
+    Ok(RandomEffects {
+        delta,
+        std_err,
+        ci_low: delta - z * std_err,
+        ci_high: delta + z * std_err,
+        tau_squared,
+        q,
+        heterogeneity_p_value,
+        i_squared,
+        p_value,
+        alpha,
+    })
+}
+
+/// Check that the p-values can be combined.
+fn check(p_values: &[f64]) -> Result<(), Error> {
+    if p_values.is_empty() {
+        return Err(Error::InsufficientSamples);
+    }
+    if let Some(&p) = p_values.iter().find(|&&p| !(p > 0.0 && p <= 1.0)) {
+        return Err(Error::InvalidPValue(p));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+    use crate::Summary;
+
+    const P: [f64; 4] = [0.08, 0.12, 0.05, 0.2];
+
+    #[test]
+    fn fisher_method() {
+        let c = fisher(&P, Confidence::P95).unwrap();
+        assert_relative_eq!(c.statistic, 18.502324732992876, epsilon = 1e-12);
+        assert_relative_eq!(c.p_value, 0.017760168272597662, epsilon = 1e-9);
+        assert!(c.is_significant());
+
+        assert_relative_eq!(fisher(&[1.0], Confidence::P95).unwrap().p_value, 1.0);
+    }
+
+    #[test]
+    fn stouffer_method() {
+        let c = stouffer(&P, None, Confidence::P95).unwrap();
+        assert_relative_eq!(c.statistic, 2.533266606450055, epsilon = 1e-9);
+        assert_relative_eq!(c.p_value, 0.005650248340903577, epsilon = 1e-9);
+        assert!(c.is_significant());
+
+        let c = stouffer(&P, Some(&[10.0, 20.0, 40.0, 80.0]), Confidence::P95).unwrap();
+        assert_relative_eq!(c.statistic, 2.25586443729928, epsilon = 1e-9);
+        assert_relative_eq!(c.p_value, 0.012039561155345265, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn random_effects_model() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let base = a.compare(&a, Confidence::P95);
+        let diffs: Vec<Difference> = [(1.2, 0.5), (0.4, 0.3), (2.0, 0.6), (0.9, 0.4)]
+            .iter()
+            .map(|&(delta, std_err)| Difference { delta, std_err, ..base })
+            .collect();
+        let re = random_effects(&diffs, Confidence::P95).unwrap();
+
+        assert_relative_eq!(re.q, 6.428877381409027, epsilon = 1e-12);
+        assert_relative_eq!(re.heterogeneity_p_value, 0.0925100020982347, epsilon = 1e-9);
+        assert_relative_eq!(re.i_squared, 53.33555421860472, epsilon = 1e-9);
+        assert_relative_eq!(re.tau_squared, 0.2087757882444531, epsilon = 1e-12);
+        assert_relative_eq!(re.delta, 0.9914713016283755, epsilon = 1e-12);
+        assert_relative_eq!(re.std_err, 0.3162824526610429, epsilon = 1e-12);
+        assert_relative_eq!(re.ci_low, 0.3715690854707367, epsilon = 1e-9);
+        assert_relative_eq!(re.ci_high, 1.611373517786014, epsilon = 1e-9);
+        assert_relative_eq!(re.p_value, 0.0017199157373512446, epsilon = 1e-9);
+        assert!(re.is_significant());
+
+        // Identical differences have no heterogeneity.
+        let re = random_effects(&[diffs[0], diffs[0]], Confidence::P95).unwrap();
+        assert_relative_eq!(re.tau_squared, 0.0);
+        assert_relative_eq!(re.i_squared, 0.0);
+        assert_relative_eq!(re.delta, 1.2);
+    }
+
+    #[test]
+    fn meta_errors() {
+        assert_eq!(fisher(&[], Confidence::P95).unwrap_err(), Error::InsufficientSamples);
+        assert_eq!(fisher(&[0.5, 0.0], Confidence::P95).unwrap_err(), Error::InvalidPValue(0.0));
+        assert_eq!(
+            stouffer(&[0.5, 1.5], None, Confidence::P95).unwrap_err(),
+            Error::InvalidPValue(1.5)
+        );
+        assert_eq!(
+            stouffer(&P, Some(&[1.0]), Confidence::P95).unwrap_err(),
+            Error::MismatchedLengths
+        );
+        assert_eq!(
+            stouffer(&P, Some(&[1.0, 2.0, 0.0, 1.0]), Confidence::P95).unwrap_err(),
+            Error::NonPositive
+        );
+
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let diff = a.compare(&a, Confidence::P95);
+        assert_eq!(
+            random_effects(&[diff], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Control charts, which monitor a stream of measurements, e.g. the results of each nightly
+//! benchmark run, and signal when their mean drifts away from a baseline.
+
+use crate::{Error, Summary};
+
+/// The state of a control chart after a batch of measurements.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Signal {
+    /// The mean is consistent with the baseline.
+    InControl,
+    /// The mean has drifted above the baseline.
+    High,
+    /// The mean has drifted below the baseline.
+    Low,
+}
+
+/// A two-sided tabular CUSUM chart, which accumulates the deviations of each batch's mean from the
+/// baseline's and signals when either sum exceeds the decision interval. It detects small,
+/// persistent shifts much sooner than checking each batch separately.
+///
+/// The deviations are standardized by each batch's standard error, given the baseline's standard
+/// deviation, so batches may have different sizes.
+///
+/// ```
+/// use nanostat::monitoring::{Cusum, Signal};
+/// use nanostat::Summary;
+///
+/// let baseline: Summary = [10.0, 10.5, 9.5, 10.2, 9.8, 10.1, 9.9, 10.0].iter().collect();
+/// let mut chart = Cusum::new(&baseline, 0.5, 5.0)?;
+/// let nightly = [10.1, 9.9, 10.2, 10.4, 10.5, 10.3, 10.6, 10.4];
+/// let signals: Vec<Signal> = nightly.iter().map(|&x| chart.push(&[x].iter().collect())).collect();
+/// assert_eq!(signals[0], Signal::InControl);
+/// assert_eq!(signals[7], Signal::High);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cusum {
+    mean: f64,
+    std_dev: f64,
+    allowance: f64,
+    interval: f64,
+    high: f64,
+    low: f64,
+}
+
+impl Cusum {
+    /// Create a chart which monitors for shifts from the baseline's mean, given the allowance (or
+    /// reference value) and decision interval in units of standard error. Typical values are `0.5`
+    /// and `5.0`, which quickly detect shifts of one standard error while rarely signalling
+    /// falsely. The allowance must be non-negative and the decision interval positive.
+    pub fn new(baseline: &Summary, allowance: f64, interval: f64) -> Result<Cusum, Error> {
+        baseline.check()?;
+        if baseline.variance == 0.0 {
+            return Err(Error::ZeroVariance);
+        }
+        if !(allowance.is_finite() && allowance >= 0.0) {
+            return Err(Error::InvalidLimit(allowance));
+        }
+        if !(interval.is_finite() && interval > 0.0) {
+            return Err(Error::InvalidLimit(interval));
+        }
+        Ok(Cusum {
+            mean: baseline.mean,
+            std_dev: baseline.std_dev(),
+            allowance,
+            interval,
+            high: 0.0,
+            low: 0.0,
+        })
+    }
+
+    /// Add a batch of measurements to the chart and return its state.
+    pub fn push(&mut self, batch: &Summary) -> Signal {
+        let z = (batch.mean - self.mean) / (self.std_dev / batch.n.sqrt());
+        self.high = (self.high + z - self.allowance).max(0.0);
+        self.low = (self.low - z - self.allowance).max(0.0);
+        self.signal()
+    }
+
+    /// The state of the chart given the batches so far.
+    #[must_use]
+    pub fn signal(&self) -> Signal {
+        if self.high > self.interval {
+            Signal::High
+        } else if self.low > self.interval {
+            Signal::Low
+        } else {
+            Signal::InControl
+        }
+    }
+
+    /// The upper and lower cumulative sums.
+    #[must_use]
+    pub const fn sums(&self) -> (f64, f64) {
+        (self.high, self.low)
+    }
+
+    /// Reset the cumulative sums, e.g. after responding to a signal.
+    pub const fn reset(&mut self) {
+        (self.high, self.low) = (0.0, 0.0);
+    }
+}
+
+/// An exponentially weighted moving average (EWMA) chart, which smooths the standardized
+/// deviations of each batch's mean from the baseline's and signals when the average leaves the
+/// control limits. Like [Cusum], it detects small, persistent shifts, but it weights recent
+/// batches more heavily.
+///
+/// ```
+/// use nanostat::monitoring::{Ewma, Signal};
+/// use nanostat::Summary;
+///
+/// let baseline: Summary = [10.0, 10.5, 9.5, 10.2, 9.8, 10.1, 9.9, 10.0].iter().collect();
+/// let mut chart = Ewma::new(&baseline, 0.2, 3.0)?;
+/// assert_eq!(chart.push(&[10.1].iter().collect()), Signal::InControl);
+/// for _ in 0..5 {
+///     chart.push(&[9.4].iter().collect());
+/// }
+/// assert_eq!(chart.signal(), Signal::Low);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ewma {
+    mean: f64,
+    std_dev: f64,
+    lambda: f64,
+    width: f64,
+    average: f64,
+    batches: i32,
+}
+
+impl Ewma {
+    /// Create a chart which monitors for shifts from the baseline's mean, given the weight of each
+    /// new batch, in the range `(0, 1]`, and the width of the control limits in units of the
+    /// average's standard deviation. Typical values are `0.2` and `3.0`. The width must be
+    /// positive.
+    pub fn new(baseline: &Summary, lambda: f64, width: f64) -> Result<Ewma, Error> {
+        baseline.check()?;
+        if baseline.variance == 0.0 {
+            return Err(Error::ZeroVariance);
+        }
+        if !(lambda > 0.0 && lambda <= 1.0) {
+            return Err(Error::InvalidLimit(lambda));
+        }
+        if !(width.is_finite() && width > 0.0) {
+            return Err(Error::InvalidLimit(width));
+        }
+        Ok(Ewma {
+            mean: baseline.mean,
+            std_dev: baseline.std_dev(),
+            lambda,
+            width,
+            average: 0.0,
+            batches: 0,
+        })
+    }
+
+    /// Add a batch of measurements to the chart and return its state.
+    pub fn push(&mut self, batch: &Summary) -> Signal {
+        let z = (batch.mean - self.mean) / (self.std_dev / batch.n.sqrt());
+        self.average = self.lambda * z + (1.0 - self.lambda) * self.average;
+        self.batches = self.batches.saturating_add(1);
+        self.signal()
+    }
+
+    /// The state of the chart given the batches so far.
+    #[must_use]
+    pub fn signal(&self) -> Signal {
+        let limit = self.limit();
+        if self.average > limit {
+            Signal::High
+        } else if self.average < -limit {
+            Signal::Low
+        } else {
+            Signal::InControl
+        }
+    }
+
+    /// The moving average of the standardized deviations from the baseline's mean.
+    #[must_use]
+    pub const fn average(&self) -> f64 {
+        self.average
+    }
+
+    /// The control limit for the moving average, which widens as batches are added until it
+    /// reaches its asymptotic value.
+    #[must_use]
+    pub fn limit(&self) -> f64 {
+        let l = self.lambda;
+        let decay = 1.0 - (1.0 - l).powi(2 * self.batches);
+        self.width * (l / (2.0 - l) * decay).sqrt()
+    }
+
+    /// Reset the moving average, e.g. after responding to a signal.
+    pub const fn reset(&mut self) {
+        (self.average, self.batches) = (0.0, 0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn baseline() -> Summary {
+        [10.0, 10.5, 9.5, 10.2, 9.8, 10.1, 9.9, 10.0].iter().collect()
+    }
+
+    #[test]
+    fn cusum() {
+        let mut chart = Cusum::new(&baseline(), 0.5, 5.0).unwrap();
+        let sd = baseline().std_dev();
+
+        // A batch of four measurements one standard deviation high is two standard errors high.
+        let batch = Summary::from_parts(4.0, 10.0 + sd, 0.1).unwrap();
+        assert_eq!(chart.push(&batch), Signal::InControl);
+        assert_relative_eq!(chart.sums().0, 1.5, epsilon = 1e-12);
+        assert_relative_eq!(chart.sums().1, 0.0);
+        assert_eq!(chart.push(&batch), Signal::InControl);
+        assert_eq!(chart.push(&batch), Signal::InControl);
+        assert_eq!(chart.push(&batch), Signal::High);
+
+        chart.reset();
+        assert_eq!(chart.signal(), Signal::InControl);
+        let low = Summary::from_parts(4.0, 10.0 - 2.0 * sd, 0.1).unwrap();
+        assert_eq!(chart.push(&low), Signal::InControl);
+        assert_eq!(chart.push(&low), Signal::Low);
+    }
+
+    #[test]
+    fn ewma() {
+        let mut chart = Ewma::new(&baseline(), 0.2, 3.0).unwrap();
+        let sd = baseline().std_dev();
+        assert_relative_eq!(chart.limit(), 0.0);
+
+        // A single measurement one standard deviation high barely moves the average.
+        assert_eq!(chart.push(&[10.0 + sd].iter().collect()), Signal::InControl);
+        assert_relative_eq!(chart.average(), 0.2, epsilon = 1e-12);
+        assert_relative_eq!(chart.limit(), 0.6, epsilon = 1e-12);
+
+        chart.reset();
+        let batch = Summary::from_parts(4.0, 10.0 + sd, 0.1).unwrap();
+        assert_eq!(chart.push(&batch), Signal::InControl);
+        assert_eq!(chart.push(&batch), Signal::InControl);
+        assert_eq!(chart.push(&batch), Signal::High);
+        assert_relative_eq!(chart.average(), 0.976, epsilon = 1e-12);
+        assert_relative_eq!(chart.limit(), 0.8589854480723175, epsilon = 1e-12);
+
+        // Large shifts signal immediately.
+        chart.reset();
+        let low = Summary::from_parts(4.0, 10.0 - 2.0 * sd, 0.1).unwrap();
+        assert_eq!(chart.push(&low), Signal::Low);
+    }
+
+    #[test]
+    fn chart_errors() {
+        let flat: Summary = [1.0, 1.0].iter().collect();
+        assert_eq!(Cusum::new(&flat, 0.5, 5.0).unwrap_err(), Error::ZeroVariance);
+        assert_eq!(Cusum::new(&baseline(), -0.5, 5.0).unwrap_err(), Error::InvalidLimit(-0.5));
+        assert_eq!(Cusum::new(&baseline(), 0.5, 0.0).unwrap_err(), Error::InvalidLimit(0.0));
+        assert_eq!(Ewma::new(&baseline(), 1.5, 3.0).unwrap_err(), Error::InvalidLimit(1.5));
+        assert_eq!(Ewma::new(&baseline(), 0.0, 3.0).unwrap_err(), Error::InvalidLimit(0.0));
+        assert!(matches!(Ewma::new(&baseline(), 0.2, f64::NAN), Err(Error::InvalidLimit(_))));
+        assert_eq!(Ewma::new(&Summary::EMPTY, 0.2, 3.0).unwrap_err(), Error::InsufficientSamples);
+    }
+}
@@ -0,0 +1,1177 @@
+//! Non-parametric tests which operate on raw measurements rather than summaries.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use statrs::distribution::{Binomial, ChiSquared, ContinuousCDF, DiscreteCDF, Normal, StudentsT};
+
+use crate::resampling::{binomial, combinations};
+use crate::{Confidence, EffectMagnitude, Error};
+
+/// The default maximum number of arrangements of the measurements which [mann_whitney] and
+/// [wilcoxon] will enumerate to calculate an exact p-value, rather than using a normal
+/// approximation.
+pub const EXACT_LIMIT: usize = 10_000;
+
+/// The result of a Mann-Whitney U test between two data sets.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MannWhitney {
+    /// The U statistic for the first sample: the number of pairs in which the first sample's
+    /// measurement is larger, counting ties as one half.
+    pub u: f64,
+
+    /// The two-tailed p-value for the test. Unless `exact`, this uses a normal approximation with
+    /// tie and continuity corrections.
+    pub p_value: f64,
+
+    /// Whether the p-value was calculated by enumerating every assignment of the ranks to the two
+    /// samples, rather than by a normal approximation.
+    pub exact: bool,
+
+    /// The rank-biserial correlation, in the range `[-1, 1]`. Positive values indicate that
+    /// measurements in the first sample tend to be larger than those in the second.
+    pub effect_size: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl MannWhitney {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Compare two data sets using a two-tailed Mann-Whitney U test. Unlike Welch's t-test, this makes
+/// no assumptions about the distribution of the measurements, which makes it suitable for skewed
+/// data like latencies.
+///
+/// For small samples, where the normal approximation is unreliable, the p-value is calculated
+/// exactly; see [mann_whitney_with].
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::nonparametric::mann_whitney;
+///
+/// let mw = mann_whitney(&[1.0, 2.0, 3.0, 4.0, 5.0], &[6.0, 7.0, 8.0, 9.0, 10.0], Confidence::P95)?;
+/// assert!(mw.is_significant());
+/// assert!(mw.exact);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn mann_whitney(a: &[f64], b: &[f64], confidence: Confidence) -> Result<MannWhitney, Error> {
+    mann_whitney_with(a, b, confidence, EXACT_LIMIT)
+}
+
+/// Compare two data sets using a two-tailed Mann-Whitney U test, calculating the p-value exactly
+/// if there are no more than `exact_limit` ways of assigning the measurements to the two samples.
+/// An `exact_limit` of zero always uses the normal approximation.
+pub fn mann_whitney_with(
+    a: &[f64],
+    b: &[f64],
+    confidence: Confidence,
+    exact_limit: usize,
+) -> Result<MannWhitney, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if a.is_empty() || b.is_empty() {
+        return Err(Error::InsufficientSamples);
+    }
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+
+    // Rank the pooled samples and sum the ranks of the first sample.
+    let pooled: Vec<f64> = a.iter().chain(b.iter()).copied().collect();
+    let (ranks, ties) = rank(&pooled)?;
+    let r1: f64 = ranks[..a.len()].iter().sum();
+
+    // Calculate the U statistic and its expected value under the null hypothesis.
+    let u = r1 - n1 * (n1 + 1.0) / 2.0;
+    let mu = n1 * n2 / 2.0;
+
+    // Calculate the standard deviation of U, corrected for ties.
+    let sigma = (n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)))).sqrt();
+    if sigma == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+
+    let exact = binomial(pooled.len(), a.len()) <= exact_limit as f64;
+    let p_value = if exact {
+        // Enumerate every assignment of the ranks to the first sample, counting those with a U
+        // statistic at least as extreme as the observed one, allowing for rounding error.
+        let threshold = (u - mu).abs() - 1e-9;
+        let (mut hits, mut count) = (0usize, 0usize);
+        combinations(pooled.len(), a.len(), |idx| {
+            count += 1;
+            let r1: f64 = idx.iter().map(|&i| ranks[i]).sum();
+            if (r1 - n1 * (n1 + 1.0) / 2.0 - mu).abs() >= threshold {
+                hits += 1;
+            }
+        });
+        hits as f64 / count as f64
+    } else {
+        // Calculate the p-value using the normal approximation with a continuity correction.
+        let z = ((u - mu).abs() - 0.5).max(0.0) / sigma;
+        (Normal::new(0.0, 1.0).unwrap().cdf(-z) * 2.0).min(1.0)
+    };
+
+    // Calculate the rank-biserial correlation.
+    let effect_size = 2.0 * u / (n1 * n2) - 1.0;
+
+    Ok(MannWhitney { u, p_value, exact, effect_size, alpha })
+}
+
+/// The result of a Brunner-Munzel test between two data sets.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BrunnerMunzel {
+    /// The W statistic for the test, which is positive if measurements in the second sample tend to
+    /// be larger than those in the first.
+    pub w: f64,
+
+    /// The degrees of freedom of the t-distribution used to approximate the distribution of W.
+    pub df: f64,
+
+    /// The two-tailed p-value for the test.
+    pub p_value: f64,
+
+    /// The estimated relative effect: the probability that a measurement from the first sample is
+    /// smaller than one from the second, plus half the probability that they are equal.
+    pub effect_size: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl BrunnerMunzel {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Compare two data sets using a two-tailed Brunner-Munzel test. Like [mann_whitney], this makes
+/// no assumptions about the distribution of the measurements, but like Welch's t-test, it does not
+/// assume the data sets have equal variances. Each data set must have at least two measurements.
+///
+/// The test is undefined if there is no variance in the placements of the measurements, e.g. if
+/// every measurement in one data set is smaller than every measurement in the other, in which case
+/// [Error::ZeroVariance] is returned.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::nonparametric::brunner_munzel;
+///
+/// let a = [1.0, 2.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 2.0, 4.0, 1.0, 1.0];
+/// let b = [3.0, 3.0, 4.0, 3.0, 1.0, 2.0, 3.0, 1.0, 1.0, 5.0, 4.0];
+/// assert!(brunner_munzel(&a, &b, Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn brunner_munzel(
+    a: &[f64],
+    b: &[f64],
+    confidence: Confidence,
+) -> Result<BrunnerMunzel, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if a.len() < 2 || b.len() < 2 {
+        return Err(Error::InsufficientSamples);
+    }
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+
+    // Rank the pooled samples and each sample on its own.
+    let pooled: Vec<f64> = a.iter().chain(b.iter()).copied().collect();
+    let (ranks, _) = rank(&pooled)?;
+    let (ranks_a, ranks_b) = ranks.split_at(a.len());
+    let (within_a, _) = rank(a)?;
+    let (within_b, _) = rank(b)?;
+
+    // Calculate the variances of the placements of each sample's measurements among the other's.
+    let placements = |pooled: &[f64], within: &[f64]| {
+        let n = pooled.len() as f64;
+        let mean = pooled.iter().sum::<f64>() / n;
+        let s: f64 = pooled
+            .iter()
+            .zip(within.iter())
+            .map(|(r, w)| (r - w - mean + (n + 1.0) / 2.0).powf(2.0))
+            .sum();
+        (mean, s / (n - 1.0))
+    };
+    let (mean_a, s_a) = placements(ranks_a, &within_a);
+    let (mean_b, s_b) = placements(ranks_b, &within_b);
+    let (v_a, v_b) = (n1 * s_a, n2 * s_b);
+    if v_a + v_b == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Calculate the statistic and the Welch-Satterthwaite degrees of freedom.
+    let w = n1 * n2 * (mean_b - mean_a) / ((n1 + n2) * (v_a + v_b).sqrt());
+    let df = (v_a + v_b).powf(2.0) / (v_a.powf(2.0) / (n1 - 1.0) + v_b.powf(2.0) / (n2 - 1.0));
+
+    // Calculate the p-value using a t-distribution.
+    let dist_st = StudentsT::new(0.0, 1.0, df).map_err(|_| Error::NonFinite)?;
+    let p_value = dist_st.cdf(-w.abs()) * 2.0;
+
+    // Calculate the relative effect.
+    let effect_size = (mean_b - (n2 + 1.0) / 2.0) / n1;
+
+    Ok(BrunnerMunzel { w, df, p_value, effect_size, alpha })
+}
+
+/// The result of an Anderson-Darling test for whether two data sets are drawn from the same
+/// distribution.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AndersonDarling {
+    /// The Anderson-Darling statistic, adjusted for ties.
+    pub a2: f64,
+
+    /// The standardized statistic, which is compared against the critical values of Scholz and
+    /// Stephens (1987).
+    pub statistic: f64,
+
+    /// The approximate p-value for the test, interpolated from the critical values. It is capped
+    /// to the range `[0.001, 0.25]`.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl AndersonDarling {
+    /// Whether or not the distributions differ significantly.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Test whether two data sets are drawn from the same distribution using the two-sample
+/// Anderson-Darling test. Unlike tests of location, this is sensitive to any difference in the
+/// distributions, including in their tails. The confidence level must be in the range `(0, 100)`.
+pub fn anderson_darling(
+    a: &[f64],
+    b: &[f64],
+    confidence: Confidence,
+) -> Result<AndersonDarling, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if a.is_empty() || b.is_empty() || a.len() + b.len() < 4 {
+        return Err(Error::InsufficientSamples);
+    }
+    if a.iter().chain(b.iter()).any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    let mut pooled: Vec<f64> = a.iter().chain(b.iter()).copied().collect();
+    pooled.sort_by(f64::total_cmp);
+    let mut distinct = pooled.clone();
+    distinct.dedup();
+    if distinct.len() < 2 {
+        return Err(Error::ZeroVariance);
+    }
+    let n = pooled.len() as f64;
+
+    // Calculate the statistic using midranks to account for ties.
+    let mut a2 = 0.0;
+    for sample in [a, b] {
+        let mut sorted = sample.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let n_i = sorted.len() as f64;
+
+        let mut inner = 0.0;
+        for z in &distinct {
+            let below = pooled.partition_point(|x| x < z) as f64;
+            let l = pooled.partition_point(|x| x <= z) as f64 - below;
+            let b_j = below + l / 2.0;
+            let at_most = sorted.partition_point(|x| x <= z) as f64;
+            let m_ij = at_most - (at_most - sorted.partition_point(|x| x < z) as f64) / 2.0;
+            inner += l / n * (n * m_ij - b_j * n_i).powf(2.0) / (b_j * (n - b_j) - n * l / 4.0);
+        }
+        a2 += inner / n_i;
+    }
+    a2 *= (n - 1.0) / n;
+
+    // Calculate the variance of the statistic under the null hypothesis.
+    let k = 2.0;
+    let h_sum = 1.0 / a.len() as f64 + 1.0 / b.len() as f64;
+    let (mut h, mut g) = (0.0, 0.0);
+    for i in (2..pooled.len()).rev() {
+        h += 1.0 / i as f64;
+        g += h / (pooled.len() - i + 1) as f64;
+    }
+    h += 1.0;
+    let c_a = (4.0 * g - 6.0) * (k - 1.0) + (10.0 - 6.0 * g) * h_sum;
+    let c_b = (2.0 * g - 4.0) * k * k + 8.0 * h * k + (2.0 * g - 14.0 * h - 4.0) * h_sum - 8.0 * h
+        + 4.0 * g
+        - 6.0;
+    let c_c = (6.0 * h + 2.0 * g - 2.0) * k * k
+        + (4.0 * h - 4.0 * g + 6.0) * k
+        + (2.0 * h - 6.0) * h_sum
+        + 4.0 * h;
+    let c_d = (2.0 * h + 6.0) * k * k - 4.0 * h * k;
+    let variance = (c_a * n.powf(3.0) + c_b * n.powf(2.0) + c_c * n + c_d)
+        / ((n - 1.0) * (n - 2.0) * (n - 3.0));
+
+    // Standardize the statistic.
+    let m = k - 1.0;
+    let statistic = (a2 - m) / variance.sqrt();
+
+    // Interpolate the p-value by fitting a quadratic to the logs of the significance levels of the
+    // critical values.
+    const B0: [f64; 7] = [0.675, 1.281, 1.645, 1.96, 2.326, 2.573, 3.085];
+    const B1: [f64; 7] = [-0.245, 0.25, 0.678, 1.149, 1.822, 2.364, 3.615];
+    const B2: [f64; 7] = [-0.105, -0.305, -0.362, -0.391, -0.396, -0.345, -0.154];
+    const SIG: [f64; 7] = [0.25, 0.1, 0.05, 0.025, 0.01, 0.005, 0.001];
+    let critical: Vec<f64> = (0..7).map(|i| B0[i] + B1[i] / m.sqrt() + B2[i] / m).collect();
+    let log_sig: Vec<f64> = SIG.iter().map(|s| s.ln()).collect();
+    let c = quadratic_fit(&critical, &log_sig);
+    let p_value = (c[0] + c[1] * statistic + c[2] * statistic.powf(2.0)).exp().clamp(0.001, 0.25);
+
+    Ok(AndersonDarling { a2, statistic, p_value, alpha })
+}
+
+/// The result of a two-sample Kolmogorov-Smirnov test for whether two data sets are drawn from the
+/// same distribution.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KolmogorovSmirnov {
+    /// The D statistic: the largest absolute difference between the empirical distribution
+    /// functions of the two data sets.
+    pub d: f64,
+
+    /// The p-value for the test, using the asymptotic Kolmogorov distribution with Stephens'
+    /// correction for small samples.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl KolmogorovSmirnov {
+    /// Whether or not the distributions differ significantly.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Test whether two data sets are drawn from the same distribution using the two-sample
+/// Kolmogorov-Smirnov test. Unlike tests of location, this detects any difference in the
+/// distributions, such as in their spread or modality. The confidence level must be in the range
+/// `(0, 100)`.
+///
+/// [anderson_darling] is more sensitive to differences in the tails of the distributions.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::nonparametric::kolmogorov_smirnov;
+///
+/// // The same mean, but one data set is bimodal.
+/// let a: Vec<f64> = (0..20).map(|i| 5.0 + f64::from(i % 5) / 10.0).collect();
+/// let b: Vec<f64> = (0..20).map(|i| f64::from(i % 2 * 8 + 1) + f64::from(i % 5) / 10.0).collect();
+/// assert!(kolmogorov_smirnov(&a, &b, Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn kolmogorov_smirnov(
+    a: &[f64],
+    b: &[f64],
+    confidence: Confidence,
+) -> Result<KolmogorovSmirnov, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if a.is_empty() || b.is_empty() {
+        return Err(Error::InsufficientSamples);
+    }
+    if a.iter().chain(b.iter()).any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    let (mut a, mut b) = (a.to_vec(), b.to_vec());
+    a.sort_by(f64::total_cmp);
+    b.sort_by(f64::total_cmp);
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+
+    // Find the largest difference between the empirical distribution functions, which can only
+    // occur at one of the measurements.
+    let d = a
+        .iter()
+        .chain(b.iter())
+        .map(|x| {
+            let f1 = a.partition_point(|y| y <= x) as f64 / n1;
+            let f2 = b.partition_point(|y| y <= x) as f64 / n2;
+            (f1 - f2).abs()
+        })
+        .fold(0.0, f64::max);
+
+    // Calculate the p-value from the Kolmogorov distribution, whose series converges slowly for
+    // small values but is indistinguishable from one for them.
+    let ne = (n1 * n2 / (n1 + n2)).sqrt();
+    let lambda = (ne + 0.12 + 0.11 / ne) * d;
+    let p_value = if lambda < 0.2 {
+        1.0
+    } else {
+        let q: f64 = (1..=100)
+            .map(|k| {
+                let sign = if k % 2 == 1 { 1.0 } else { -1.0 };
+                sign * (-2.0 * (k * k) as f64 * lambda * lambda).exp()
+            })
+            .sum();
+        (2.0 * q).clamp(0.0, 1.0)
+    };
+
+    Ok(KolmogorovSmirnov { d, p_value, alpha })
+}
+
+/// Fit a quadratic `c[0] + c[1]x + c[2]x²` to the given points by least squares.
+fn quadratic_fit(x: &[f64], y: &[f64]) -> [f64; 3] {
+    // Build the augmented normal equations.
+    let mut m = [[0.0; 4]; 3];
+    for (&x, &y) in x.iter().zip(y.iter()) {
+        let row = [1.0, x, x * x];
+        for i in 0..3 {
+            for j in 0..3 {
+                m[i][j] += row[i] * row[j];
+            }
+            m[i][3] += row[i] * y;
+        }
+    }
+
+    // Solve them by Gauss-Jordan elimination with partial pivoting.
+    for i in 0..3 {
+        let p = (i..3).max_by(|&r, &s| m[r][i].abs().total_cmp(&m[s][i].abs())).unwrap();
+        m.swap(i, p);
+        for r in 0..3 {
+            if r != i {
+                let f = m[r][i] / m[i][i];
+                let pivot = m[i];
+                for (v, p) in m[r].iter_mut().zip(pivot.iter()) {
+                    *v -= f * p;
+                }
+            }
+        }
+    }
+    [m[0][3] / m[0][0], m[1][3] / m[1][1], m[2][3] / m[2][2]]
+}
+
+/// Cliff's delta, a non-parametric measure of how often measurements in one data set are larger
+/// than those in another.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CliffsDelta {
+    /// The dominance statistic, in the range `[-1, 1]`: the probability that a measurement from the
+    /// first data set is larger than one from the second, minus the probability that it is
+    /// smaller.
+    pub delta: f64,
+
+    /// The qualitative magnitude of the effect, using the thresholds of Romano et al. (2006).
+    pub magnitude: EffectMagnitude,
+}
+
+/// Calculate Cliff's delta between two data sets.
+pub fn cliffs_delta(a: &[f64], b: &[f64]) -> Result<CliffsDelta, Error> {
+    if a.is_empty() || b.is_empty() {
+        return Err(Error::InsufficientSamples);
+    }
+    if a.iter().chain(b.iter()).any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    // Count the number of measurements in the second data set above and below each measurement in
+    // the first.
+    let mut sorted = b.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let dominance: f64 = a
+        .iter()
+        .map(|x| {
+            let below = sorted.partition_point(|y| y < x);
+            let above = sorted.len() - sorted.partition_point(|y| y <= x);
+            below as f64 - above as f64
+        })
+        .sum();
+
+    let delta = dominance / (a.len() * b.len()) as f64;
+    let magnitude = match delta.abs() {
+        d if d < 0.147 => EffectMagnitude::Negligible,
+        d if d < 0.33 => EffectMagnitude::Small,
+        d if d < 0.474 => EffectMagnitude::Medium,
+        _ => EffectMagnitude::Large,
+    };
+
+    Ok(CliffsDelta { delta, magnitude })
+}
+
+/// The result of a Kruskal-Wallis H test across several data sets.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KruskalWallis {
+    /// The H statistic for the test, corrected for ties.
+    pub h: f64,
+
+    /// The degrees of freedom of the chi-squared approximation.
+    pub df: f64,
+
+    /// The p-value for the test, using a chi-squared approximation of the distribution of H.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl KruskalWallis {
+    /// Whether or not any of the data sets differ significantly.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Test whether any of the given data sets are drawn from different distributions using the
+/// Kruskal-Wallis H test. This is the non-parametric counterpart of [crate::anova] and makes no
+/// assumptions about the distribution of the measurements.
+pub fn kruskal_wallis(groups: &[&[f64]], confidence: Confidence) -> Result<KruskalWallis, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if groups.len() < 2 || groups.iter().any(|g| g.is_empty()) {
+        return Err(Error::InsufficientSamples);
+    }
+
+    // Rank the pooled samples.
+    let pooled: Vec<f64> = groups.iter().flat_map(|g| g.iter()).copied().collect();
+    let (ranks, ties) = rank(&pooled)?;
+
+    // Sum the squared rank sums of each group, scaled by the group's size.
+    let mut offset = 0;
+    let mut sum = 0.0;
+    for g in groups {
+        let r: f64 = ranks[offset..offset + g.len()].iter().sum();
+        sum += r * r / g.len() as f64;
+        offset += g.len();
+    }
+
+    // Calculate the H statistic, corrected for ties.
+    let n = pooled.len() as f64;
+    let correction = 1.0 - ties / (n.powf(3.0) - n);
+    if correction == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+    let h = (12.0 / (n * (n + 1.0)) * sum - 3.0 * (n + 1.0)) / correction;
+
+    // Calculate the p-value using a chi-squared approximation.
+    let df = groups.len() as f64 - 1.0;
+    let p_value = 1.0 - ChiSquared::new(df).unwrap().cdf(h);
+
+    Ok(KruskalWallis { h, df, p_value, alpha })
+}
+
+/// The result of a Wilcoxon signed-rank test on paired measurements.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Wilcoxon {
+    /// The sum of the ranks of the positive differences, after discarding zero differences.
+    pub w: f64,
+
+    /// The two-tailed p-value for the test. Unless `exact`, this uses a normal approximation with
+    /// tie and continuity corrections.
+    pub p_value: f64,
+
+    /// Whether the p-value was calculated by enumerating every assignment of signs to the ranks,
+    /// rather than by a normal approximation.
+    pub exact: bool,
+
+    /// The Hodges-Lehmann estimate of the location shift: the median of all pairwise averages of
+    /// the differences (the Walsh averages).
+    pub estimate: f64,
+
+    /// The matched-pairs rank-biserial correlation, in the range `[-1, 1]`. Positive values
+    /// indicate that the differences tend to be positive.
+    pub effect_size: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl Wilcoxon {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Compare two paired data sets using a two-tailed Wilcoxon signed-rank test on the differences
+/// `a[i] - b[i]`. Both data sets must have the same length.
+pub fn wilcoxon(a: &[f64], b: &[f64], confidence: Confidence) -> Result<Wilcoxon, Error> {
+    if a.len() != b.len() {
+        return Err(Error::MismatchedLengths);
+    }
+    let deltas: Vec<f64> = a.iter().zip(b.iter()).map(|(x, y)| x - y).collect();
+    wilcoxon_deltas(&deltas, confidence)
+}
+
+/// Test whether the given paired differences are centered on zero using a two-tailed Wilcoxon
+/// signed-rank test. Differences of exactly zero are discarded.
+///
+/// For small samples, where the normal approximation is unreliable, the p-value is calculated
+/// exactly; see [wilcoxon_deltas_with].
+pub fn wilcoxon_deltas(deltas: &[f64], confidence: Confidence) -> Result<Wilcoxon, Error> {
+    wilcoxon_deltas_with(deltas, confidence, EXACT_LIMIT)
+}
+
+/// Test whether the given paired differences are centered on zero using a two-tailed Wilcoxon
+/// signed-rank test, calculating the p-value exactly if there are no more than `exact_limit` ways
+/// of assigning signs to the non-zero differences. An `exact_limit` of zero always uses the normal
+/// approximation.
+pub fn wilcoxon_deltas_with(
+    deltas: &[f64],
+    confidence: Confidence,
+    exact_limit: usize,
+) -> Result<Wilcoxon, Error> {
+    let alpha = confidence.checked_alpha()?;
+
+    // Discard zero differences and rank the remaining absolute differences.
+    let d: Vec<f64> = deltas.iter().copied().filter(|&x| x != 0.0).collect();
+    if d.is_empty() {
+        return Err(Error::InsufficientSamples);
+    }
+    let abs: Vec<f64> = d.iter().map(|x| x.abs()).collect();
+    let (ranks, ties) = rank(&abs)?;
+
+    // Sum the ranks of the positive differences.
+    let w: f64 = d.iter().zip(ranks.iter()).filter(|(&x, _)| x > 0.0).map(|(_, &r)| r).sum();
+
+    // Calculate the expected value and standard deviation of W, corrected for ties.
+    let n = d.len() as f64;
+    let total = n * (n + 1.0) / 2.0;
+    let mu = total / 2.0;
+    let sigma = (n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - ties / 48.0).sqrt();
+
+    let exact = d.len() < usize::BITS as usize && 1usize << d.len() <= exact_limit;
+    let p_value = if exact {
+        // Enumerate every assignment of signs to the ranks, counting those with a W statistic at
+        // least as extreme as the observed one, allowing for rounding error.
+        let threshold = (w - mu).abs() - 1e-9;
+        let count = 1usize << d.len();
+        let hits = (0..count)
+            .filter(|signs| {
+                let w: f64 = ranks
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| signs >> i & 1 == 1)
+                    .map(|(_, r)| r)
+                    .sum();
+                (w - mu).abs() >= threshold
+            })
+            .count();
+        hits as f64 / count as f64
+    } else {
+        // Calculate the p-value using the normal approximation with a continuity correction.
+        let z = ((w - mu).abs() - 0.5).max(0.0) / sigma;
+        (Normal::new(0.0, 1.0).unwrap().cdf(-z) * 2.0).min(1.0)
+    };
+
+    // Calculate the Hodges-Lehmann estimator from the Walsh averages.
+    let estimate = walsh_median(&d);
+
+    // Calculate the matched-pairs rank-biserial correlation.
+    let effect_size = (2.0 * w - total) / total;
+
+    Ok(Wilcoxon { w, p_value, exact, estimate, effect_size, alpha })
+}
+
+/// The result of a sign test on paired measurements.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignTest {
+    /// The number of positive differences.
+    pub positive: usize,
+
+    /// The number of negative differences.
+    pub negative: usize,
+
+    /// The exact two-tailed p-value for the test.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl SignTest {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Compare two paired data sets using a two-tailed sign test on the differences `a[i] - b[i]`.
+/// Both data sets must have the same length.
+///
+/// This only uses the direction of each difference, making it less powerful than [wilcoxon] but
+/// usable when the magnitudes of the differences are untrustworthy.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::nonparametric::sign_test;
+///
+/// let a = [10.2, 11.9, 14.1, 16.3, 18.0, 11.2, 13.4, 15.1, 12.8];
+/// let b = [9.8, 11.1, 12.2, 15.9, 16.4, 10.7, 12.9, 14.6, 12.5];
+/// assert!(sign_test(&a, &b, Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn sign_test(a: &[f64], b: &[f64], confidence: Confidence) -> Result<SignTest, Error> {
+    if a.len() != b.len() {
+        return Err(Error::MismatchedLengths);
+    }
+    let deltas: Vec<f64> = a.iter().zip(b.iter()).map(|(x, y)| x - y).collect();
+    sign_test_deltas(&deltas, confidence)
+}
+
+/// Test whether the given paired differences have a median of zero using a two-tailed sign test.
+/// Differences of exactly zero are discarded.
+pub fn sign_test_deltas(deltas: &[f64], confidence: Confidence) -> Result<SignTest, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if deltas.iter().any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    // Count the positive and negative differences.
+    let positive = deltas.iter().filter(|&&x| x > 0.0).count();
+    let negative = deltas.iter().filter(|&&x| x < 0.0).count();
+    let n = positive + negative;
+    if n == 0 {
+        return Err(Error::InsufficientSamples);
+    }
+
+    // Calculate the exact p-value using the binomial distribution of the number of positive
+    // differences under the null hypothesis.
+    let dist_b = Binomial::new(0.5, n as u64).unwrap();
+    let p_value = (dist_b.cdf(positive.min(negative) as u64) * 2.0).min(1.0);
+
+    Ok(SignTest { positive, negative, p_value, alpha })
+}
+
+/// Calculate the median of the given non-empty values, reordering them in the process.
+pub(crate) fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Calculate the median of the Walsh averages `(x[i] + x[j]) / 2` for all `i <= j` using Monahan's
+/// selection algorithm (Algorithm 616, 1984).
+///
+/// The averages of the sorted values form an implicit matrix whose rows and columns are sorted, so
+/// the number of averages below a pivot can be counted in `O(n)` time; see [staircase]. Each
+/// iteration picks a random pivot from the remaining candidates and discards the candidates on the
+/// wrong side of it, until few enough candidates remain to sort. This takes `O(n log n)` expected
+/// time and `O(n)` space, rather than materializing all `n(n + 1) / 2` averages. The pivots are
+/// drawn from a fixed seed, so the result is deterministic.
+fn walsh_median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len();
+    let total = n * (n + 1) / 2;
+    if total % 2 == 1 {
+        walsh_select(&sorted, total / 2)
+    } else {
+        (walsh_select(&sorted, total / 2 - 1) + walsh_select(&sorted, total / 2)) / 2.0
+    }
+}
+
+/// Select the `k`-th smallest (from zero) of the Walsh averages of the given sorted values.
+fn walsh_select(sorted: &[f64], k: usize) -> f64 {
+    let n = sorted.len();
+    let walsh = |i: usize, j: usize| (sorted[i] + sorted[j]) / 2.0;
+
+    // The candidates in row i are the averages with columns in lo[i]..hi[i].
+    let mut lo: Vec<usize> = (0..n).collect();
+    let mut hi = vec![n; n];
+    let mut rng = StdRng::seed_from_u64(0);
+    loop {
+        let remaining: usize = lo.iter().zip(&hi).map(|(l, h)| h - l).sum();
+        if remaining <= n {
+            // Sort the few remaining candidates, all of which are larger than the averages to
+            // their left, and select from them.
+            let below: usize = lo.iter().enumerate().map(|(i, l)| l - i).sum();
+            let mut candidates: Vec<f64> = (0..n)
+                .flat_map(|i| (lo[i]..hi[i]).map(move |j| (i, j)))
+                .map(|(i, j)| walsh(i, j))
+                .collect();
+            candidates.sort_by(f64::total_cmp);
+            return candidates[k - below];
+        }
+
+        // Pick a random candidate as the pivot.
+        let mut r = rng.gen_range(0..remaining);
+        let mut pivot = f64::NAN;
+        for i in 0..n {
+            let width = hi[i] - lo[i];
+            if r < width {
+                pivot = walsh(i, lo[i] + r);
+                break;
+            }
+            r -= width;
+        }
+
+        // Count the averages in each row which are less than, and no more than, the pivot.
+        let less = staircase(sorted, |x| x < pivot);
+        let at_most = staircase(sorted, |x| x <= pivot);
+        let (lt, le): (usize, usize) = (
+            less.iter().enumerate().map(|(i, c)| c - i).sum(),
+            at_most.iter().enumerate().map(|(i, c)| c - i).sum(),
+        );
+
+        // Keep the candidates on the side of the pivot which has the k-th smallest average.
+        if k < lt {
+            hi.iter_mut().zip(&less).for_each(|(h, &c)| *h = (*h).min(c));
+        } else if k < le {
+            return pivot;
+        } else {
+            lo.iter_mut().zip(&at_most).for_each(|(l, &c)| *l = (*l).max(c));
+        }
+    }
+}
+
+/// Find the end of the columns in each row `i` of the Walsh averages of the given sorted values for
+/// which the averages are below some threshold, starting from column `i`. The ends can only move
+/// left as the rows' values increase, so they are found in `O(n)` time by walking down and to the
+/// left through the matrix.
+fn staircase(sorted: &[f64], below: impl Fn(f64) -> bool) -> Vec<usize> {
+    let mut j = sorted.len();
+    (0..sorted.len())
+        .map(|i| {
+            while j > 0 && !below((sorted[i] + sorted[j - 1]) / 2.0) {
+                j -= 1;
+            }
+            j.max(i)
+        })
+        .collect()
+}
+
+/// Assign fractional ranks (starting at 1) to the given values, averaging the ranks of tied
+/// values. Also returns the tie correction term `Σ(t³ - t)` over all groups of `t` tied values.
+pub(crate) fn rank(values: &[f64]) -> Result<(Vec<f64>, f64), Error> {
+    if values.iter().any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    let mut idx: Vec<usize> = (0..values.len()).collect();
+    idx.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut ties = 0.0;
+    let mut i = 0;
+    while i < idx.len() {
+        // Find the run of values tied with the current one.
+        let mut j = i + 1;
+        while j < idx.len() && values[idx[j]] == values[idx[i]] {
+            j += 1;
+        }
+
+        // Assign each the average of their ranks.
+        let r = (i + j + 1) as f64 / 2.0;
+        for &k in &idx[i..j] {
+            ranks[k] = r;
+        }
+
+        let t = (j - i) as f64;
+        ties += t * t * t - t;
+        i = j;
+    }
+    Ok((ranks, ties))
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn ranks_with_ties() {
+        let (ranks, ties) = rank(&[3.0, 1.0, 4.0, 1.0, 5.0]).unwrap();
+
+        assert_eq!(ranks, vec![3.0, 1.5, 4.0, 1.5, 5.0]);
+        assert_relative_eq!(ties, 6.0);
+    }
+
+    #[test]
+    fn mann_whitney_separated() {
+        let (a, b) = ([1.0, 2.0, 3.0, 4.0, 5.0], [6.0, 7.0, 8.0, 9.0, 10.0]);
+        let mw = mann_whitney_with(&a, &b, Confidence::P95, 0).unwrap();
+
+        assert_relative_eq!(mw.u, 0.0);
+        assert_relative_eq!(mw.p_value, 0.012185780355348052, epsilon = 1e-12);
+        assert_relative_eq!(mw.effect_size, -1.0);
+        assert!(!mw.exact);
+        assert!(mw.is_significant());
+    }
+
+    #[test]
+    fn mann_whitney_exact() {
+        let (a, b) = ([1.0, 2.0, 3.0, 4.0, 5.0], [6.0, 7.0, 8.0, 9.0, 10.0]);
+        let mw = mann_whitney(&a, &b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(mw.u, 0.0);
+        assert_relative_eq!(mw.p_value, 2.0 / 252.0, epsilon = 1e-12);
+        assert!(mw.exact);
+        assert!(mw.is_significant());
+
+        let mw =
+            mann_whitney(&[1.0, 3.0, 5.0, 7.0], &[2.0, 4.0, 6.0, 8.0], Confidence::P95).unwrap();
+        assert_relative_eq!(mw.p_value, 48.0 / 70.0, epsilon = 1e-12);
+
+        // Only the approximation is used for larger samples.
+        assert!(!mann_whitney_with(&a, &b, Confidence::P95, 251).unwrap().exact);
+    }
+
+    #[test]
+    fn mann_whitney_overlapping() {
+        let mw =
+            mann_whitney(&[1.0, 3.0, 5.0, 7.0], &[2.0, 4.0, 6.0, 8.0], Confidence::P95).unwrap();
+
+        assert_relative_eq!(mw.u, 6.0);
+        assert_relative_eq!(mw.effect_size, -0.25);
+        assert!(!mw.is_significant());
+    }
+
+    #[test]
+    fn brunner_munzel_test() {
+        let a = [1.0, 2.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 2.0, 4.0, 1.0, 1.0];
+        let b = [3.0, 3.0, 4.0, 3.0, 1.0, 2.0, 3.0, 1.0, 1.0, 5.0, 4.0];
+        let bm = brunner_munzel(&a, &b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(bm.w, 3.1374674823029505, epsilon = 1e-12);
+        assert_relative_eq!(bm.p_value, 0.005786208666151538, epsilon = 1e-9);
+        assert_relative_eq!(bm.effect_size, 0.788961038961039, epsilon = 1e-12);
+        assert!(bm.is_significant());
+
+        assert_eq!(
+            brunner_munzel(&[1.0, 2.0], &[3.0, 4.0], Confidence::P95).unwrap_err(),
+            Error::ZeroVariance
+        );
+        assert_eq!(
+            brunner_munzel(&[1.0], &[3.0, 4.0], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+    }
+
+    #[test]
+    fn anderson_darling_shifted() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        let b = [6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0];
+        let ad = anderson_darling(&a, &b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(ad.a2, 4.5460171468642185, epsilon = 1e-12);
+        assert_relative_eq!(ad.statistic, 5.093343298056228, epsilon = 1e-12);
+        assert_relative_eq!(ad.p_value, 0.0032118227459270576, epsilon = 1e-12);
+        assert!(ad.is_significant());
+    }
+
+    #[test]
+    fn anderson_darling_ties() {
+        let a = [1.0, 2.0, 2.0, 3.0, 3.0, 3.0, 4.0, 5.0];
+        let b = [2.0, 3.0, 4.0, 4.0, 5.0, 5.0, 6.0, 7.0, 8.0];
+        let ad = anderson_darling(&a, &b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(ad.a2, 3.0544115080444434, epsilon = 1e-12);
+        assert_relative_eq!(ad.statistic, 2.996485276069231, epsilon = 1e-12);
+        assert_relative_eq!(ad.p_value, 0.019535791644466453, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn anderson_darling_similar() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        let b = [1.5, 2.5, 3.5, 4.5, 5.5, 6.5, 7.5, 8.5];
+        let ad = anderson_darling(&a, &b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(ad.p_value, 0.25);
+        assert!(!ad.is_significant());
+    }
+
+    #[test]
+    fn kolmogorov_smirnov_shifted() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        let b = [6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0];
+        let ks = kolmogorov_smirnov(&a, &b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(ks.d, 0.5);
+        assert_relative_eq!(ks.p_value, 0.11084033741322809, epsilon = 1e-12);
+        assert!(!ks.is_significant());
+
+        let ks = kolmogorov_smirnov(&a, &a, Confidence::P95).unwrap();
+        assert_relative_eq!(ks.d, 0.0);
+        assert_relative_eq!(ks.p_value, 1.0);
+
+        assert_eq!(
+            kolmogorov_smirnov(&a, &[], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+    }
+
+    #[test]
+    fn cliffs_delta_magnitudes() {
+        let a = [1.0, 2.0, 3.0, 4.0];
+
+        let cd = cliffs_delta(&a, &[5.0, 6.0, 7.0]).unwrap();
+        assert_relative_eq!(cd.delta, -1.0);
+        assert_eq!(cd.magnitude, EffectMagnitude::Large);
+
+        let cd = cliffs_delta(&a, &[1.0, 2.0, 3.0, 4.0]).unwrap();
+        assert_relative_eq!(cd.delta, 0.0);
+        assert_eq!(cd.magnitude, EffectMagnitude::Negligible);
+
+        let cd = cliffs_delta(&a, &[1.5, 2.5, 3.5, 4.5]).unwrap();
+        assert_relative_eq!(cd.delta, -0.25);
+        assert_eq!(cd.magnitude, EffectMagnitude::Small);
+
+        // Cliff's delta is the rank-biserial correlation of the Mann-Whitney U test.
+        let mw = mann_whitney(&a, &[1.5, 2.5, 3.5, 4.5], Confidence::P95).unwrap();
+        assert_relative_eq!(mw.effect_size, cd.delta);
+    }
+
+    #[test]
+    fn kruskal_wallis_shifted() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let b = [2.0, 4.0, 6.0, 8.0, 10.0];
+        let c = [5.0, 6.0, 7.0, 8.0, 9.5];
+        let kw = kruskal_wallis(&[&a, &b, &c], Confidence::P95).unwrap();
+
+        assert_relative_eq!(kw.h, 6.21045045045045, epsilon = 1e-12);
+        assert_relative_eq!(kw.df, 2.0);
+        assert_relative_eq!(kw.p_value, 0.04481442408378668, epsilon = 1e-9);
+        assert!(kw.is_significant());
+    }
+
+    #[test]
+    fn kruskal_wallis_errors() {
+        assert_eq!(
+            kruskal_wallis(&[&[1.0, 2.0]], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+        assert_eq!(
+            kruskal_wallis(&[&[1.0, 1.0], &[1.0]], Confidence::P95).unwrap_err(),
+            Error::ZeroVariance
+        );
+    }
+
+    #[test]
+    fn wilcoxon_shifted() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0];
+        let b = [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+        let w = wilcoxon(&a, &b, Confidence::P95).unwrap();
+        assert_relative_eq!(w.p_value, 2.0 / 256.0, epsilon = 1e-12);
+        assert!(w.exact);
+
+        let deltas: Vec<f64> = a.iter().zip(b.iter()).map(|(x, y)| x - y).collect();
+        let w = wilcoxon_deltas_with(&deltas, Confidence::P95, 0).unwrap();
+
+        assert_relative_eq!(w.w, 36.0);
+        assert_relative_eq!(w.p_value, 0.014266186701446932, epsilon = 1e-12);
+        assert!(!w.exact);
+        assert_relative_eq!(w.estimate, 4.5);
+        assert_relative_eq!(w.effect_size, 1.0);
+        assert!(w.is_significant());
+    }
+
+    #[test]
+    fn hodges_lehmann_estimate() {
+        // Compare the selection with the median of every Walsh average, with and without ties.
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+        for n in [1, 2, 3, 10, 57, 200] {
+            for ties in [false, true] {
+                let d: Vec<f64> = (0..n)
+                    .map(|_| rng.gen_range(-10.0..10.0))
+                    .map(|x: f64| if ties { x.round() } else { x })
+                    .collect();
+                let mut walsh: Vec<f64> = (0..n)
+                    .flat_map(|i| (i..n).map(move |j| (i, j)))
+                    .map(|(i, j)| (d[i] + d[j]) / 2.0)
+                    .collect();
+                assert_eq!(walsh_median(&d), median(&mut walsh), "n = {}, ties = {}", n, ties);
+            }
+        }
+
+        // Large inputs don't materialize the n(n + 1) / 2 averages.
+        let d: Vec<f64> = (0..50_000).map(|i| f64::from(i % 1_000) - 250.0).collect();
+        assert_relative_eq!(walsh_median(&d), 249.5);
+    }
+
+    #[test]
+    fn wilcoxon_symmetric() {
+        let w = wilcoxon_deltas(&[-3.0, -1.0, 1.0, 2.0, -2.0, 3.0], Confidence::P95).unwrap();
+
+        assert_relative_eq!(w.w, 10.5);
+        assert_relative_eq!(w.p_value, 1.0);
+        assert_relative_eq!(w.estimate, 0.0);
+        assert_relative_eq!(w.effect_size, 0.0);
+        assert!(!w.is_significant());
+    }
+
+    #[test]
+    fn sign_test_shifted() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0];
+        let b = [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+        let s = sign_test(&a, &b, Confidence::P95).unwrap();
+
+        assert_eq!(s.positive, 8);
+        assert_eq!(s.negative, 0);
+        assert_relative_eq!(s.p_value, 0.0078125, epsilon = 1e-12);
+        assert!(s.is_significant());
+
+        let s =
+            sign_test_deltas(&[1.0, 2.0, -1.0, 3.0, 1.0, 1.0, 2.0, 1.0], Confidence::P95).unwrap();
+        assert_relative_eq!(s.p_value, 0.0703125, epsilon = 1e-12);
+        assert!(!s.is_significant());
+
+        let s = sign_test_deltas(&[-3.0, -1.0, 1.0, 2.0, -2.0, 3.0], Confidence::P95).unwrap();
+        assert_relative_eq!(s.p_value, 1.0);
+    }
+
+    #[test]
+    fn sign_test_errors() {
+        assert_eq!(
+            sign_test(&[1.0, 2.0], &[1.0], Confidence::P95).unwrap_err(),
+            Error::MismatchedLengths
+        );
+        assert_eq!(
+            sign_test(&[1.0, 2.0], &[1.0, 2.0], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+        assert_eq!(
+            sign_test_deltas(&[1.0, f64::NAN], Confidence::P95).unwrap_err(),
+            Error::NonFinite
+        );
+    }
+
+    #[test]
+    fn wilcoxon_errors() {
+        assert_eq!(
+            wilcoxon(&[1.0, 2.0], &[1.0], Confidence::P95).unwrap_err(),
+            Error::MismatchedLengths
+        );
+        assert_eq!(
+            wilcoxon(&[1.0, 2.0], &[1.0, 2.0], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+    }
+
+    #[test]
+    fn mann_whitney_errors() {
+        assert_eq!(
+            mann_whitney(&[], &[1.0], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+        assert_eq!(
+            mann_whitney(&[f64::NAN], &[1.0], Confidence::P95).unwrap_err(),
+            Error::NonFinite
+        );
+        assert_eq!(
+            mann_whitney(&[1.0, 1.0], &[1.0], Confidence::P95).unwrap_err(),
+            Error::ZeroVariance
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Diagnostics for whether a data set is plausibly normally distributed, as Welch's t-test assumes.
+
+use std::f64::consts::PI;
+
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{Confidence, Error};
+
+/// The result of a Shapiro-Wilk test for normality.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShapiroWilk {
+    /// The W statistic for the test, in the range `(0, 1]`. Values close to 1 indicate normality.
+    pub w: f64,
+
+    /// The p-value for the test: the probability of a W statistic at least this small if the data
+    /// were drawn from a normal distribution.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl ShapiroWilk {
+    /// Whether or not the data set is consistent with a normal distribution, i.e. whether the
+    /// hypothesis of normality could not be rejected.
+    #[must_use]
+    pub fn is_normal(&self) -> bool {
+        self.p_value >= self.alpha
+    }
+}
+
+/// Test whether the given measurements are drawn from a normal distribution using the Shapiro-Wilk
+/// test, as approximated by Royston (1995) for between 3 and 5000 measurements. The confidence
+/// level must be in the range `(0, 100)`.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::normality::shapiro_wilk;
+///
+/// let weights = [148.0, 154.0, 158.0, 160.0, 161.0, 162.0, 166.0, 170.0, 182.0, 195.0, 236.0];
+/// assert!(!shapiro_wilk(&weights, Confidence::P95)?.is_normal());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn shapiro_wilk(values: &[f64], confidence: Confidence) -> Result<ShapiroWilk, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if values.len() < 3 {
+        return Err(Error::InsufficientSamples);
+    }
+    if values.iter().any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    let mut x = values.to_vec();
+    x.sort_by(f64::total_cmp);
+    let n = x.len() as f64;
+
+    // Calculate the sum of squared deviations from the mean.
+    let mean = x.iter().sum::<f64>() / n;
+    let ssq = x.iter().map(|v| (v - mean).powf(2.0)).sum::<f64>();
+    if ssq == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Calculate the W statistic from the coefficients for the upper half of the order statistics.
+    let a = coefficients(x.len());
+    let num = a.iter().enumerate().map(|(i, a_i)| a_i * (x[x.len() - 1 - i] - x[i])).sum::<f64>();
+    let w = (num.powf(2.0) / ssq).min(1.0);
+
+    // Calculate the p-value, which is exact for three measurements.
+    let p_value = if x.len() == 3 {
+        (6.0 / PI * (w.sqrt().asin() - (0.75f64).sqrt().asin())).max(0.0)
+    } else {
+        let y = (1.0 - w).ln();
+        let (y, mu, sigma) = if x.len() <= 11 {
+            let gamma = poly(&[-2.273, 0.459], n);
+            if y >= gamma {
+                return Ok(ShapiroWilk { w, p_value: 0.0, alpha });
+            }
+            (
+                -(gamma - y).ln(),
+                poly(&[0.544, -0.39978, 0.025054, -6.714e-4], n),
+                poly(&[1.3822, -0.77857, 0.062767, -0.0020322], n).exp(),
+            )
+        } else {
+            let ln_n = n.ln();
+            (
+                y,
+                poly(&[-1.5861, -0.31082, -0.083751, 0.0038915], ln_n),
+                poly(&[-0.4803, -0.082676, 0.0030302], ln_n).exp(),
+            )
+        };
+        1.0 - Normal::new(mu, sigma).unwrap().cdf(y)
+    };
+    Ok(ShapiroWilk { w, p_value, alpha })
+}
+
+/// Calculate Royston's approximation of the Shapiro-Wilk coefficients for the upper half of `n`
+/// order statistics, from largest to smallest.
+fn coefficients(n: usize) -> Vec<f64> {
+    if n == 3 {
+        return vec![(0.5f64).sqrt()];
+    }
+
+    // Approximate the expected values of the standard normal order statistics.
+    let dist_norm = Normal::new(0.0, 1.0).unwrap();
+    let half = n / 2;
+    let nf = n as f64;
+    let m: Vec<f64> =
+        (1..=half).map(|i| -dist_norm.inverse_cdf((i as f64 - 0.375) / (nf + 0.25))).collect();
+    let summ2 = 2.0 * m.iter().map(|v| v * v).sum::<f64>();
+    let u = 1.0 / nf.sqrt();
+
+    // Calculate the largest one or two coefficients by polynomial approximation, then normalize
+    // the remainder.
+    let mut a = vec![0.0; half];
+    a[0] =
+        m[0] / summ2.sqrt() + poly(&[0.0, 0.221157, -0.147981, -2.07119, 4.434685, -2.706056], u);
+    let (first, phi) = if n > 5 {
+        a[1] = m[1] / summ2.sqrt()
+            + poly(&[0.0, 0.042981, -0.293762, -1.752461, 5.682633, -3.582633], u);
+        (
+            2,
+            (summ2 - 2.0 * m[0].powf(2.0) - 2.0 * m[1].powf(2.0))
+                / (1.0 - 2.0 * a[0].powf(2.0) - 2.0 * a[1].powf(2.0)),
+        )
+    } else {
+        (1, (summ2 - 2.0 * m[0].powf(2.0)) / (1.0 - 2.0 * a[0].powf(2.0)))
+    };
+    for i in first..half {
+        a[i] = m[i] / phi.sqrt();
+    }
+    a
+}
+
+/// Evaluate the polynomial with the given coefficients, in increasing order of degree, at `x`.
+fn poly(c: &[f64], x: f64) -> f64 {
+    c.iter().rev().fold(0.0, |acc, c_i| acc * x + c_i)
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn shapiro_wilk_skewed() {
+        let weights = [148.0, 154.0, 158.0, 160.0, 161.0, 162.0, 166.0, 170.0, 182.0, 195.0, 236.0];
+        let sw = shapiro_wilk(&weights, Confidence::P95).unwrap();
+
+        assert_relative_eq!(sw.w, 0.7888146948353874, epsilon = 1e-9);
+        assert_relative_eq!(sw.p_value, 0.006703814056503012, epsilon = 1e-9);
+        assert!(!sw.is_normal());
+    }
+
+    #[test]
+    fn shapiro_wilk_normal() {
+        let values = [2.1, 3.4, 1.9, 5.6, 4.4, 3.8, 2.9, 4.1, 3.3, 4.7];
+        let sw = shapiro_wilk(&values, Confidence::P95).unwrap();
+
+        assert_relative_eq!(sw.w, 0.9796929022343997, epsilon = 1e-9);
+        assert_relative_eq!(sw.p_value, 0.9635033958086335, epsilon = 1e-9);
+        assert!(sw.is_normal());
+    }
+
+    #[test]
+    fn shapiro_wilk_large() {
+        let values: Vec<f64> = (0..13).map(|i| 2.0f64.powi(i)).collect();
+        let sw = shapiro_wilk(&values, Confidence::P95).unwrap();
+
+        assert_relative_eq!(sw.w, 0.6093153043706117, epsilon = 1e-9);
+        assert_relative_eq!(sw.p_value, 7.888751738351912e-5, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn shapiro_wilk_three() {
+        let sw = shapiro_wilk(&[1.0, 2.0, 3.0], Confidence::P95).unwrap();
+
+        assert_relative_eq!(sw.w, 1.0);
+        assert_relative_eq!(sw.p_value, 1.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn shapiro_wilk_errors() {
+        assert_eq!(
+            shapiro_wilk(&[1.0, 2.0], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+        assert_eq!(
+            shapiro_wilk(&[1.0, 1.0, 1.0], Confidence::P95).unwrap_err(),
+            Error::ZeroVariance
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Detection and removal of outliers, such as GC pauses or context switches, from raw measurements.
+
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+use crate::nonparametric::median;
+use crate::quantiles::quantile;
+use crate::{Confidence, Error};
+
+/// The measurements of a data set, partitioned into those that were kept and the outliers that were
+/// removed. Both retain the order of the original measurements.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outliers {
+    /// The measurements which were not outliers.
+    pub kept: Vec<f64>,
+
+    /// The measurements which were outliers.
+    pub removed: Vec<f64>,
+}
+
+/// Remove the measurements outside of Tukey's fences, which are `k` times the interquartile range
+/// below the first quartile or above the third. A `k` of 1.5 is conventional for outliers; 3.0
+/// removes only extreme outliers.
+///
+/// ```
+/// use nanostat::outliers::tukey;
+///
+/// let timings = [10.1, 10.3, 9.8, 10.0, 10.2, 9.9, 10.4, 15.7, 10.1, 9.7];
+/// assert_eq!(tukey(&timings, 1.5)?.removed, vec![15.7]);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn tukey(values: &[f64], k: f64) -> Result<Outliers, Error> {
+    check(values, 1)?;
+
+    // Calculate the quartiles and the fences.
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+    let iqr = q3 - q1;
+    let (low, high) = (q1 - k * iqr, q3 + k * iqr);
+
+    Ok(partition(values, |x| x < low || x > high))
+}
+
+/// Remove the measurements whose modified z-score, based on the median absolute deviation (MAD),
+/// exceeds the given threshold. Iglewicz and Hoaglin recommend a threshold of 3.5.
+///
+/// Unlike the standard deviation, the MAD is not itself inflated by the outliers it is used to
+/// find.
+pub fn mad(values: &[f64], threshold: f64) -> Result<Outliers, Error> {
+    check(values, 1)?;
+
+    // Calculate the median and the median absolute deviation from it.
+    let m = median(&mut values.to_vec());
+    let mad = median(&mut values.iter().map(|x| (x - m).abs()).collect::<Vec<f64>>());
+    if mad == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Scale the deviations to be consistent with the standard deviation of a normal distribution.
+    Ok(partition(values, |x| (0.6745 * (x - m) / mad).abs() > threshold))
+}
+
+/// Remove outliers from normally distributed measurements using a two-sided Grubbs' test, which is
+/// repeated until no more outliers are found. The confidence level must be in the range `(0, 100)`.
+///
+/// Each round removes only the most extreme measurement, so a pair of outliers on opposite sides
+/// of the mean can mask each other. Use [tukey] or [mad] for data with many outliers.
+pub fn grubbs(values: &[f64], confidence: Confidence) -> Result<Outliers, Error> {
+    let alpha = confidence.checked_alpha()?;
+    check(values, 3)?;
+
+    // Track the indexes of the remaining measurements so the original order can be restored.
+    let mut kept: Vec<usize> = (0..values.len()).collect();
+    let mut removed = Vec::new();
+    while kept.len() > 2 {
+        // Find the measurement which is furthest from the mean.
+        let n = kept.len() as f64;
+        let mean = kept.iter().map(|&i| values[i]).sum::<f64>() / n;
+        let ssq = kept.iter().map(|&i| (values[i] - mean).powf(2.0)).sum::<f64>();
+        let std_dev = (ssq / (n - 1.0)).sqrt();
+        if std_dev == 0.0 {
+            break;
+        }
+        let (j, g) = kept
+            .iter()
+            .map(|&i| (values[i] - mean).abs() / std_dev)
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap();
+
+        // Calculate the critical value of the G statistic for this round.
+        let dist_st = StudentsT::new(0.0, 1.0, n - 2.0).map_err(|_| Error::NonFinite)?;
+        let t = dist_st.inverse_cdf(1.0 - alpha / (2.0 * n));
+        let g_crit = (n - 1.0) / n.sqrt() * (t.powf(2.0) / (n - 2.0 + t.powf(2.0))).sqrt();
+        if g <= g_crit {
+            break;
+        }
+        removed.push(kept.remove(j));
+    }
+    removed.sort_unstable();
+
+    Ok(Outliers {
+        kept: kept.into_iter().map(|i| values[i]).collect(),
+        removed: removed.into_iter().map(|i| values[i]).collect(),
+    })
+}
+
+/// Check that there are at least `min` measurements and that all of them are finite.
+fn check(values: &[f64], min: usize) -> Result<(), Error> {
+    if values.len() < min {
+        return Err(Error::InsufficientSamples);
+    }
+    if values.iter().any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+    Ok(())
+}
+
+/// Partition the measurements into those which are kept and those which are outliers.
+fn partition(values: &[f64], is_outlier: impl Fn(f64) -> bool) -> Outliers {
+    let (removed, kept) = values.iter().partition(|&&x| is_outlier(x));
+    Outliers { kept, removed }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TIMINGS: [f64; 10] = [10.1, 10.3, 9.8, 10.0, 10.2, 9.9, 10.4, 15.7, 10.1, 3.2];
+
+    #[test]
+    fn tukey_fences() {
+        let o = tukey(&TIMINGS, 1.5).unwrap();
+
+        assert_eq!(o.kept, vec![10.1, 10.3, 9.8, 10.0, 10.2, 9.9, 10.4, 10.1]);
+        assert_eq!(o.removed, vec![15.7, 3.2]);
+
+        let o = tukey(&TIMINGS, 30.0).unwrap();
+        assert!(o.removed.is_empty());
+    }
+
+    #[test]
+    fn mad_scores() {
+        let o = mad(&TIMINGS, 3.5).unwrap();
+
+        assert_eq!(o.removed, vec![15.7, 3.2]);
+        assert_eq!(mad(&[1.0, 1.0, 1.0, 2.0], 3.5).unwrap_err(), Error::ZeroVariance);
+    }
+
+    #[test]
+    fn grubbs_test() {
+        let timings = [10.1, 10.3, 9.8, 10.0, 10.2, 9.9, 10.4, 15.7, 10.1, 9.7];
+        let o = grubbs(&timings, Confidence::P95).unwrap();
+
+        assert_eq!(o.kept, vec![10.1, 10.3, 9.8, 10.0, 10.2, 9.9, 10.4, 10.1, 9.7]);
+        assert_eq!(o.removed, vec![15.7]);
+
+        // Outliers on both sides of the mean mask each other.
+        assert!(grubbs(&TIMINGS, Confidence::P95).unwrap().removed.is_empty());
+    }
+
+    #[test]
+    fn outliers_errors() {
+        assert_eq!(tukey(&[], 1.5).unwrap_err(), Error::InsufficientSamples);
+        assert_eq!(mad(&[1.0, f64::NAN], 3.5).unwrap_err(), Error::NonFinite);
+        assert_eq!(grubbs(&[1.0, 2.0], Confidence::P95).unwrap_err(), Error::InsufficientSamples);
+    }
+}
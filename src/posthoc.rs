@@ -0,0 +1,418 @@
+//! Post-hoc tests for pairwise differences between the means of several groups, which control the
+//! family-wise error rate across all of the comparisons.
+//!
+//! Like [crate::anova], [games_howell] does not assume the groups have equal variances, and it
+//! should be preferred unless they are known to.
+
+use std::f64::consts::SQRT_2;
+
+use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+use crate::{scale_points, Confidence, Error, Summary};
+
+/// The statistical difference between two of several groups, adjusted for the number of
+/// comparisons.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PairwiseDifference {
+    /// The index of the first group.
+    pub a: usize,
+
+    /// The index of the second group.
+    pub b: usize,
+
+    /// The difference between the means: the second group's mean minus the first's.
+    pub delta: f64,
+
+    /// The standard error of the difference.
+    pub std_err: f64,
+
+    /// The lower bound of the simultaneous confidence interval for the difference.
+    pub ci_low: f64,
+
+    /// The upper bound of the simultaneous confidence interval for the difference.
+    pub ci_high: f64,
+
+    /// The p-value for the difference, adjusted for the number of comparisons.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl PairwiseDifference {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Compare every pair of the given groups using the Tukey-Kramer honestly significant difference
+/// test, which assumes the groups have equal variances. The confidence level must be in the range
+/// `(0, 100)`. If the groups may have different variances, use [games_howell] instead.
+///
+/// The differences are returned in order of their groups' indexes, i.e. `(0, 1), (0, 2), …, (1, 2),
+/// …`.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::posthoc::tukey_hsd;
+/// use nanostat::Summary;
+///
+/// let a: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+/// let b: Summary = [2.0, 3.0, 4.0, 5.0, 6.0].iter().collect();
+/// let c: Summary = [5.0, 6.0, 7.0, 8.0, 9.0].iter().collect();
+/// let diffs = tukey_hsd(&[a, b, c], Confidence::P95)?;
+/// assert!(!diffs[0].is_significant()); // a and b
+/// assert!(diffs[1].is_significant()); // a and c
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn tukey_hsd(
+    groups: &[Summary],
+    confidence: Confidence,
+) -> Result<Vec<PairwiseDifference>, Error> {
+    let alpha = confidence.checked_alpha()?;
+    check(groups)?;
+
+    // Calculate the pooled variance of all the groups.
+    let k = groups.len() as f64;
+    let df = groups.iter().map(|g| g.n - 1.0).sum::<f64>();
+    let pooled = groups.iter().map(|g| (g.n - 1.0) * g.variance).sum::<f64>() / df;
+    if pooled == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Compare each pair using the same critical value.
+    let q_crit = studentized_range_quantile(1.0 - alpha, k, df);
+    Ok(pairs(groups.len())
+        .map(|(i, j)| {
+            let (a, b) = (&groups[i], &groups[j]);
+            let delta = b.mean - a.mean;
+            let std_err = (pooled * (1.0 / a.n + 1.0 / b.n)).sqrt();
+            let scale = std_err / SQRT_2; // the studentized range is scaled by √2
+            PairwiseDifference {
+                a: i,
+                b: j,
+                delta,
+                std_err,
+                ci_low: delta - q_crit * scale,
+                ci_high: delta + q_crit * scale,
+                p_value: 1.0 - studentized_range_cdf(delta.abs() / scale, k, df),
+                alpha,
+            }
+        })
+        .collect())
+}
+
+/// Compare every pair of the given groups using the Games-Howell test, which does not assume the
+/// groups have equal variances. The confidence level must be in the range `(0, 100)`.
+///
+/// Each pair is compared using its own standard error and Welch-Satterthwaite degrees of freedom,
+/// as in Welch's t-test, with the studentized range distribution accounting for the number of
+/// groups. The differences are returned in order of their groups' indexes, i.e. `(0, 1), (0, 2),
+/// …, (1, 2), …`.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::posthoc::games_howell;
+/// use nanostat::Summary;
+///
+/// let a: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+/// let b: Summary = [2.0, 4.0, 6.0, 8.0, 10.0].iter().collect();
+/// let c: Summary = [5.0, 6.0, 7.0, 8.0, 9.0].iter().collect();
+/// let diffs = games_howell(&[a, b, c], Confidence::P95)?;
+/// assert!(!diffs[0].is_significant()); // a and b
+/// assert!(diffs[1].is_significant()); // a and c
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn games_howell(
+    groups: &[Summary],
+    confidence: Confidence,
+) -> Result<Vec<PairwiseDifference>, Error> {
+    let alpha = confidence.checked_alpha()?;
+    check(groups)?;
+    if groups.iter().any(|g| g.variance == 0.0) {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Compare each pair using its own critical value.
+    let k = groups.len() as f64;
+    Ok(pairs(groups.len())
+        .map(|(i, j)| {
+            let (a, b) = (&groups[i], &groups[j]);
+            let delta = b.mean - a.mean;
+            let (se_a, se_b) = (a.variance / a.n, b.variance / b.n);
+            let std_err = (se_a + se_b).sqrt();
+            let scale = std_err / SQRT_2; // the studentized range is scaled by √2
+            let df = (se_a + se_b).powf(2.0)
+                / (se_a.powf(2.0) / (a.n - 1.0) + se_b.powf(2.0) / (b.n - 1.0));
+            let q_crit = studentized_range_quantile(1.0 - alpha, k, df);
+            PairwiseDifference {
+                a: i,
+                b: j,
+                delta,
+                std_err,
+                ci_low: delta - q_crit * scale,
+                ci_high: delta + q_crit * scale,
+                p_value: 1.0 - studentized_range_cdf(delta.abs() / scale, k, df),
+                alpha,
+            }
+        })
+        .collect())
+}
+
+/// Compare each of the given experiment groups with the control group, which must be the first,
+/// using Dunnett's test, which assumes the groups have equal variances. The confidence level must
+/// be in the range `(0, 100)`.
+///
+/// This is more powerful than comparing every pair of groups, or correcting each of a control's
+/// comparisons with [crate::corrections::bonferroni], because it accounts for the correlation
+/// between comparisons which share a control. The differences are returned in order, i.e. `(0,
+/// 1), (0, 2), …`.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::posthoc::dunnett;
+/// use nanostat::Summary;
+///
+/// let control: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+/// let a: Summary = [2.0, 3.0, 4.0, 5.0, 6.0].iter().collect();
+/// let b: Summary = [5.0, 6.0, 7.0, 8.0, 9.0].iter().collect();
+/// let diffs = dunnett(&[control, a, b], Confidence::P95)?;
+/// assert!(!diffs[0].is_significant());
+/// assert!(diffs[1].is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn dunnett(
+    groups: &[Summary],
+    confidence: Confidence,
+) -> Result<Vec<PairwiseDifference>, Error> {
+    let alpha = confidence.checked_alpha()?;
+    check(groups)?;
+
+    // Calculate the pooled variance of all the groups.
+    let df = groups.iter().map(|g| g.n - 1.0).sum::<f64>();
+    let pooled = groups.iter().map(|g| (g.n - 1.0) * g.variance).sum::<f64>() / df;
+    if pooled == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Calculate the correlation structure of the comparisons, which share the control.
+    let ctrl = &groups[0];
+    let lambdas: Vec<f64> = groups[1..].iter().map(|g| (g.n / (g.n + ctrl.n)).sqrt()).collect();
+
+    // Compare each experiment with the control using the same critical value.
+    let crit = quantile(1.0 - alpha, |c| dunnett_cdf(c, &lambdas, df));
+    Ok(groups
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(j, b)| {
+            let delta = b.mean - ctrl.mean;
+            let std_err = (pooled * (1.0 / ctrl.n + 1.0 / b.n)).sqrt();
+            PairwiseDifference {
+                a: 0,
+                b: j,
+                delta,
+                std_err,
+                ci_low: delta - crit * std_err,
+                ci_high: delta + crit * std_err,
+                p_value: 1.0 - dunnett_cdf(delta.abs() / std_err, &lambdas, df),
+                alpha,
+            }
+        })
+        .collect())
+}
+
+/// Check that the groups can be compared.
+fn check(groups: &[Summary]) -> Result<(), Error> {
+    if groups.len() < 2 {
+        return Err(Error::InsufficientSamples);
+    }
+    for g in groups {
+        g.check()?;
+    }
+    Ok(())
+}
+
+/// Iterate over the indexes of every pair of `k` groups.
+fn pairs(k: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..k).flat_map(move |i| (i + 1..k).map(move |j| (i, j)))
+}
+
+/// Calculate the CDF of the studentized range of `k` independent standard normal variables,
+/// divided by an independent scale with the given degrees of freedom.
+fn studentized_range_cdf(q: f64, k: f64, df: f64) -> f64 {
+    // The probability that the range of the variables is at most w is the probability that, for
+    // some variable z, all the others fall in [z - w, z]. Integrate over z using Simpson's rule,
+    // calculating everything which doesn't depend on w in advance.
+    let dist_norm = Normal::new(0.0, 1.0).unwrap();
+    let points: Vec<(f64, f64, f64)> =
+        simpson(-8.0, 8.0).map(|(z, w)| (z, w * k * dist_norm.pdf(z), dist_norm.cdf(z))).collect();
+    let range_cdf = |r: f64| {
+        points.iter().map(|(z, w, p)| w * (p - dist_norm.cdf(z - r)).powf(k - 1.0)).sum::<f64>()
+    };
+    let p: f64 = scale_points(df).iter().map(|(s, w)| w * range_cdf(q * s)).sum();
+    p.clamp(0.0, 1.0)
+}
+
+/// Find the given quantile of the studentized range distribution.
+fn studentized_range_quantile(p: f64, k: f64, df: f64) -> f64 {
+    quantile(p, |q| studentized_range_cdf(q, k, df))
+}
+
+/// Calculate the CDF of the largest absolute value of several correlated t-statistics which share
+/// a control, given the square roots of the experiments' shares of the sample sizes of their
+/// comparisons.
+fn dunnett_cdf(c: f64, lambdas: &[f64], df: f64) -> f64 {
+    // Each statistic's numerator is λX + √(1 - λ²)E for independent standard normal variables X
+    // and E, with X shared by all of them, so integrate over X using Simpson's rule.
+    let dist_norm = Normal::new(0.0, 1.0).unwrap();
+    let points: Vec<(f64, f64)> =
+        simpson(-8.0, 8.0).map(|(x, w)| (x, w * dist_norm.pdf(x))).collect();
+    let max_cdf = |r: f64| {
+        points
+            .iter()
+            .map(|(x, w)| {
+                w * lambdas
+                    .iter()
+                    .map(|l| {
+                        let s = (1.0 - l * l).sqrt();
+                        dist_norm.cdf((r - l * x) / s) - dist_norm.cdf((-r - l * x) / s)
+                    })
+                    .product::<f64>()
+            })
+            .sum::<f64>()
+    };
+    let p: f64 = scale_points(df).iter().map(|(s, w)| w * max_cdf(c * s)).sum();
+    p.clamp(0.0, 1.0)
+}
+
+/// Find the given quantile of a non-negative distribution with the given CDF by bisection.
+fn quantile(p: f64, cdf: impl Fn(f64) -> f64) -> f64 {
+    let mut high = 4.0;
+    while cdf(high) < p {
+        high *= 2.0;
+    }
+    let mut low = 0.0;
+    for _ in 0..48 {
+        let mid = (low + high) / 2.0;
+        if cdf(mid) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Calculate the points and weights with which to integrate a function over `[low, high]` using
+/// Simpson's rule.
+fn simpson(low: f64, high: f64) -> impl Iterator<Item = (f64, f64)> {
+    const STEPS: usize = 100;
+
+    let h = (high - low) / STEPS as f64;
+    (0..=STEPS).map(move |i| {
+        let w = match i {
+            0 | STEPS => h / 3.0,
+            i if i % 2 == 1 => 4.0 * h / 3.0,
+            _ => 2.0 * h / 3.0,
+        };
+        (low + h * i as f64, w)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn studentized_range() {
+        assert_relative_eq!(
+            studentized_range_cdf(3.5, 3.0, 12.0),
+            0.9300045147248137,
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            studentized_range_cdf(2.0, 5.0, 4.0),
+            0.3484899414742461,
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            studentized_range_quantile(0.95, 3.0, 10.0),
+            3.876776750012141,
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn tukey() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+        let b: Summary = [2.0, 3.0, 4.0, 5.0, 6.0, 7.0].iter().collect();
+        let c: Summary = [5.0, 6.0, 7.0, 8.0, 9.5].iter().collect();
+        let diffs = tukey_hsd(&[a, b, c], Confidence::P95).unwrap();
+
+        assert_eq!(diffs.iter().map(|d| (d.a, d.b)).collect::<Vec<_>>(), [(0, 1), (0, 2), (1, 2)]);
+        assert_relative_eq!(diffs[0].delta, 1.5);
+        assert_relative_eq!(diffs[0].std_err, 1.0581793750322248, epsilon = 1e-12);
+        assert_relative_eq!(diffs[0].p_value, 0.36092278063942684, epsilon = 1e-9);
+        assert_relative_eq!(diffs[0].ci_low, -1.294056110320189, epsilon = 1e-9);
+        assert_relative_eq!(diffs[0].ci_high, 4.294056110320189, epsilon = 1e-9);
+        assert!(!diffs[0].is_significant());
+        assert_relative_eq!(diffs[1].p_value, 0.006885466883438163, epsilon = 1e-9);
+        assert!(diffs[1].is_significant());
+    }
+
+    #[test]
+    fn games_howell_test() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+        let b: Summary = [2.0, 3.0, 4.0, 5.0, 6.0, 7.0].iter().collect();
+        let c: Summary = [5.0, 6.0, 7.0, 8.0, 9.5].iter().collect();
+        let diffs = games_howell(&[a, b, c], Confidence::P95).unwrap();
+
+        assert_eq!(diffs.len(), 3);
+        assert_relative_eq!(diffs[0].std_err, 1.0408329997330665, epsilon = 1e-12);
+        assert_relative_eq!(diffs[0].p_value, 0.36194852722553905, epsilon = 1e-9);
+        assert_relative_eq!(diffs[0].ci_low, -1.406645181241818, epsilon = 1e-9);
+        assert_relative_eq!(diffs[0].ci_high, 4.406645181241818, epsilon = 1e-9);
+        assert!(!diffs[0].is_significant());
+        assert_relative_eq!(diffs[1].p_value, 0.01156538408123875, epsilon = 1e-9);
+        assert_relative_eq!(diffs[1].ci_low, 1.083283786889425, epsilon = 1e-9);
+        assert!(diffs[1].is_significant());
+    }
+
+    #[test]
+    fn dunnett_test() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+        let b: Summary = [2.0, 3.0, 4.0, 5.0, 6.0, 7.0].iter().collect();
+        let c: Summary = [5.0, 6.0, 7.0, 8.0, 9.5].iter().collect();
+        let diffs = dunnett(&[a, b, c], Confidence::P95).unwrap();
+
+        assert_eq!(diffs.iter().map(|d| (d.a, d.b)).collect::<Vec<_>>(), [(0, 1), (0, 2)]);
+        assert_relative_eq!(diffs[0].std_err, 1.0581793750322248, epsilon = 1e-12);
+        assert_relative_eq!(diffs[0].p_value, 0.295755883074589, epsilon = 1e-9);
+        assert_relative_eq!(diffs[0].ci_low, -1.1179194821320153, epsilon = 1e-9);
+        assert_relative_eq!(diffs[0].ci_high, 4.117919482132015, epsilon = 1e-9);
+        assert!(!diffs[0].is_significant());
+        assert_relative_eq!(diffs[1].p_value, 0.004888417570926729, epsilon = 1e-9);
+        assert!(diffs[1].is_significant());
+
+        // With a single experiment, it's a pooled t-test.
+        let diffs = dunnett(&[a, b], Confidence::P95).unwrap();
+        assert_relative_eq!(diffs[0].p_value, 0.19011811321303287, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn posthoc_errors() {
+        let a: Summary = [1.0, 2.0, 3.0, 4.0].iter().collect();
+        let flat: Summary = [1.0, 1.0].iter().collect();
+
+        assert_eq!(tukey_hsd(&[a], Confidence::P95).unwrap_err(), Error::InsufficientSamples);
+        assert_eq!(tukey_hsd(&[flat, flat], Confidence::P95).unwrap_err(), Error::ZeroVariance);
+        assert_eq!(games_howell(&[a, flat], Confidence::P95).unwrap_err(), Error::ZeroVariance);
+        assert_eq!(dunnett(&[a], Confidence::P95).unwrap_err(), Error::InsufficientSamples);
+    }
+}
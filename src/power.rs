@@ -0,0 +1,224 @@
+//! Power analysis for planning experiments, such as how many measurements are needed to reliably
+//! detect a regression of a given size.
+
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+use crate::{Confidence, Error, Summary};
+
+/// The smallest difference in means which a comparison can reliably detect.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DetectableEffect {
+    /// The smallest absolute difference in means which can be detected.
+    pub delta: f64,
+
+    /// The smallest difference in means which can be detected, as a percentage of the first
+    /// sample's mean.
+    pub relative_delta: f64,
+}
+
+/// Calculate the number of measurements required in each of two samples for a two-tailed Welch's
+/// t-test to detect a difference in means of the given effect size (Cohen's d) with the given
+/// power. The confidence level must be in the range `(0, 100)`, and the power in the range `(0,
+/// 1)`.
+///
+/// To detect a relative change in the mean, divide it by the coefficient of variation of the
+/// measurements:
+///
+/// ```
+/// use nanostat::power::required_n;
+/// use nanostat::{Confidence, Summary};
+///
+/// let s: Summary = [99.0, 101.0, 100.5, 98.7, 100.2, 101.3, 99.6].iter().collect();
+/// let n = required_n(0.02 / s.coefficient_of_variation(), Confidence::P95, 0.8)?;
+/// assert_eq!(n, 5);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn required_n(effect_size: f64, confidence: Confidence, power: f64) -> Result<usize, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if !(effect_size.is_finite() && effect_size > 0.0) {
+        return Err(Error::InvalidEffectSize(effect_size));
+    }
+    if !(0.0 < power && power < 1.0) {
+        return Err(Error::InvalidPower(power));
+    }
+
+    // The power increases with the sample size, so double it until it's sufficient and then
+    // narrow it down with a binary search.
+    let sufficient = |n: usize| -> Result<bool, Error> {
+        Ok(two_sample_power(effect_size, n as f64, n as f64, alpha)? >= power)
+    };
+    let mut high = 2;
+    while !sufficient(high)? {
+        high *= 2;
+    }
+    let mut low = high / 2;
+    while high - low > 1 {
+        let mid = (low + high) / 2;
+        if sufficient(mid)? {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    Ok(high)
+}
+
+/// Calculate the smallest difference in means which a two-tailed Welch's t-test of the two samples
+/// can detect with the given power. The confidence level must be in the range `(0, 100)`, and the
+/// power in the range `(0, 1)`.
+///
+/// This answers whether a benchmark is sensitive enough to detect the changes of interest. To
+/// plan an experiment of `n` measurements using a pilot sample, pass a summary with the same mean
+/// and variance created with [Summary::from_parts] as the second sample.
+///
+/// ```
+/// use nanostat::power::minimum_detectable_effect;
+/// use nanostat::{Confidence, Summary};
+///
+/// let a = Summary::from_parts(20.0, 100.0, 16.0)?;
+/// let b = Summary::from_parts(20.0, 100.0, 16.0)?;
+/// let mde = minimum_detectable_effect(&a, &b, Confidence::P95, 0.8)?;
+/// assert!(mde.relative_delta < 4.0);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn minimum_detectable_effect(
+    a: &Summary,
+    b: &Summary,
+    confidence: Confidence,
+    power: f64,
+) -> Result<DetectableEffect, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if !(0.0 < power && power < 1.0) {
+        return Err(Error::InvalidPower(power));
+    }
+    let (std_err, dist_st) = a.welch(b)?;
+
+    // Find the noncentrality parameter of the t-statistic which gives the required power, doubling
+    // an upper bound for it and then bisecting.
+    let mut high = 1.0;
+    while power_at(high, &dist_st, alpha) < power {
+        high *= 2.0;
+    }
+    let mut low = 0.0;
+    for _ in 0..64 {
+        let mid = (low + high) / 2.0;
+        if power_at(mid, &dist_st, alpha) < power {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    let delta = high * std_err;
+    Ok(DetectableEffect { delta, relative_delta: 100.0 * delta / a.mean.abs() })
+}
+
+/// Calculate the power of a two-tailed Welch's t-test of the two samples to detect each of the
+/// given absolute differences in means, returning `(delta, power)` points. The confidence level
+/// must be in the range `(0, 100)`.
+///
+/// ```
+/// use nanostat::power::power_curve;
+/// use nanostat::{Confidence, Summary};
+///
+/// let a = Summary::from_parts(20.0, 100.0, 16.0)?;
+/// let curve = power_curve(&a, &a, Confidence::P95, &[0.0, 1.0, 2.0, 4.0, 8.0])?;
+/// assert!(curve.windows(2).all(|w| w[0].1 < w[1].1));
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn power_curve(
+    a: &Summary,
+    b: &Summary,
+    confidence: Confidence,
+    deltas: &[f64],
+) -> Result<Vec<(f64, f64)>, Error> {
+    let alpha = confidence.checked_alpha()?;
+    let (std_err, dist_st) = a.welch(b)?;
+    Ok(deltas.iter().map(|&d| (d, power_at(d / std_err, &dist_st, alpha))).collect())
+}
+
+/// Calculate the power of a two-tailed Welch's t-test to detect the given effect size in samples of
+/// the given sizes with equal variances, approximating the noncentral t-distribution with a
+/// shifted central one.
+fn two_sample_power(effect_size: f64, n_a: f64, n_b: f64, alpha: f64) -> Result<f64, Error> {
+    // Calculate the degrees of freedom and the noncentrality parameter.
+    let (v_a, v_b) = (1.0 / n_a, 1.0 / n_b);
+    let df = (v_a + v_b).powf(2.0) / (v_a.powf(2.0) / (n_a - 1.0) + v_b.powf(2.0) / (n_b - 1.0));
+    let delta = effect_size / (v_a + v_b).sqrt();
+
+    let dist_st = StudentsT::new(0.0, 1.0, df).map_err(|_| Error::NonFinite)?;
+    Ok(power_at(delta, &dist_st, alpha))
+}
+
+/// Calculate the power of a two-tailed t-test given the noncentrality parameter of the t-statistic
+/// and its distribution under the null hypothesis.
+fn power_at(delta: f64, dist_st: &StudentsT, alpha: f64) -> f64 {
+    // Calculate the probability of the t-statistic falling outside the critical values.
+    let t_crit = dist_st.inverse_cdf(1.0 - alpha / 2.0);
+    1.0 - dist_st.cdf(t_crit - delta) + dist_st.cdf(-t_crit - delta)
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn required_sample_size() {
+        assert_eq!(required_n(0.5, Confidence::P95, 0.8).unwrap(), 64);
+        assert_eq!(required_n(0.8, Confidence::P95, 0.8).unwrap(), 26);
+        assert_eq!(required_n(0.2, Confidence::P95, 0.8).unwrap(), 394);
+        assert_eq!(required_n(10.0, Confidence::P95, 0.8).unwrap(), 2);
+
+        assert_relative_eq!(
+            two_sample_power(0.5, 64.0, 64.0, 0.05).unwrap(),
+            0.8013829274577107,
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn detectable_effect() {
+        let a = Summary::from_parts(20.0, 100.0, 16.0).unwrap();
+        let mde = minimum_detectable_effect(&a, &a, Confidence::P95, 0.8).unwrap();
+
+        assert_relative_eq!(mde.delta, 3.637307227837668, epsilon = 1e-6);
+        assert_relative_eq!(mde.relative_delta, 3.637307227837668, epsilon = 1e-6);
+
+        // A larger sample can detect a smaller effect.
+        let b = Summary::from_parts(80.0, 100.0, 16.0).unwrap();
+        assert!(minimum_detectable_effect(&a, &b, Confidence::P95, 0.8).unwrap().delta < mde.delta);
+
+        assert_eq!(
+            minimum_detectable_effect(&a, &a, Confidence::P95, 0.0).unwrap_err(),
+            Error::InvalidPower(0.0)
+        );
+    }
+
+    #[test]
+    fn power_curves() {
+        let a = Summary::from_parts(20.0, 100.0, 16.0).unwrap();
+        let mde = minimum_detectable_effect(&a, &a, Confidence::P95, 0.8).unwrap();
+        let curve = power_curve(&a, &a, Confidence::P95, &[0.0, mde.delta, -mde.delta]).unwrap();
+
+        // With no difference, the power is the probability of a false positive.
+        assert_relative_eq!(curve[0].1, 0.05, epsilon = 1e-9);
+        assert_relative_eq!(curve[1].1, 0.8, epsilon = 1e-9);
+        assert_relative_eq!(curve[2].1, 0.8, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn required_sample_size_errors() {
+        assert_eq!(
+            required_n(0.0, Confidence::P95, 0.8).unwrap_err(),
+            Error::InvalidEffectSize(0.0)
+        );
+        assert_eq!(required_n(0.5, Confidence::P95, 1.0).unwrap_err(), Error::InvalidPower(1.0));
+        assert_eq!(
+            required_n(0.5, Confidence::Custom(100.0), 0.8).unwrap_err(),
+            Error::InvalidConfidence(100.0)
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Comparisons of proportions, such as error rates or cache hit ratios, rather than of means.
+
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::{unwrap, Confidence, Error};
+
+/// A proportion of trials which were successes, e.g. the number of requests which hit a cache out
+/// of the total number of requests.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Proportion {
+    /// The number of successes.
+    pub successes: u64,
+
+    /// The number of trials.
+    pub trials: u64,
+}
+
+impl Proportion {
+    /// Create a new proportion from the given counts. There must be at least one trial, and no
+    /// more successes than trials.
+    pub const fn new(successes: u64, trials: u64) -> Result<Proportion, Error> {
+        if trials == 0 || successes > trials {
+            return Err(Error::InvalidCounts(successes, trials));
+        }
+        Ok(Proportion { successes, trials })
+    }
+
+    /// The fraction of trials which were successes.
+    #[must_use]
+    pub fn rate(&self) -> f64 {
+        self.successes as f64 / self.trials as f64
+    }
+
+    /// The Wilson score interval for the proportion of the population from which the trials were
+    /// drawn. Unlike the normal approximation, this is well-behaved for few trials and for rates
+    /// close to zero or one. The confidence level must be in the range `(0, 100)`.
+    ///
+    /// ```
+    /// use nanostat::Confidence;
+    /// use nanostat::proportions::Proportion;
+    ///
+    /// // No failures in ten trials is consistent with a failure rate of more than 25%.
+    /// let (_, high) = Proportion::new(0, 10)?.confidence_interval(Confidence::P95);
+    /// assert!(high > 0.25);
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the confidence level is out of range.
+    #[must_use]
+    pub fn confidence_interval(&self, confidence: Confidence) -> (f64, f64) {
+        let alpha = unwrap(confidence.checked_alpha());
+        let z = Normal::new(0.0, 1.0).unwrap().inverse_cdf(1.0 - alpha / 2.0);
+        wilson(self.rate(), self.trials as f64, z)
+    }
+
+    /// Calculate the statistical difference between the two proportions using a two-tailed
+    /// two-proportion z-test. The confidence level must be in the range `(0, 100)`. Returns
+    /// [Error::CountOverflow] if the total number of successes does not fit in a `u64`.
+    ///
+    /// ```
+    /// use nanostat::Confidence;
+    /// use nanostat::proportions::Proportion;
+    ///
+    /// let control = Proportion::new(45, 100)?;
+    /// let experiment = Proportion::new(60, 100)?;
+    /// assert!(control.compare(&experiment, Confidence::P95)?.is_significant());
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    pub fn compare(
+        &self,
+        other: &Proportion,
+        confidence: Confidence,
+    ) -> Result<ProportionDifference, Error> {
+        let alpha = confidence.checked_alpha()?;
+        let (a, b) = (self, other);
+        let (p_a, p_b) = (a.rate(), b.rate());
+        let (n_a, n_b) = (a.trials as f64, b.trials as f64);
+
+        // Calculate the standard error of the difference under the null hypothesis, using the
+        // pooled proportion.
+        let successes = a.successes.checked_add(b.successes).ok_or(Error::CountOverflow)?;
+        let pooled = successes as f64 / (n_a + n_b);
+        let std_err = (pooled * (1.0 - pooled) * (1.0 / n_a + 1.0 / n_b)).sqrt();
+        if std_err == 0.0 {
+            return Err(Error::ZeroVariance);
+        }
+
+        // Calculate the z-statistic and the p-value given it.
+        let delta = p_b - p_a;
+        let z = delta / std_err;
+        let dist_norm = Normal::new(0.0, 1.0).unwrap();
+        let p_value = dist_norm.cdf(-z.abs()) * 2.0;
+
+        // Calculate the confidence interval for the difference by combining the Wilson intervals
+        // of the two proportions (Newcombe's hybrid score interval).
+        let z_crit = dist_norm.inverse_cdf(1.0 - alpha / 2.0);
+        let (low_a, high_a) = wilson(p_a, n_a, z_crit);
+        let (low_b, high_b) = wilson(p_b, n_b, z_crit);
+        let ci_low = delta - ((p_b - low_b).powf(2.0) + (high_a - p_a).powf(2.0)).sqrt();
+        let ci_high = delta + ((high_b - p_b).powf(2.0) + (p_a - low_a).powf(2.0)).sqrt();
+
+        Ok(ProportionDifference {
+            delta,
+            relative_risk: p_b / p_a,
+            ci_low,
+            ci_high,
+            z,
+            p_value,
+            alpha,
+        })
+    }
+}
+
+/// The statistical difference between two [Proportion] instances.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProportionDifference {
+    /// The risk difference: the second proportion's rate minus the first's.
+    pub delta: f64,
+
+    /// The relative risk: the second proportion's rate divided by the first's. It is infinite if
+    /// the first proportion has no successes.
+    pub relative_risk: f64,
+
+    /// The lower bound of Newcombe's confidence interval for the risk difference.
+    pub ci_low: f64,
+
+    /// The upper bound of Newcombe's confidence interval for the risk difference.
+    pub ci_high: f64,
+
+    /// The z-statistic for the test.
+    pub z: f64,
+
+    /// The two-tailed p-value for the test.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl ProportionDifference {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Calculate the Wilson score interval for the given rate and number of trials, given the critical
+/// z-value.
+fn wilson(p: f64, n: f64, z: f64) -> (f64, f64) {
+    let d = 1.0 + z * z / n;
+    let center = (p + z * z / (2.0 * n)) / d;
+    let h = z / d * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt();
+    ((center - h).max(0.0), (center + h).min(1.0))
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn wilson_interval() {
+        let (low, high) = Proportion::new(45, 100).unwrap().confidence_interval(Confidence::P95);
+        assert_relative_eq!(low, 0.3561453797951198, epsilon = 1e-9);
+        assert_relative_eq!(high, 0.5475539700255787, epsilon = 1e-9);
+
+        let (low, high) = Proportion::new(0, 10).unwrap().confidence_interval(Confidence::P95);
+        assert_relative_eq!(low, 0.0);
+        assert_relative_eq!(high, 0.27753279986288925, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn compare_proportions() {
+        let a = Proportion::new(45, 100).unwrap();
+        let b = Proportion::new(60, 100).unwrap();
+        let diff = a.compare(&b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(diff.delta, 0.15, epsilon = 1e-12);
+        assert_relative_eq!(diff.relative_risk, 4.0 / 3.0, epsilon = 1e-12);
+        assert_relative_eq!(diff.z, 2.123976976214366, epsilon = 1e-12);
+        assert_relative_eq!(diff.p_value, 0.03367206885634583, epsilon = 1e-9);
+        assert_relative_eq!(diff.ci_low, 0.011723935319973407, epsilon = 1e-9);
+        assert_relative_eq!(diff.ci_high, 0.2804485210107637, epsilon = 1e-9);
+        assert!(diff.is_significant());
+
+        // The test is symmetric.
+        let diff = b.compare(&a, Confidence::P95).unwrap();
+        assert_relative_eq!(diff.z, -2.123976976214366, epsilon = 1e-12);
+        assert_relative_eq!(diff.ci_high, -0.011723935319973407, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn proportion_errors() {
+        assert_eq!(Proportion::new(11, 10).unwrap_err(), Error::InvalidCounts(11, 10));
+        assert_eq!(Proportion::new(0, 0).unwrap_err(), Error::InvalidCounts(0, 0));
+
+        let none = Proportion::new(0, 10).unwrap();
+        assert_eq!(none.compare(&none, Confidence::P95).unwrap_err(), Error::ZeroVariance);
+
+        let all = Proportion::new(u64::MAX, u64::MAX).unwrap();
+        assert_eq!(all.compare(&all, Confidence::P95).unwrap_err(), Error::CountOverflow);
+    }
+}
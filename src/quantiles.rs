@@ -0,0 +1,503 @@
+//! Order statistics, such as the median and tail percentiles, of raw measurements.
+
+use std::borrow::Cow;
+use std::f64::consts::PI;
+use std::iter::FromIterator;
+
+use statrs::distribution::{Binomial, DiscreteCDF};
+
+use crate::{Confidence, Error, Summary};
+
+/// The exact quantiles of a data set, which retains all of its measurements.
+///
+/// Created from an iterable of `f64`s or `&f64`s:
+///
+/// ```
+/// use nanostat::quantiles::Quantiles;
+///
+/// let latencies: Quantiles = [12.0, 15.0, 11.0, 14.0, 90.0, 13.0, 12.0, 16.0, 13.0, 14.0]
+///     .iter()
+///     .collect();
+/// assert_eq!(latencies.median(), 13.5);
+/// assert_eq!(latencies.percentile(99.0), 83.34);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quantiles {
+    sorted: Vec<f64>,
+}
+
+impl FromIterator<f64> for Quantiles {
+    fn from_iter<T: IntoIterator<Item = f64>>(iter: T) -> Self {
+        let mut sorted: Vec<f64> = iter.into_iter().collect();
+        sorted.sort_by(f64::total_cmp);
+        Quantiles { sorted }
+    }
+}
+
+impl<'a> FromIterator<&'a f64> for Quantiles {
+    fn from_iter<T: IntoIterator<Item = &'a f64>>(iter: T) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl Quantiles {
+    /// The number of measurements in the set.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.sorted.len()
+    }
+
+    /// Whether or not the set has no measurements.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.sorted.is_empty()
+    }
+
+    /// The measurements in the set, in ascending order.
+    #[must_use]
+    pub fn values(&self) -> &[f64] {
+        &self.sorted
+    }
+
+    /// The `p` quantile of the measurements, where `p` is in the range `[0, 1]`, calculated by
+    /// linear interpolation between the closest ranks. Returns NaN if the set is empty.
+    #[must_use]
+    pub fn quantile(&self, p: f64) -> f64 {
+        if self.sorted.is_empty() {
+            return f64::NAN;
+        }
+        quantile(&self.sorted, p)
+    }
+
+    /// The `p`th percentile of the measurements, where `p` is in the range `[0, 100]`.
+    #[must_use]
+    pub fn percentile(&self, p: f64) -> f64 {
+        self.quantile(p / 100.0)
+    }
+
+    /// The median of the measurements.
+    #[must_use]
+    pub fn median(&self) -> f64 {
+        self.quantile(0.5)
+    }
+
+    /// The interquartile range of the measurements: the difference between the third and first
+    /// quartiles.
+    #[must_use]
+    pub fn iqr(&self) -> f64 {
+        self.quantile(0.75) - self.quantile(0.25)
+    }
+
+    /// The median absolute deviation (MAD) of the measurements from their median, scaled by
+    /// `1.4826` to be a consistent estimator of the standard deviation of a normal distribution.
+    /// Unlike [Summary::std_dev], it is robust to outliers. Returns NaN if the set is empty.
+    ///
+    /// ```
+    /// use nanostat::quantiles::Quantiles;
+    ///
+    /// let latencies: Quantiles = [12.0, 15.0, 11.0, 14.0, 90.0, 13.0, 12.0, 16.0, 13.0, 14.0]
+    ///     .iter()
+    ///     .collect();
+    /// assert!((latencies.mad() - 2.2239).abs() < 1e-4);
+    /// ```
+    #[must_use]
+    pub fn mad(&self) -> f64 {
+        // The reciprocal of the third quartile of the standard normal distribution.
+        const SCALE: f64 = 1.482_602_218_505_602;
+
+        let m = self.median();
+        let deviations: Quantiles = self.sorted.iter().map(|x| (x - m).abs()).collect();
+        SCALE * deviations.median()
+    }
+
+    /// The distribution-free tolerance interval which contains the given proportion of the
+    /// population from which the measurements were drawn, at the given confidence level. The
+    /// coverage must be in the range `(0, 1)` and the confidence level must be in the range `(0,
+    /// 100)`.
+    ///
+    /// The interval is bounded by the pair of order statistics, symmetric about the median, which
+    /// are furthest from the extremes while still providing the required coverage. Unlike
+    /// [Summary::tolerance_interval], this makes no assumptions about the shape of the
+    /// distribution, but it requires many more measurements: at least 46 for 90% coverage with 95%
+    /// confidence, for example.
+    ///
+    /// ```
+    /// use nanostat::Confidence;
+    /// use nanostat::quantiles::Quantiles;
+    ///
+    /// let runs: Quantiles = (1..=100).map(f64::from).collect();
+    /// assert_eq!(runs.tolerance_interval(0.9, Confidence::P95)?, (2.0, 99.0));
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    pub fn tolerance_interval(
+        &self,
+        coverage: f64,
+        confidence: Confidence,
+    ) -> Result<(f64, f64), Error> {
+        let alpha = confidence.checked_alpha()?;
+        if !(coverage > 0.0 && coverage < 1.0) {
+            return Err(Error::InvalidCoverage(coverage));
+        }
+        let n = self.sorted.len();
+
+        // The coverage of the interval between the rth smallest and rth largest measurements is at
+        // least the required coverage unless more than n - 2r of the measurements fall below the
+        // corresponding quantile, so find the largest r for which that is unlikely enough.
+        let dist_b = Binomial::new(coverage, n as u64).unwrap();
+        let r = (1..=n / 2).take_while(|&r| dist_b.cdf((n - 2 * r) as u64) >= 1.0 - alpha).last();
+        match r {
+            Some(r) => Ok((self.sorted[r - 1], self.sorted[n - r])),
+            None => Err(Error::InsufficientSamples),
+        }
+    }
+
+    /// A [Summary] of the measurements, for comparison with other data sets.
+    #[must_use]
+    pub fn summary(&self) -> Summary {
+        self.sorted.iter().collect()
+    }
+}
+
+/// A t-digest (Dunning & Ertl, 2019), which estimates the quantiles of a stream of measurements in
+/// bounded memory by clustering them into weighted centroids. Clusters are kept small near the
+/// tails of the distribution, so extreme quantiles like p99.9 remain accurate.
+///
+/// Created from an iterable of `f64`s or `&f64`s, or incrementally with [TDigest::push]:
+///
+/// ```
+/// use nanostat::quantiles::TDigest;
+///
+/// let mut digest = TDigest::new(100.0);
+/// for i in 0..100_000 {
+///     digest.push(f64::from(i % 1000));
+/// }
+/// assert!((digest.percentile(99.0) - 990.0).abs() < 1.0);
+/// assert_eq!(digest.summary().n, 100_000.0);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<Centroid>,
+    min: f64,
+    max: f64,
+    summary: Summary,
+}
+
+/// A cluster of measurements in a [TDigest].
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+impl Default for TDigest {
+    fn default() -> Self {
+        TDigest::new(100.0)
+    }
+}
+
+impl FromIterator<f64> for TDigest {
+    fn from_iter<T: IntoIterator<Item = f64>>(iter: T) -> Self {
+        let mut digest = TDigest::default();
+        digest.extend(iter);
+        digest
+    }
+}
+
+impl<'a> FromIterator<&'a f64> for TDigest {
+    fn from_iter<T: IntoIterator<Item = &'a f64>>(iter: T) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl Extend<f64> for TDigest {
+    fn extend<T: IntoIterator<Item = f64>>(&mut self, iter: T) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<'a> Extend<&'a f64> for TDigest {
+    fn extend<T: IntoIterator<Item = &'a f64>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl TDigest {
+    /// Create an empty digest with the given compression, which bounds the number of centroids it
+    /// retains. Higher compressions are more accurate but use more memory; 100 is typical.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the compression is less than 1.
+    #[must_use]
+    pub fn new(compression: f64) -> TDigest {
+        assert!(compression >= 1.0, "compression must be at least 1, was {}", compression);
+        TDigest {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            summary: Summary::EMPTY,
+        }
+    }
+
+    /// Add a measurement to the digest.
+    pub fn push(&mut self, x: f64) {
+        self.buffer.push(Centroid { mean: x, weight: 1.0 });
+        self.summary.extend([x]);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        if self.buffer.len() as f64 >= 5.0 * self.compression {
+            self.compress();
+        }
+    }
+
+    /// Add all the measurements of the other digest to this one.
+    pub fn merge(&mut self, other: &TDigest) {
+        self.buffer.extend(other.centroids.iter().chain(other.buffer.iter()));
+        self.summary = self.summary.merge(&other.summary);
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.compress();
+    }
+
+    /// The number of measurements in the digest.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.summary.n as usize
+    }
+
+    /// Whether or not the digest has no measurements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.summary.n == 0.0
+    }
+
+    /// The estimated `p` quantile of the measurements, where `p` is in the range `[0, 1]`. Returns
+    /// NaN if the digest is empty.
+    #[must_use]
+    pub fn quantile(&self, p: f64) -> f64 {
+        let centroids = if self.buffer.is_empty() {
+            Cow::Borrowed(&self.centroids)
+        } else {
+            Cow::Owned(self.merged())
+        };
+        let (first, last) = match (centroids.first(), centroids.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return f64::NAN,
+        };
+
+        // Treat each centroid as centered at the middle of its weight and interpolate between them,
+        // or between the outermost centroids and the extreme measurements.
+        let total = centroids.iter().map(|c| c.weight).sum::<f64>();
+        let t = p.clamp(0.0, 1.0) * total;
+        if t < first.weight / 2.0 {
+            return self.min + t / (first.weight / 2.0) * (first.mean - self.min);
+        }
+        let mut cum = 0.0;
+        for pair in centroids.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let (center_a, center_b) = (cum + a.weight / 2.0, cum + a.weight + b.weight / 2.0);
+            if t < center_b {
+                return a.mean + (t - center_a) / (center_b - center_a) * (b.mean - a.mean);
+            }
+            cum += a.weight;
+        }
+        let center = total - last.weight / 2.0;
+        last.mean + (t - center) / (last.weight / 2.0) * (self.max - last.mean)
+    }
+
+    /// The estimated `p`th percentile of the measurements, where `p` is in the range `[0, 100]`.
+    #[must_use]
+    pub fn percentile(&self, p: f64) -> f64 {
+        self.quantile(p / 100.0)
+    }
+
+    /// The estimated median of the measurements.
+    #[must_use]
+    pub fn median(&self) -> f64 {
+        self.quantile(0.5)
+    }
+
+    /// An exact [Summary] of the measurements, for comparison with other data sets.
+    #[must_use]
+    pub const fn summary(&self) -> Summary {
+        self.summary
+    }
+
+    /// Merge the buffered measurements into the centroids.
+    fn compress(&mut self) {
+        self.centroids = self.merged();
+        self.buffer.clear();
+    }
+
+    /// Merge the centroids and buffered measurements into as few centroids as the compression
+    /// allows, using the arcsine scale function to keep centroids near the tails small.
+    fn merged(&self) -> Vec<Centroid> {
+        let mut all: Vec<Centroid> =
+            self.centroids.iter().chain(self.buffer.iter()).copied().collect();
+        all.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+        let total = all.iter().map(|c| c.weight).sum::<f64>();
+
+        // Calculate the largest cumulative weight which a centroid starting at q may reach.
+        let delta = self.compression;
+        let limit = |q: f64| {
+            let k = delta / (2.0 * PI) * (2.0 * q - 1.0).asin() + 1.0;
+            if k >= delta / 4.0 {
+                total
+            } else {
+                total * ((2.0 * PI * k / delta).sin() + 1.0) / 2.0
+            }
+        };
+
+        let mut merged = Vec::with_capacity(delta as usize);
+        let mut all = all.into_iter();
+        let mut current = match all.next() {
+            Some(c) => c,
+            None => return merged,
+        };
+        let mut w = 0.0;
+        let mut w_limit = limit(0.0);
+        for next in all {
+            if w + current.weight + next.weight <= w_limit {
+                current.weight += next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / current.weight;
+            } else {
+                w += current.weight;
+                merged.push(current);
+                w_limit = limit(w / total);
+                current = next;
+            }
+        }
+        merged.push(current);
+        merged
+    }
+}
+
+/// Calculate the `p` quantile of the given sorted, non-empty values by linear interpolation.
+pub(crate) fn quantile(sorted: &[f64], p: f64) -> f64 {
+    let h = (sorted.len() - 1) as f64 * p.clamp(0.0, 1.0);
+    let lo = h.floor() as usize;
+    match sorted.get(lo + 1) {
+        Some(hi) => sorted[lo] + (h - lo as f64) * (hi - sorted[lo]),
+        None => sorted[lo],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn interpolated_quantiles() {
+        let v = [1.0, 2.0, 3.0, 4.0];
+
+        assert_relative_eq!(quantile(&v, 0.0), 1.0);
+        assert_relative_eq!(quantile(&v, 0.5), 2.5);
+        assert_relative_eq!(quantile(&v, 1.0), 4.0);
+    }
+
+    #[test]
+    fn distribution_free_tolerance_interval() {
+        let q: Quantiles = (1..=100).map(f64::from).collect();
+        assert_eq!(q.tolerance_interval(0.9, Confidence::P95), Ok((2.0, 99.0)));
+        assert_eq!(q.tolerance_interval(0.99, Confidence::P95), Err(Error::InsufficientSamples));
+        assert_eq!(q.tolerance_interval(1.0, Confidence::P95), Err(Error::InvalidCoverage(1.0)));
+
+        // At least 46 measurements are required for 90% coverage with 95% confidence.
+        let q: Quantiles = (1..=46).map(f64::from).collect();
+        assert_eq!(q.tolerance_interval(0.9, Confidence::P95), Ok((1.0, 46.0)));
+        let q: Quantiles = (1..=45).map(f64::from).collect();
+        assert_eq!(q.tolerance_interval(0.9, Confidence::P95), Err(Error::InsufficientSamples));
+    }
+
+    #[test]
+    fn latency_quantiles() {
+        let q: Quantiles =
+            [12.0, 15.0, 11.0, 14.0, 90.0, 13.0, 12.0, 16.0, 13.0, 14.0].iter().collect();
+
+        assert_eq!(q.len(), 10);
+        assert_relative_eq!(q.values()[0], 11.0);
+        assert_relative_eq!(q.median(), 13.5);
+        assert_relative_eq!(q.percentile(90.0), 23.4, epsilon = 1e-12);
+        assert_relative_eq!(q.percentile(99.0), 83.34, epsilon = 1e-12);
+        assert_relative_eq!(q.iqr(), 2.5);
+        assert_relative_eq!(q.mad(), 2.223903327758403, epsilon = 1e-12);
+        assert_relative_eq!(q.summary().mean, 21.0);
+    }
+
+    #[test]
+    fn empty_quantiles() {
+        let q: Quantiles = std::iter::empty::<f64>().collect();
+
+        assert!(q.is_empty());
+        assert!(q.median().is_nan());
+        assert!(q.mad().is_nan());
+
+        let d = TDigest::default();
+        assert!(d.is_empty());
+        assert!(d.median().is_nan());
+    }
+
+    #[test]
+    fn t_digest_accuracy() {
+        // Draw exponentially distributed measurements, which have a long right tail.
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+        let values: Vec<f64> = (0..100_000).map(|_| -(1.0 - rng.gen::<f64>()).ln()).collect();
+        let exact: Quantiles = values.iter().collect();
+        let digest: TDigest = values.iter().collect();
+
+        assert_eq!(digest.len(), 100_000);
+        assert!(digest.centroids.len() + digest.buffer.len() < 1000);
+        // Check the rank error of each estimate, which is what the digest bounds.
+        for p in [0.001, 0.01, 0.1, 0.5, 0.9, 0.99, 0.999] {
+            let q = digest.quantile(p);
+            let rank = exact.values().partition_point(|&x| x < q) as f64 / exact.len() as f64;
+            assert_relative_eq!(rank, p, epsilon = 5e-3);
+        }
+        assert_relative_eq!(digest.quantile(0.0), exact.quantile(0.0));
+        assert_relative_eq!(digest.quantile(1.0), exact.quantile(1.0));
+        assert_relative_eq!(digest.summary().mean, exact.summary().mean, epsilon = 1e-9);
+        assert_relative_eq!(digest.summary().variance, exact.summary().variance, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn t_digest_merge() {
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+        let values: Vec<f64> = (0..10_000).map(|_| rng.gen::<f64>() * 100.0).collect();
+        let exact: Quantiles = values.iter().collect();
+
+        let mut a: TDigest = values[..3000].iter().collect();
+        let b: TDigest = values[3000..].iter().collect();
+        a.merge(&b);
+
+        assert_eq!(a.len(), 10_000);
+        for p in [0.01, 0.25, 0.5, 0.75, 0.99] {
+            let q = a.quantile(p);
+            let rank = exact.values().partition_point(|&x| x < q) as f64 / exact.len() as f64;
+            assert_relative_eq!(rank, p, epsilon = 5e-3);
+        }
+        assert_relative_eq!(a.summary().mean, exact.summary().mean, epsilon = 1e-9);
+        assert_relative_eq!(a.summary().variance, exact.summary().variance, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn t_digest_small() {
+        let d: TDigest = [4.0, 1.0, 3.0, 2.0].iter().collect();
+
+        assert_relative_eq!(d.quantile(0.0), 1.0);
+        assert_relative_eq!(d.median(), 2.5);
+        assert_relative_eq!(d.quantile(1.0), 4.0);
+    }
+}
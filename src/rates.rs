@@ -0,0 +1,214 @@
+//! Comparisons of event rates, such as page faults, retries, or garbage collections per run, using
+//! the Poisson distribution.
+
+use statrs::distribution::{Beta, Binomial, ChiSquared, ContinuousCDF, DiscreteCDF};
+
+use crate::{unwrap, Confidence, Error};
+
+/// A number of events observed over an amount of exposure, e.g. the number of retries over a
+/// number of runs or seconds.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rate {
+    /// The number of events.
+    pub events: u64,
+
+    /// The amount of exposure over which the events were observed.
+    pub exposure: f64,
+}
+
+impl Rate {
+    /// Create a new rate from the given count of events and exposure, which must be positive and
+    /// finite.
+    pub fn new(events: u64, exposure: f64) -> Result<Rate, Error> {
+        if !(exposure.is_finite() && exposure > 0.0) {
+            return Err(Error::InvalidExposure(exposure));
+        }
+        Ok(Rate { events, exposure })
+    }
+
+    /// The number of events per unit of exposure.
+    #[must_use]
+    pub fn rate(&self) -> f64 {
+        self.events as f64 / self.exposure
+    }
+
+    /// The exact (Garwood) confidence interval for the rate of the Poisson process from which the
+    /// events were drawn. The confidence level must be in the range `(0, 100)`.
+    ///
+    /// ```
+    /// use nanostat::Confidence;
+    /// use nanostat::rates::Rate;
+    ///
+    /// let (low, high) = Rate::new(10, 100.0)?.confidence_interval(Confidence::P95);
+    /// assert!(low < 0.1 && 0.1 < high);
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the confidence level is out of range.
+    #[must_use]
+    pub fn confidence_interval(&self, confidence: Confidence) -> (f64, f64) {
+        let alpha = unwrap(confidence.checked_alpha());
+        let k = self.events as f64;
+        let low = if self.events == 0 {
+            0.0
+        } else {
+            quantile(&ChiSquared::new(2.0 * k).unwrap(), alpha / 2.0) / 2.0
+        };
+        let high = quantile(&ChiSquared::new(2.0 * k + 2.0).unwrap(), 1.0 - alpha / 2.0) / 2.0;
+        (low / self.exposure, high / self.exposure)
+    }
+
+    /// Calculate the statistical difference between the two rates using an exact two-tailed test,
+    /// conditioning on the total number of events. The confidence level must be in the range `(0,
+    /// 100)`.
+    ///
+    /// Given the total, the number of events in the second sample is binomially distributed, so
+    /// the p-value and the confidence interval for the ratio of the rates are exact (if
+    /// conservative) even for small counts. Returns [Error::CountOverflow] if the total number of
+    /// events does not fit in a `u64`.
+    ///
+    /// ```
+    /// use nanostat::Confidence;
+    /// use nanostat::rates::Rate;
+    ///
+    /// let control = Rate::new(10, 100.0)?;
+    /// let experiment = Rate::new(25, 100.0)?;
+    /// assert!(control.compare(&experiment, Confidence::P95)?.is_significant());
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    pub fn compare(&self, other: &Rate, confidence: Confidence) -> Result<RateRatio, Error> {
+        let alpha = confidence.checked_alpha()?;
+        let (a, b) = (self, other);
+        let total = a.events.checked_add(b.events).ok_or(Error::CountOverflow)?;
+        if total == 0 {
+            return Err(Error::ZeroVariance);
+        }
+
+        // Calculate the p-value by doubling the smaller tail of the binomial distribution of the
+        // second sample's events under the null hypothesis of equal rates.
+        let p0 = b.exposure / (a.exposure + b.exposure);
+        let dist_b = Binomial::new(p0, total).unwrap();
+        let lower = dist_b.cdf(b.events);
+        let upper = if b.events == 0 { 1.0 } else { 1.0 - dist_b.cdf(b.events - 1) };
+        let p_value = (lower.min(upper) * 2.0).min(1.0);
+
+        // Calculate the Clopper-Pearson interval for the fraction of the events in the second
+        // sample, and convert it to an interval for the ratio of the rates.
+        let (k, x) = (total as f64, b.events as f64);
+        let p_low = if b.events == 0 {
+            0.0
+        } else {
+            quantile(&Beta::new(x, k - x + 1.0).unwrap(), alpha / 2.0)
+        };
+        let p_high = if b.events == total {
+            1.0
+        } else {
+            quantile(&Beta::new(x + 1.0, k - x).unwrap(), 1.0 - alpha / 2.0)
+        };
+        let scale = a.exposure / b.exposure;
+        let ci_low = p_low / (1.0 - p_low) * scale;
+        let ci_high = p_high / (1.0 - p_high) * scale;
+
+        Ok(RateRatio { ratio: b.rate() / a.rate(), ci_low, ci_high, p_value, alpha })
+    }
+}
+
+/// The statistical difference between two [Rate] instances.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RateRatio {
+    /// The ratio of the rates: the second rate divided by the first. It is infinite if the first
+    /// sample has no events.
+    pub ratio: f64,
+
+    /// The lower bound of the exact confidence interval for the ratio of the rates.
+    pub ci_low: f64,
+
+    /// The upper bound of the exact confidence interval for the ratio of the rates. It is infinite
+    /// if the first sample has no events.
+    pub ci_high: f64,
+
+    /// The two-tailed p-value for the test.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl RateRatio {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Find the given quantile of a non-negative distribution by bisection, which is more accurate than
+/// the generic inverse CDF of the chi-squared and beta distributions.
+pub(crate) fn quantile(dist: &impl ContinuousCDF<f64, f64>, p: f64) -> f64 {
+    let mut high = 1.0;
+    while dist.cdf(high) < p {
+        high *= 2.0;
+    }
+    let mut low = 0.0;
+    for _ in 0..128 {
+        let mid = (low + high) / 2.0;
+        if dist.cdf(mid) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn rate_interval() {
+        let (low, high) = Rate::new(10, 100.0).unwrap().confidence_interval(Confidence::P95);
+        assert_relative_eq!(low, 0.04795388696132434, epsilon = 1e-9);
+        assert_relative_eq!(high, 0.18390356042017777, epsilon = 1e-9);
+
+        let (low, _) = Rate::new(0, 100.0).unwrap().confidence_interval(Confidence::P95);
+        assert_relative_eq!(low, 0.0);
+    }
+
+    #[test]
+    fn compare_rates() {
+        let a = Rate::new(10, 100.0).unwrap();
+        let b = Rate::new(25, 100.0).unwrap();
+        let ratio = a.compare(&b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(ratio.ratio, 2.5);
+        assert_relative_eq!(ratio.p_value, 0.01667384780012071, epsilon = 1e-9);
+        assert_relative_eq!(ratio.ci_low, 1.1596189735922394, epsilon = 1e-7);
+        assert_relative_eq!(ratio.ci_high, 5.832713200335717, epsilon = 1e-7);
+        assert!(ratio.is_significant());
+
+        // Exposure is accounted for.
+        let b = Rate::new(25, 250.0).unwrap();
+        let ratio = a.compare(&b, Confidence::P95).unwrap();
+        assert_relative_eq!(ratio.ratio, 1.0);
+        assert_relative_eq!(ratio.p_value, 1.0);
+    }
+
+    #[test]
+    fn rate_errors() {
+        assert_eq!(Rate::new(1, 0.0).unwrap_err(), Error::InvalidExposure(0.0));
+        assert_eq!(Rate::new(1, f64::INFINITY).unwrap_err(), Error::InvalidExposure(f64::INFINITY));
+
+        let none = Rate::new(0, 10.0).unwrap();
+        assert_eq!(none.compare(&none, Confidence::P95).unwrap_err(), Error::ZeroVariance);
+
+        let many = Rate::new(u64::MAX, 10.0).unwrap();
+        assert_eq!(many.compare(&many, Confidence::P95).unwrap_err(), Error::CountOverflow);
+    }
+}
@@ -0,0 +1,382 @@
+//! Linear regression over paired measurements, for testing how performance scales with a variable
+//! like input size.
+
+use statrs::distribution::{ContinuousCDF, StudentsT};
+
+use crate::{Confidence, Error};
+
+/// An ordinary least-squares fit of a line to paired measurements.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Regression {
+    /// The slope of the fitted line: the change in `y` for each unit of `x`.
+    pub slope: f64,
+
+    /// The intercept of the fitted line: the value of `y` when `x` is zero.
+    pub intercept: f64,
+
+    /// The coefficient of determination: the fraction of the variance of `y` explained by the fit.
+    pub r_squared: f64,
+
+    /// The standard error of the slope.
+    pub std_err: f64,
+
+    /// The degrees of freedom of the residuals.
+    pub df: f64,
+
+    /// The lower bound of the confidence interval for the slope.
+    pub ci_low: f64,
+
+    /// The upper bound of the confidence interval for the slope.
+    pub ci_high: f64,
+
+    /// The two-tailed p-value for the null hypothesis that the slope is zero.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl Regression {
+    /// Whether or not the slope is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+
+    /// The value of `y` predicted by the fitted line for the given `x`.
+    #[must_use]
+    pub fn predict(&self, x: f64) -> f64 {
+        self.intercept + self.slope * x
+    }
+
+    /// Calculate the statistical difference between the slopes of the two fits using a two-tailed
+    /// t-test, e.g. to test whether the scaling of a benchmark changed between versions. Both fits
+    /// must use the same confidence level.
+    ///
+    /// ```
+    /// use nanostat::Confidence;
+    /// use nanostat::regression::linear;
+    ///
+    /// let size = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+    /// let before = linear(&size, &[2.1, 3.9, 6.2, 7.8, 10.1, 12.2, 13.8, 16.5], Confidence::P95)?;
+    /// let after = linear(&size, &[2.0, 4.3, 6.8, 8.9, 11.5, 13.6, 16.4, 18.7], Confidence::P95)?;
+    /// assert!(before.compare_slopes(&after).is_significant());
+    /// # Ok::<(), nanostat::Error>(())
+    /// ```
+    #[must_use]
+    pub fn compare_slopes(&self, other: &Regression) -> SlopeDifference {
+        let (a, b) = (self, other);
+        let delta = b.slope - a.slope;
+        let std_err = (a.std_err.powf(2.0) + b.std_err.powf(2.0)).sqrt();
+        let df = a.df + b.df;
+
+        let dist_st = StudentsT::new(0.0, 1.0, df).unwrap();
+        let p_value = dist_st.cdf(-(delta / std_err).abs()) * 2.0;
+        let h = dist_st.inverse_cdf(1.0 - a.alpha / 2.0) * std_err;
+
+        SlopeDifference { delta, ci_low: delta - h, ci_high: delta + h, p_value, alpha: a.alpha }
+    }
+}
+
+/// The statistical difference between the slopes of two [Regression] instances.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlopeDifference {
+    /// The second slope minus the first.
+    pub delta: f64,
+
+    /// The lower bound of the confidence interval for the difference in slopes.
+    pub ci_low: f64,
+
+    /// The upper bound of the confidence interval for the difference in slopes.
+    pub ci_high: f64,
+
+    /// The two-tailed p-value for the test.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl SlopeDifference {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Fit a line to the paired measurements `(x[i], y[i])` using ordinary least squares, with a
+/// confidence interval and a t-test for the slope. Both data sets must have the same length, of at
+/// least three measurements. The confidence level must be in the range `(0, 100)`.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::regression::linear;
+///
+/// let size = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+/// let duration = [2.1, 3.9, 6.2, 7.8, 10.1, 12.2, 13.8, 16.5];
+/// let fit = linear(&size, &duration, Confidence::P95)?;
+/// assert!(fit.is_significant());
+/// assert!(fit.r_squared > 0.99);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn linear(x: &[f64], y: &[f64], confidence: Confidence) -> Result<Regression, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if x.len() != y.len() {
+        return Err(Error::MismatchedLengths);
+    }
+    if x.len() < 3 {
+        return Err(Error::InsufficientSamples);
+    }
+    if x.iter().chain(y.iter()).any(|v| !v.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    // Calculate the sums of squares and products about the means.
+    let n = x.len() as f64;
+    let (mean_x, mean_y) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+    let (mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(y.iter()) {
+        let (dx, dy) = (a - mean_x, b - mean_y);
+        sxx += dx * dx;
+        syy += dy * dy;
+        sxy += dx * dy;
+    }
+    if sxx == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Calculate the coefficients of the line.
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+
+    // Calculate the residual sum of squares and the standard error of the slope.
+    let sse = (syy - slope * sxy).max(0.0);
+    let r_squared = if syy == 0.0 { 1.0 } else { 1.0 - sse / syy };
+    let df = n - 2.0;
+    let std_err = (sse / df / sxx).sqrt();
+
+    // Calculate the p-value and the confidence interval using a t-distribution.
+    let dist_st = StudentsT::new(0.0, 1.0, df).map_err(|_| Error::NonFinite)?;
+    let p_value = dist_st.cdf(-(slope / std_err).abs()) * 2.0;
+    let h = dist_st.inverse_cdf(1.0 - alpha / 2.0) * std_err;
+
+    Ok(Regression {
+        slope,
+        intercept,
+        r_squared,
+        std_err,
+        df,
+        ci_low: slope - h,
+        ci_high: slope + h,
+        p_value,
+        alpha,
+    })
+}
+
+/// The difference between the means of two data sets after adjusting for a covariate.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdjustedDifference {
+    /// The difference between the adjusted means: the second data set's minus the first's, at the
+    /// same value of the covariate.
+    pub delta: f64,
+
+    /// The common slope of the measurements on the covariate within each data set.
+    pub slope: f64,
+
+    /// The standard error of the difference in adjusted means.
+    pub std_err: f64,
+
+    /// The degrees of freedom of the t-distribution used by the test.
+    pub df: f64,
+
+    /// The lower bound of the confidence interval for the difference in adjusted means.
+    pub ci_low: f64,
+
+    /// The upper bound of the confidence interval for the difference in adjusted means.
+    pub ci_high: f64,
+
+    /// The two-tailed p-value for the test.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl AdjustedDifference {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Calculate the statistical difference between the means of two data sets of measurements `y`
+/// after adjusting each for a numeric covariate `x` (e.g. machine load or input size), using an
+/// analysis of covariance with a common slope. Each data set's `x` and `y` must have the same
+/// length, and there must be at least four measurements in total. The confidence level must be in
+/// the range `(0, 100)`.
+///
+/// When the covariate explains much of the variance of the measurements, this is far more powerful
+/// than comparing the raw means, and it corrects for differences in the covariate between the two
+/// data sets.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::regression::compare_adjusted;
+///
+/// let (a_load, a) = ([1.0, 3.0, 2.0, 5.0, 4.0, 6.0], [10.2, 14.1, 12.0, 18.3, 15.9, 20.1]);
+/// let (b_load, b) = ([3.0, 7.0, 2.0, 5.0, 6.0, 4.0], [12.8, 20.9, 10.7, 16.6, 19.1, 14.6]);
+/// let diff = compare_adjusted(&a_load, &a, &b_load, &b, Confidence::P95)?;
+/// assert!(diff.is_significant());
+/// assert!(diff.delta < 0.0);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn compare_adjusted(
+    a_x: &[f64],
+    a_y: &[f64],
+    b_x: &[f64],
+    b_y: &[f64],
+    confidence: Confidence,
+) -> Result<AdjustedDifference, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if a_x.len() != a_y.len() || b_x.len() != b_y.len() {
+        return Err(Error::MismatchedLengths);
+    }
+    if a_x.is_empty() || b_x.is_empty() || a_x.len() + b_x.len() < 4 {
+        return Err(Error::InsufficientSamples);
+    }
+    if a_x.iter().chain(a_y).chain(b_x).chain(b_y).any(|v| !v.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    // Calculate the means and the sums of squares and products about them.
+    let sums = |x: &[f64], y: &[f64]| {
+        let n = x.len() as f64;
+        let (mean_x, mean_y) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+        let (mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0);
+        for (a, b) in x.iter().zip(y.iter()) {
+            let (dx, dy) = (a - mean_x, b - mean_y);
+            sxx += dx * dx;
+            syy += dy * dy;
+            sxy += dx * dy;
+        }
+        (n, mean_x, mean_y, sxx, syy, sxy)
+    };
+    let (n_a, mean_ax, mean_ay, sxx_a, syy_a, sxy_a) = sums(a_x, a_y);
+    let (n_b, mean_bx, mean_by, sxx_b, syy_b, sxy_b) = sums(b_x, b_y);
+    let (sxx, syy, sxy) = (sxx_a + sxx_b, syy_a + syy_b, sxy_a + sxy_b);
+    if sxx == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Calculate the common within-group slope and the difference in adjusted means.
+    let slope = sxy / sxx;
+    let dx = mean_bx - mean_ax;
+    let delta = (mean_by - mean_ay) - slope * dx;
+
+    // Calculate the standard error of the difference from the residual mean square.
+    let df = n_a + n_b - 3.0;
+    let mse = (syy - slope * sxy).max(0.0) / df;
+    let std_err = (mse * (1.0 / n_a + 1.0 / n_b + dx * dx / sxx)).sqrt();
+
+    // Calculate the p-value and the confidence interval using a t-distribution.
+    let dist_st = StudentsT::new(0.0, 1.0, df).map_err(|_| Error::NonFinite)?;
+    let p_value = dist_st.cdf(-(delta / std_err).abs()) * 2.0;
+    let h = dist_st.inverse_cdf(1.0 - alpha / 2.0) * std_err;
+
+    Ok(AdjustedDifference {
+        delta,
+        slope,
+        std_err,
+        df,
+        ci_low: delta - h,
+        ci_high: delta + h,
+        p_value,
+        alpha,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::Summary;
+
+    use super::*;
+
+    const X: [f64; 8] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+
+    #[test]
+    fn linear_fit() {
+        let y = [2.1, 3.9, 6.2, 7.8, 10.1, 12.2, 13.8, 16.5];
+        let fit = linear(&X, &y, Confidence::P95).unwrap();
+
+        assert_relative_eq!(fit.slope, 2.030952380952381, epsilon = 1e-12);
+        assert_relative_eq!(fit.intercept, -0.06428571428571436, epsilon = 1e-12);
+        assert_relative_eq!(fit.r_squared, 0.9979563817808007, epsilon = 1e-12);
+        assert_relative_eq!(fit.std_err, 0.037520465566897795, epsilon = 1e-12);
+        assert_relative_eq!(fit.df, 6.0);
+        assert_relative_eq!(fit.ci_low, 1.939143109096262, epsilon = 1e-9);
+        assert_relative_eq!(fit.ci_high, 2.1227616528085, epsilon = 1e-9);
+        assert_relative_eq!(fit.p_value, 2.669208041838976e-9, epsilon = 1e-12);
+        assert_relative_eq!(fit.predict(10.0), 20.245238095238093, epsilon = 1e-12);
+        assert!(fit.is_significant());
+    }
+
+    #[test]
+    fn compare_slopes() {
+        let a = linear(&X, &[2.1, 3.9, 6.2, 7.8, 10.1, 12.2, 13.8, 16.5], Confidence::P95).unwrap();
+        let b = linear(&X, &[2.0, 4.3, 6.8, 8.9, 11.5, 13.6, 16.4, 18.7], Confidence::P95).unwrap();
+        let diff = a.compare_slopes(&b);
+
+        assert_relative_eq!(diff.delta, 0.3547619047619046, epsilon = 1e-12);
+        assert_relative_eq!(diff.ci_low, 0.2575943981881568, epsilon = 1e-9);
+        assert_relative_eq!(diff.ci_high, 0.45192941133565244, epsilon = 1e-9);
+        assert_relative_eq!(diff.p_value, 3.983643159168139e-6, epsilon = 1e-12);
+        assert!(diff.is_significant());
+    }
+
+    #[test]
+    fn covariate_adjustment() {
+        let (a_x, a_y) = ([1.0, 3.0, 2.0, 5.0, 4.0, 6.0], [10.2, 14.1, 12.0, 18.3, 15.9, 20.1]);
+        let (b_x, b_y) = ([3.0, 7.0, 2.0, 5.0, 6.0, 4.0], [12.8, 20.9, 10.7, 16.6, 19.1, 14.6]);
+        let diff = compare_adjusted(&a_x, &a_y, &b_x, &b_y, Confidence::P95).unwrap();
+
+        assert_relative_eq!(diff.slope, 2.03, epsilon = 1e-12);
+        assert_relative_eq!(diff.delta, -1.346666666666667, epsilon = 1e-12);
+        assert_relative_eq!(diff.std_err, 0.1016252409313565, epsilon = 1e-12);
+        assert_relative_eq!(diff.df, 9.0);
+        assert_relative_eq!(diff.ci_low, -1.5765589333606284, epsilon = 1e-9);
+        assert_relative_eq!(diff.ci_high, -1.1167743999727055, epsilon = 1e-9);
+        assert_relative_eq!(diff.p_value, 3.2938914633022975e-7, epsilon = 1e-12);
+        assert!(diff.is_significant());
+
+        // Without the adjustment, the experiment appears slower, but not significantly so.
+        let (a, b): (Summary, Summary) = (a_y.iter().collect(), b_y.iter().collect());
+        let raw = a.compare(&b, Confidence::P95);
+        assert!(raw.delta > 0.0);
+        assert!(!raw.is_significant());
+
+        assert_eq!(
+            compare_adjusted(&a_x, &a_y[..2], &b_x, &b_y, Confidence::P95).unwrap_err(),
+            Error::MismatchedLengths
+        );
+    }
+
+    #[test]
+    fn linear_errors() {
+        assert_eq!(linear(&X, &[1.0], Confidence::P95).unwrap_err(), Error::MismatchedLengths);
+        assert_eq!(
+            linear(&[1.0, 2.0], &[1.0, 2.0], Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+        assert_eq!(
+            linear(&[1.0; 3], &[1.0, 2.0, 3.0], Confidence::P95).unwrap_err(),
+            Error::ZeroVariance
+        );
+    }
+}
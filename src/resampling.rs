@@ -0,0 +1,690 @@
+//! Resampling-based tests which operate on raw measurements and make no assumptions about their
+//! distribution.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::quantiles::quantile;
+use crate::{Confidence, Error, Summary};
+
+/// The configuration of a resampling-based test. The random number generator is seeded from the
+/// given seed, so the results of a test are reproducible, e.g. across CI runs. With the `rayon`
+/// feature, the iterations are run in parallel without changing the results.
+///
+/// ```
+/// use nanostat::resampling::Resampling;
+///
+/// let resampling = Resampling { seed: 0xDEADBEEF, ..Resampling::default() };
+/// assert_eq!(resampling.iterations, 10_000);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Resampling {
+    /// The number of resamples or random permutations.
+    pub iterations: usize,
+
+    /// The seed for the random number generator.
+    pub seed: u64,
+}
+
+impl Default for Resampling {
+    fn default() -> Self {
+        Resampling { iterations: 10_000, seed: 0 }
+    }
+}
+
+impl Resampling {
+    /// Create a configuration with the given number of iterations and seed.
+    #[must_use]
+    pub const fn new(iterations: usize, seed: u64) -> Resampling {
+        Resampling { iterations, seed }
+    }
+
+    /// Run the configured number of iterations in chunks, passing each chunk's size and an
+    /// independent random number generator to the given function and returning its results in
+    /// order. The chunks' generators are seeded from the configuration's seed, so the results are
+    /// the same whether or not the chunks are run in parallel on Rayon's thread pool.
+    fn chunks<T: Send>(&self, f: impl Fn(usize, &mut StdRng) -> T + Sync) -> Vec<T> {
+        const CHUNK: usize = 1_000;
+
+        let mut master = StdRng::seed_from_u64(self.seed);
+        let chunks: Vec<(usize, u64)> = (0..self.iterations)
+            .step_by(CHUNK)
+            .map(|i| (CHUNK.min(self.iterations - i), master.gen()))
+            .collect();
+        let run = |&(n, seed): &(usize, u64)| f(n, &mut StdRng::seed_from_u64(seed));
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            chunks.par_iter().map(run).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            chunks.iter().map(run).collect()
+        }
+    }
+}
+
+/// A bootstrap confidence interval for the difference between two data sets, e.g. in their means.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bootstrap {
+    /// The observed difference between the two samples, e.g. `mean(b) - mean(a)`.
+    pub effect: f64,
+
+    /// The lower bound of the percentile confidence interval.
+    pub percentile_low: f64,
+
+    /// The upper bound of the percentile confidence interval.
+    pub percentile_high: f64,
+
+    /// The lower bound of the bias-corrected and accelerated (BCa) confidence interval.
+    pub bca_low: f64,
+
+    /// The upper bound of the bias-corrected and accelerated (BCa) confidence interval.
+    pub bca_high: f64,
+
+    /// The significance level of the test. It is the probability that the true difference lies
+    /// outside the confidence interval.
+    pub alpha: f64,
+}
+
+impl Bootstrap {
+    /// Whether or not the difference is statistically significant, i.e. whether the BCa interval
+    /// excludes zero.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.bca_low > 0.0 || self.bca_high < 0.0
+    }
+}
+
+/// Estimate confidence intervals for the difference between the means of two data sets,
+/// `mean(b) - mean(a)`, by resampling each data set with replacement the configured number of
+/// times. The confidence level must be in the range `(0, 100)`.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::resampling::{compare_bootstrap, Resampling};
+///
+/// let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+/// let b = [11.0, 12.0, 13.0, 14.0, 15.0, 16.0, 17.0, 18.0];
+/// let boot = compare_bootstrap(&a, &b, Confidence::P95, Resampling::default())?;
+/// assert!(boot.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn compare_bootstrap(
+    a: &[f64],
+    b: &[f64],
+    confidence: Confidence,
+    resampling: Resampling,
+) -> Result<Bootstrap, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if a.len() < 2 || b.len() < 2 || resampling.iterations == 0 {
+        return Err(Error::InsufficientSamples);
+    }
+    if a.iter().chain(b.iter()).any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    let mean = |x: &[f64]| x.iter().sum::<f64>() / x.len() as f64;
+    bootstrap(a, b, alpha, resampling, |a, b| mean(b) - mean(a))
+}
+
+/// A statistic of two data sets whose confidence interval can be estimated by
+/// [bootstrap_effect_size].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Effect {
+    /// Cohen's d, the difference in means, `mean(b) - mean(a)`, divided by the square root of the
+    /// unweighted mean of the two variances.
+    #[default]
+    CohensD,
+    /// Cliff's delta, the probability that a measurement from `b` is larger than one from `a`,
+    /// minus the probability that it is smaller; see [crate::nonparametric::cliffs_delta].
+    CliffsDelta,
+}
+
+/// Estimate confidence intervals for the given effect size of the difference between two data
+/// sets by resampling each data set with replacement the configured number of times. The
+/// confidence level must be in the range `(0, 100)`, and Cohen's d requires at least three
+/// measurements in each data set.
+///
+/// Resamples for which the effect size is undefined, e.g. because neither resampled data set has
+/// any variance, are discarded.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::resampling::{bootstrap_effect_size, Effect, Resampling};
+///
+/// let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+/// let b = [5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0];
+/// let resampling = Resampling::new(1_000, 0xDEADBEEF);
+/// let d = bootstrap_effect_size(&a, &b, Effect::CohensD, Confidence::P95, resampling)?;
+/// assert!(d.bca_low > 0.0 && d.effect > 1.5);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn bootstrap_effect_size(
+    a: &[f64],
+    b: &[f64],
+    effect: Effect,
+    confidence: Confidence,
+    resampling: Resampling,
+) -> Result<Bootstrap, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if a.len() < 2 || b.len() < 2 || resampling.iterations == 0 {
+        return Err(Error::InsufficientSamples);
+    }
+    if a.iter().chain(b.iter()).any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    match effect {
+        Effect::CohensD => {
+            if a.len() < 3 || b.len() < 3 {
+                return Err(Error::InsufficientSamples);
+            }
+            let (sa, sb): (Summary, Summary) = (a.iter().collect(), b.iter().collect());
+            if sa.variance == 0.0 && sb.variance == 0.0 {
+                return Err(Error::ZeroVariance);
+            }
+            bootstrap(a, b, alpha, resampling, |a, b| {
+                let (sa, sb): (Summary, Summary) = (a.iter().collect(), b.iter().collect());
+                (sb.mean - sa.mean) / ((sa.variance + sb.variance) / 2.0).sqrt()
+            })
+        }
+        Effect::CliffsDelta => bootstrap(a, b, alpha, resampling, |a, b| {
+            crate::nonparametric::cliffs_delta(b, a).map_or(f64::NAN, |cd| cd.delta)
+        }),
+    }
+}
+
+/// Estimate the percentile and BCa confidence intervals for the given statistic of two data sets
+/// by resampling each with replacement, discarding any resampled or jackknife statistics which
+/// aren't finite. Returns [Error::ZeroVariance] if none of the resampled statistics are finite.
+fn bootstrap(
+    a: &[f64],
+    b: &[f64],
+    alpha: f64,
+    resampling: Resampling,
+    statistic: impl Fn(&[f64], &[f64]) -> f64 + Sync,
+) -> Result<Bootstrap, Error> {
+    let effect = statistic(a, b);
+
+    // Calculate the statistic of many resampled pairs.
+    let mut boot: Vec<f64> = resampling
+        .chunks(|n, rng| {
+            let (mut ra, mut rb) = (a.to_vec(), b.to_vec());
+            (0..n)
+                .map(|_| {
+                    resample(b, &mut rb, rng);
+                    resample(a, &mut ra, rng);
+                    statistic(&ra, &rb)
+                })
+                .collect::<Vec<f64>>()
+        })
+        .concat();
+    boot.retain(|x| x.is_finite());
+    if boot.is_empty() {
+        return Err(Error::ZeroVariance);
+    }
+    boot.sort_by(f64::total_cmp);
+    let iterations = boot.len();
+
+    // Calculate the percentile interval.
+    let percentile_low = quantile(&boot, alpha / 2.0);
+    let percentile_high = quantile(&boot, 1.0 - alpha / 2.0);
+
+    // Calculate the bias correction from the fraction of resampled statistics below the observed
+    // statistic, clamped to avoid infinite corrections.
+    let n = iterations as f64;
+    let below = boot.iter().filter(|&&x| x < effect).count() as f64;
+    let dist_norm = Normal::new(0.0, 1.0).unwrap();
+    let z0 = dist_norm.inverse_cdf((below / n).clamp(0.5 / n, 1.0 - 0.5 / n));
+
+    // Calculate the acceleration from the jackknife estimates of the statistic.
+    let without = |x: &[f64], i: usize| [&x[..i], &x[i + 1..]].concat();
+    let jack: Vec<f64> = (0..a.len())
+        .map(|i| statistic(&without(a, i), b))
+        .chain((0..b.len()).map(|i| statistic(a, &without(b, i))))
+        .filter(|x| x.is_finite())
+        .collect();
+    let jack_mean = jack.iter().sum::<f64>() / jack.len() as f64;
+    let num = jack.iter().map(|x| (jack_mean - x).powf(3.0)).sum::<f64>();
+    let den = 6.0 * jack.iter().map(|x| (jack_mean - x).powf(2.0)).sum::<f64>().powf(1.5);
+    let accel = if den == 0.0 { 0.0 } else { num / den };
+
+    // Calculate the BCa interval by adjusting the percentiles.
+    let adjust = |z: f64| dist_norm.cdf(z0 + (z0 + z) / (1.0 - accel * (z0 + z)));
+    let z = dist_norm.inverse_cdf(alpha / 2.0);
+    let bca_low = quantile(&boot, adjust(z));
+    let bca_high = quantile(&boot, adjust(-z));
+
+    Ok(Bootstrap { effect, percentile_low, percentile_high, bca_low, bca_high, alpha })
+}
+
+/// The result of a permutation test for a difference between the means of two data sets.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Permutation {
+    /// The observed difference between the means of the two samples, `mean(b) - mean(a)`.
+    pub effect: f64,
+
+    /// The two-tailed p-value for the test: the fraction of permutations with a difference at
+    /// least as extreme as the observed difference.
+    pub p_value: f64,
+
+    /// Whether the p-value was calculated by enumerating every permutation, rather than by Monte
+    /// Carlo sampling.
+    pub exact: bool,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl Permutation {
+    /// Whether or not the difference is statistically significant.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Test for a difference between the means of two data sets by comparing the observed difference
+/// to the differences obtained by randomly reassigning measurements between the two sets. The
+/// confidence level must be in the range `(0, 100)`.
+///
+/// If the number of distinct reassignments is no more than the configured number of iterations,
+/// every one is enumerated and the p-value is exact; otherwise, that many random permutations are
+/// sampled.
+pub fn permutation(
+    a: &[f64],
+    b: &[f64],
+    confidence: Confidence,
+    resampling: Resampling,
+) -> Result<Permutation, Error> {
+    let alpha = confidence.checked_alpha()?;
+    let iterations = resampling.iterations;
+    if a.is_empty() || b.is_empty() || iterations == 0 {
+        return Err(Error::InsufficientSamples);
+    }
+    if a.iter().chain(b.iter()).any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    let pooled: Vec<f64> = a.iter().chain(b.iter()).copied().collect();
+    let total = pooled.iter().sum::<f64>();
+    let (n_a, n_b) = (a.len() as f64, b.len() as f64);
+
+    // The difference in means when the given sum is assigned to the first sample.
+    let diff = |sum_a: f64| (total - sum_a) / n_b - sum_a / n_a;
+
+    // Calculate the observed difference, allowing for rounding error when comparing it to the
+    // differences of permuted samples.
+    let effect = diff(a.iter().sum());
+    let threshold = effect.abs() * (1.0 - 1e-12);
+
+    let exact = binomial(pooled.len(), a.len()) <= iterations as f64;
+    let p_value = if exact {
+        // Enumerate every combination of indexes assigned to the first sample.
+        let (mut hits, mut count) = (0usize, 0usize);
+        combinations(pooled.len(), a.len(), |idx| {
+            count += 1;
+            if diff(idx.iter().map(|&i| pooled[i]).sum()).abs() >= threshold {
+                hits += 1;
+            }
+        });
+        hits as f64 / count as f64
+    } else {
+        // Sample random permutations, counting the observed assignment as one of them.
+        let hits: usize = resampling
+            .chunks(|n, rng| {
+                let mut pooled = pooled.clone();
+                (0..n)
+                    .filter(|_| {
+                        pooled.shuffle(rng);
+                        diff(pooled[..a.len()].iter().sum()).abs() >= threshold
+                    })
+                    .count()
+            })
+            .iter()
+            .sum();
+        (hits + 1) as f64 / (iterations + 1) as f64
+    };
+
+    Ok(Permutation { effect, p_value, exact, alpha })
+}
+
+/// A uniform random sample of bounded size from a stream of measurements of unknown length, kept
+/// using Vitter's Algorithm R. Every measurement in the stream is equally likely to be retained, so
+/// exact tests and plots can be run on the sample of an arbitrarily large input.
+///
+/// ```
+/// use nanostat::resampling::Reservoir;
+///
+/// let mut reservoir = Reservoir::seeded(100, 0xDEADBEEF);
+/// reservoir.extend((0..1_000_000).map(f64::from));
+/// assert_eq!(reservoir.values().len(), 100);
+/// assert_eq!(reservoir.seen(), 1_000_000);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Reservoir {
+    capacity: usize,
+    seen: u64,
+    values: Vec<f64>,
+    rng: StdRng,
+}
+
+impl Extend<f64> for Reservoir {
+    fn extend<T: IntoIterator<Item = f64>>(&mut self, iter: T) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<'a> Extend<&'a f64> for Reservoir {
+    fn extend<T: IntoIterator<Item = &'a f64>>(&mut self, iter: T) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl Reservoir {
+    /// Create an empty reservoir which retains at most `capacity` measurements, seeded from the
+    /// operating system's source of randomness.
+    #[must_use]
+    pub fn new(capacity: usize) -> Reservoir {
+        Reservoir::with_rng(capacity, StdRng::from_entropy())
+    }
+
+    /// Create an empty reservoir which retains at most `capacity` measurements, seeded
+    /// deterministically so that the same stream always produces the same sample.
+    #[must_use]
+    pub fn seeded(capacity: usize, seed: u64) -> Reservoir {
+        Reservoir::with_rng(capacity, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(capacity: usize, rng: StdRng) -> Reservoir {
+        Reservoir { capacity, seen: 0, values: Vec::with_capacity(capacity), rng }
+    }
+
+    /// Offer a measurement to the reservoir, which retains it with probability `capacity / seen`.
+    pub fn push(&mut self, x: f64) {
+        self.seen += 1;
+        if self.values.len() < self.capacity {
+            self.values.push(x);
+        } else {
+            let i = self.rng.gen_range(0..self.seen);
+            if i < self.capacity as u64 {
+                self.values[i as usize] = x;
+            }
+        }
+    }
+
+    /// The number of measurements offered to the reservoir.
+    #[must_use]
+    pub const fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    /// The measurements retained by the reservoir, in no particular order.
+    #[must_use]
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Consume the reservoir, returning the measurements it retained.
+    #[must_use]
+    pub fn into_values(self) -> Vec<f64> {
+        self.values
+    }
+
+    /// A [Summary] of the measurements retained by the reservoir.
+    #[must_use]
+    pub fn summary(&self) -> Summary {
+        self.values.iter().collect()
+    }
+}
+
+/// Call the given function with every combination of `k` indexes in `0..n`, in lexicographic order.
+pub(crate) fn combinations(n: usize, k: usize, mut f: impl FnMut(&[usize])) {
+    let mut idx: Vec<usize> = (0..k).collect();
+    loop {
+        f(&idx);
+
+        // Advance to the next combination.
+        match (0..k).rev().find(|&i| idx[i] != i + n - k) {
+            Some(i) => {
+                idx[i] += 1;
+                for j in i + 1..k {
+                    idx[j] = idx[j - 1] + 1;
+                }
+            }
+            None => break,
+        }
+    }
+}
+
+/// Calculate the binomial coefficient `n choose k` as a float, to avoid overflow.
+pub(crate) fn binomial(n: usize, k: usize) -> f64 {
+    let k = k.min(n - k);
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// Fill the given buffer with a resample, with replacement, of the given values.
+fn resample<R: Rng + ?Sized>(values: &[f64], buf: &mut [f64], rng: &mut R) {
+    for x in buf.iter_mut() {
+        *x = values[rng.gen_range(0..values.len())];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn bootstrap_shifted() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        let b: Vec<f64> = a.iter().map(|x| x + 5.0).collect();
+        let boot =
+            compare_bootstrap(&a, &b, Confidence::P95, Resampling::new(2_000, 0xDEADBEEF)).unwrap();
+
+        assert_relative_eq!(boot.effect, 5.0);
+        assert!(boot.percentile_low < 5.0 && 5.0 < boot.percentile_high);
+        assert!(boot.bca_low < 5.0 && 5.0 < boot.bca_high);
+        assert!(boot.is_significant());
+    }
+
+    #[test]
+    fn bootstrap_similar() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        let b = [2.0, 1.0, 4.0, 3.0, 6.0, 5.0, 8.0, 7.0, 10.0, 9.5];
+        let boot =
+            compare_bootstrap(&a, &b, Confidence::P95, Resampling::new(2_000, 0xDEADBEEF)).unwrap();
+
+        assert!(boot.percentile_low < 0.0 && 0.0 < boot.percentile_high);
+        assert!(!boot.is_significant());
+    }
+
+    #[test]
+    fn bootstrap_errors() {
+        assert_eq!(
+            compare_bootstrap(
+                &[1.0],
+                &[1.0, 2.0],
+                Confidence::P95,
+                Resampling::new(10, 0xDEADBEEF)
+            )
+            .unwrap_err(),
+            Error::InsufficientSamples
+        );
+        assert_eq!(
+            compare_bootstrap(
+                &[1.0, f64::NAN],
+                &[1.0, 2.0],
+                Confidence::P95,
+                Resampling::new(10, 0xDEADBEEF)
+            )
+            .unwrap_err(),
+            Error::NonFinite
+        );
+    }
+
+    #[test]
+    fn bootstrap_effect_sizes() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        let b = [4.0, 3.0, 6.0, 5.0, 8.0, 7.0, 10.0, 9.0, 12.0, 14.0];
+        let d = bootstrap_effect_size(
+            &a,
+            &b,
+            Effect::CohensD,
+            Confidence::P95,
+            Resampling::new(2_000, 0xDEADBEEF),
+        )
+        .unwrap();
+        let (sa, sb): (Summary, Summary) = (a.iter().collect(), b.iter().collect());
+        assert_relative_eq!(d.effect, sa.compare(&sb, Confidence::P95).effect_size);
+        assert!(d.percentile_low < d.effect && d.effect < d.percentile_high);
+        assert!(d.bca_low < d.effect && d.effect < d.bca_high);
+
+        let cd = bootstrap_effect_size(
+            &a,
+            &b,
+            Effect::CliffsDelta,
+            Confidence::P95,
+            Resampling::new(2_000, 0xDEADBEEF),
+        )
+        .unwrap();
+        assert_relative_eq!(cd.effect, 0.36);
+        assert!(cd.bca_low < 0.36 && 0.36 < cd.bca_high);
+        assert!(-1.0 <= cd.percentile_low && cd.percentile_high <= 1.0);
+
+        assert_eq!(
+            bootstrap_effect_size(
+                &[1.0, 1.0, 1.0],
+                &[2.0, 2.0, 2.0],
+                Effect::CohensD,
+                Confidence::P95,
+                Resampling::new(10, 0xDEADBEEF)
+            )
+            .unwrap_err(),
+            Error::ZeroVariance
+        );
+        assert_eq!(
+            bootstrap_effect_size(
+                &[1.0, 2.0],
+                &[3.0, 4.0, 5.0],
+                Effect::CohensD,
+                Confidence::P95,
+                Resampling::new(10, 0xDEADBEEF)
+            )
+            .unwrap_err(),
+            Error::InsufficientSamples
+        );
+    }
+
+    #[test]
+    fn bootstrap_effect_size_ties() {
+        for (a, b) in [([1.0, 2.0, 2.0], [3.0, 3.0, 5.0]), ([1.0, 1.0, 1.0], [3.0, 3.0, 4.0])] {
+            let d = bootstrap_effect_size(
+                &a,
+                &b,
+                Effect::CohensD,
+                Confidence::P95,
+                Resampling::new(1_000, 1),
+            )
+            .unwrap();
+            for x in [d.effect, d.percentile_low, d.percentile_high, d.bca_low, d.bca_high] {
+                assert!(x.is_finite(), "{:?} vs {:?}: {:?}", a, b, d);
+            }
+            assert!(d.percentile_low <= d.percentile_high && d.bca_low <= d.bca_high);
+        }
+    }
+
+    #[test]
+    fn reproducible_resampling() {
+        let a: Vec<f64> = (0..20).map(f64::from).collect();
+        let b: Vec<f64> = a.iter().map(|x| x * 1.1).collect();
+        let config = Resampling::new(2_500, 0xDEADBEEF);
+
+        let x = compare_bootstrap(&a, &b, Confidence::P95, config).unwrap();
+        let y = compare_bootstrap(&a, &b, Confidence::P95, config).unwrap();
+        assert_eq!((x.bca_low, x.bca_high), (y.bca_low, y.bca_high));
+
+        let x = permutation(&a, &b, Confidence::P95, config).unwrap();
+        let y = permutation(&a, &b, Confidence::P95, config).unwrap();
+        assert_eq!(x.p_value, y.p_value);
+
+        let z = permutation(&a, &b, Confidence::P95, Resampling { seed: 1, ..config }).unwrap();
+        assert_ne!(x.p_value, z.p_value);
+    }
+
+    #[test]
+    fn permutation_exact() {
+        let perm = permutation(
+            &[1.0, 2.0, 3.0],
+            &[4.0, 5.0, 6.0],
+            Confidence::P95,
+            Resampling::new(1_000, 0xDEADBEEF),
+        )
+        .unwrap();
+
+        assert_relative_eq!(perm.effect, 3.0);
+        assert_relative_eq!(perm.p_value, 0.1);
+        assert!(perm.exact);
+        assert!(!perm.is_significant());
+    }
+
+    #[test]
+    fn permutation_monte_carlo() {
+        let a: Vec<f64> = (0..20).map(f64::from).collect();
+        let b: Vec<f64> = a.iter().map(|x| x + 15.0).collect();
+        let perm =
+            permutation(&a, &b, Confidence::P95, Resampling::new(1_000, 0xDEADBEEF)).unwrap();
+
+        assert_relative_eq!(perm.effect, 15.0);
+        assert!(!perm.exact);
+        assert!(perm.p_value < 0.01);
+        assert!(perm.is_significant());
+    }
+
+    #[test]
+    fn reservoir_sampling() {
+        let mut small = Reservoir::seeded(10, 0xDEADBEEF);
+        small.extend(&[1.0, 2.0, 3.0]);
+        assert_eq!(small.values(), &[1.0, 2.0, 3.0]);
+
+        let mut a = Reservoir::seeded(1_000, 0xDEADBEEF);
+        let mut b = Reservoir::seeded(1_000, 0xDEADBEEF);
+        a.extend((0..100_000).map(f64::from));
+        b.extend((0..100_000).map(f64::from));
+
+        assert_eq!(a.seen(), 100_000);
+        assert_eq!(a.values(), b.values());
+
+        // The sample should be representative of the whole stream.
+        let s = a.summary();
+        assert_relative_eq!(s.n, 1_000.0);
+        assert_relative_eq!(s.mean, 49_999.5, max_relative = 0.05);
+        assert_relative_eq!(s.std_dev(), 28_867.66, max_relative = 0.05);
+        assert!(a.values().iter().filter(|&&x| x >= 50_000.0).count() > 400);
+    }
+
+    #[test]
+    fn enumerated_combinations() {
+        let mut all = Vec::new();
+        combinations(4, 2, |idx| all.push(idx.to_vec()));
+        assert_eq!(all, [[0, 1], [0, 2], [0, 3], [1, 2], [1, 3], [2, 3]]);
+    }
+
+    #[test]
+    fn binomial_coefficients() {
+        assert_relative_eq!(binomial(6, 3), 20.0);
+        assert_relative_eq!(binomial(10, 0), 1.0);
+        assert_relative_eq!(binomial(40, 20), 137846528820.0);
+    }
+}
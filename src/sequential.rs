@@ -0,0 +1,379 @@
+//! Sequential tests, which allow the results of an experiment to be checked as measurements arrive
+//! and stopped as soon as a decision can be made.
+
+use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+use crate::{Confidence, Difference, Error, Summary};
+
+/// The decision of a sequential test given the measurements so far.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Decision {
+    /// More measurements are needed to make a decision.
+    Continue,
+    /// There is no difference between the means.
+    AcceptNull,
+    /// The means differ by the hypothesized difference.
+    RejectNull,
+}
+
+/// A sequential probability ratio test (SPRT) of whether the mean of an experiment differs from
+/// that of a control by a given amount, which accepts measurements of both as they arrive.
+///
+/// The test compares the hypotheses that the difference in means (the experiment's mean minus the
+/// control's) is zero and that it is the given difference, using the estimated variances of the
+/// measurements. Wald's boundaries keep the rates of false positives and false negatives close to
+/// those given by the confidence level and power, however often the decision is checked.
+///
+/// ```
+/// use nanostat::sequential::{Decision, Sprt};
+/// use nanostat::Confidence;
+///
+/// let mut sprt = Sprt::new(1.0, Confidence::P95, 0.8)?;
+/// for i in 0..100 {
+///     let noise = (f64::from(i) * 1.7).sin();
+///     sprt.push_control(10.0 + noise);
+///     sprt.push_experiment(12.0 - noise);
+///     if sprt.decision() != Decision::Continue {
+///         break;
+///     }
+/// }
+/// assert_eq!(sprt.decision(), Decision::RejectNull);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sprt {
+    control: Summary,
+    experiment: Summary,
+    delta: f64,
+    lower: f64,
+    upper: f64,
+}
+
+impl Sprt {
+    /// Create a test for a difference in means of `delta`, which may be negative, with the given
+    /// confidence level and power. The confidence level must be in the range `(0, 100)`, and the
+    /// power in the range `(0, 1)`.
+    pub fn new(delta: f64, confidence: Confidence, power: f64) -> Result<Sprt, Error> {
+        let alpha = confidence.checked_alpha()?;
+        if !(delta.is_finite() && delta != 0.0) {
+            return Err(Error::InvalidEffectSize(delta));
+        }
+        if !(0.0 < power && power < 1.0) {
+            return Err(Error::InvalidPower(power));
+        }
+
+        // Calculate Wald's boundaries for the log-likelihood ratio.
+        let beta = 1.0 - power;
+        let (lower, upper) = ((beta / (1.0 - alpha)).ln(), ((1.0 - beta) / alpha).ln());
+        Ok(Sprt { control: Summary::EMPTY, experiment: Summary::EMPTY, delta, lower, upper })
+    }
+
+    /// Add a measurement of the control.
+    pub fn push_control(&mut self, x: f64) {
+        self.control.extend([x]);
+    }
+
+    /// Add a measurement of the experiment.
+    pub fn push_experiment(&mut self, x: f64) {
+        self.experiment.extend([x]);
+    }
+
+    /// The summary of the control's measurements so far.
+    #[must_use]
+    pub const fn control(&self) -> &Summary {
+        &self.control
+    }
+
+    /// The summary of the experiment's measurements so far.
+    #[must_use]
+    pub const fn experiment(&self) -> &Summary {
+        &self.experiment
+    }
+
+    /// The log of the ratio of the likelihood of the measurements if the means differ by the
+    /// hypothesized difference to their likelihood if the means are the same, or NaN if either
+    /// the control or the experiment has fewer than two measurements.
+    #[must_use]
+    pub fn log_likelihood_ratio(&self) -> f64 {
+        let (a, b) = (&self.control, &self.experiment);
+        if a.n < 2.0 || b.n < 2.0 {
+            return f64::NAN;
+        }
+
+        // The observed difference in means is approximately normal with a mean of either zero or
+        // the hypothesized difference.
+        let var = a.variance / a.n + b.variance / b.n;
+        self.delta * (b.mean - a.mean - self.delta / 2.0) / var
+    }
+
+    /// The decision of the test given the measurements so far.
+    #[must_use]
+    pub fn decision(&self) -> Decision {
+        let llr = self.log_likelihood_ratio();
+        if llr >= self.upper {
+            Decision::RejectNull
+        } else if llr <= self.lower {
+            Decision::AcceptNull
+        } else {
+            Decision::Continue
+        }
+    }
+}
+
+/// An alpha spending function, which determines how much of the false positive rate of a
+/// group-sequential test is spent at each interim look.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Spending {
+    /// Lan and DeMets' approximation of Pocock's boundaries, which spend the false positive rate
+    /// roughly evenly, making early stopping more likely at the cost of a stricter final look.
+    Pocock,
+    /// Lan and DeMets' approximation of O'Brien and Fleming's boundaries, which spend very little
+    /// of the false positive rate early, leaving the final look almost as powerful as a single
+    /// test.
+    #[default]
+    OBrienFleming,
+}
+
+/// The number of points at which the density of the test statistic is evaluated between looks.
+const GRID: usize = 301;
+
+/// The boundaries of a two-sided group-sequential test, which allow the results of a long
+/// experiment to be checked at several interim looks without inflating the false positive rate.
+///
+/// Half of the false positive rate is spent on each side, so the boundaries match those of a
+/// one-sided design at half the significance level.
+///
+/// ```
+/// use nanostat::sequential::{GroupSequential, Spending};
+/// use nanostat::Confidence;
+///
+/// let gs = GroupSequential::new(&[0.25, 0.5, 0.75, 1.0], Spending::OBrienFleming, Confidence::P95)?;
+/// assert!(gs.boundaries[0] > 4.0);
+/// assert!(gs.boundaries[3] < 2.1);
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupSequential {
+    /// The proportion of the total measurements which will have been taken at each look.
+    pub fractions: Vec<f64>,
+
+    /// The critical value of the test statistic at each look.
+    pub boundaries: Vec<f64>,
+
+    /// The significance level which the p-value of a two-sided test must be under at each look.
+    pub nominal_alpha: Vec<f64>,
+}
+
+impl GroupSequential {
+    /// Calculate the boundaries of a group-sequential test with looks at the given proportions of
+    /// the total measurements, spending the false positive rate for the given confidence level
+    /// with the given function. The proportions must be increasing and in the range `(0, 1]`, and
+    /// the confidence level must be in the range `(0, 100)`.
+    pub fn new(
+        fractions: &[f64],
+        spending: Spending,
+        confidence: Confidence,
+    ) -> Result<GroupSequential, Error> {
+        let alpha = confidence.checked_alpha()?;
+        if fractions.is_empty()
+            || fractions.iter().any(|&t| !(0.0 < t && t <= 1.0))
+            || fractions.windows(2).any(|w| w[0] >= w[1])
+        {
+            return Err(Error::InvalidFractions);
+        }
+
+        // Spend half of the false positive rate on each side.
+        let dist_norm = Normal::new(0.0, 1.0).unwrap();
+        let z = dist_norm.inverse_cdf(1.0 - alpha / 4.0);
+        let spent = |t: f64| match spending {
+            Spending::Pocock => alpha * (1.0 + (core::f64::consts::E - 1.0) * t).ln(),
+            Spending::OBrienFleming => 4.0 * (1.0 - dist_norm.cdf(z / t.sqrt())),
+        };
+
+        // Track the density of the sum of the standardized measurements (a Brownian motion in
+        // the information fraction) over the region in which the test hasn't stopped, as
+        // weighted points for Simpson's rule.
+        let mut points: Vec<(f64, f64)> = Vec::new();
+        let (mut boundaries, mut prev) = (Vec::with_capacity(fractions.len()), 0.0);
+        for &t in fractions {
+            let sd = (t - prev).sqrt();
+
+            // Calculate the probability of stopping at this look given a boundary for the
+            // statistic, which is the sum scaled by the square root of the information fraction.
+            let stop = |c: f64| {
+                let b = c * t.sqrt();
+                if points.is_empty() {
+                    2.0 * (1.0 - dist_norm.cdf(b / sd))
+                } else {
+                    points
+                        .iter()
+                        .map(|(s, w)| {
+                            w * (1.0 - dist_norm.cdf((b - s) / sd) + dist_norm.cdf((-b - s) / sd))
+                        })
+                        .sum()
+                }
+            };
+
+            // Find the boundary which spends the increase in the false positive rate by bisection.
+            let target = spent(t) - spent(prev);
+            let (mut low, mut high) = (0.0, 40.0);
+            for _ in 0..64 {
+                let mid = (low + high) / 2.0;
+                if stop(mid) > target {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            let c = (low + high) / 2.0;
+
+            // Calculate the density of the sum in the region in which the test continues.
+            let b = c.min(8.0) * t.sqrt();
+            let h = 2.0 * b / (GRID - 1) as f64;
+            points = (0..GRID)
+                .map(|i| {
+                    let x = -b + h * i as f64;
+                    let density = if points.is_empty() {
+                        dist_norm.pdf(x / sd) / sd
+                    } else {
+                        points.iter().map(|(s, w)| w * dist_norm.pdf((x - s) / sd) / sd).sum()
+                    };
+                    let weight = match i {
+                        i if i == 0 || i == GRID - 1 => 1.0,
+                        i if i % 2 == 1 => 4.0,
+                        _ => 2.0,
+                    };
+                    (x, weight * h / 3.0 * density)
+                })
+                .collect();
+
+            boundaries.push(c);
+            prev = t;
+        }
+
+        let nominal_alpha = boundaries.iter().map(|&c| 2.0 * (1.0 - dist_norm.cdf(c))).collect();
+        Ok(GroupSequential { fractions: fractions.to_vec(), boundaries, nominal_alpha })
+    }
+
+    /// The decision of the test at the given look (starting from zero), given the difference
+    /// between the measurements so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such look.
+    #[must_use]
+    pub fn decision(&self, look: usize, diff: &Difference) -> Decision {
+        if diff.t_statistic.abs() >= self.boundaries[look] {
+            Decision::RejectNull
+        } else if look == self.boundaries.len() - 1 {
+            Decision::AcceptNull
+        } else {
+            Decision::Continue
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    /// Run the test until it makes a decision, returning it and the number of measurements of
+    /// each needed to make it.
+    fn run(sprt: &mut Sprt, offset: f64) -> (Decision, usize) {
+        for i in 0..10_000u32 {
+            let noise = (f64::from(i) * 1.7).sin();
+            sprt.push_control(10.0 + noise);
+            sprt.push_experiment(10.0 + offset + (f64::from(i) * 2.3).cos());
+            if sprt.decision() != Decision::Continue {
+                return (sprt.decision(), i as usize + 1);
+            }
+        }
+        (Decision::Continue, 10_000)
+    }
+
+    #[test]
+    fn sprt_decisions() {
+        let mut sprt = Sprt::new(0.5, Confidence::P95, 0.8).unwrap();
+        assert_eq!(sprt.decision(), Decision::Continue);
+        assert!(sprt.log_likelihood_ratio().is_nan());
+
+        let (decision, n) = run(&mut sprt, 1.0);
+        assert_eq!(decision, Decision::RejectNull);
+        assert!(n < 100);
+        assert_eq!(sprt.control().n, n as f64);
+
+        let mut sprt = Sprt::new(0.5, Confidence::P95, 0.8).unwrap();
+        assert_eq!(run(&mut sprt, 0.0).0, Decision::AcceptNull);
+
+        // A regression in the other direction is not the hypothesized difference.
+        let mut sprt = Sprt::new(0.5, Confidence::P95, 0.8).unwrap();
+        assert_eq!(run(&mut sprt, -1.0).0, Decision::AcceptNull);
+
+        let mut sprt = Sprt::new(-0.5, Confidence::P95, 0.8).unwrap();
+        assert_eq!(run(&mut sprt, -1.0).0, Decision::RejectNull);
+    }
+
+    #[test]
+    fn group_sequential_boundaries() {
+        let fractions = [0.2, 0.4, 0.6, 0.8, 1.0];
+
+        let gs =
+            GroupSequential::new(&fractions, Spending::OBrienFleming, Confidence::P95).unwrap();
+        for (c, expected) in gs.boundaries.iter().zip([4.8769, 3.3569, 2.6803, 2.2898, 2.0310]) {
+            assert_relative_eq!(*c, expected, epsilon = 1e-3);
+        }
+
+        let gs = GroupSequential::new(&fractions, Spending::Pocock, Confidence::P95).unwrap();
+        for (c, expected) in gs.boundaries.iter().zip([2.4380, 2.4268, 2.4101, 2.3966, 2.3859]) {
+            assert_relative_eq!(*c, expected, epsilon = 1e-3);
+        }
+
+        // A single look is a fixed-sample test.
+        let gs = GroupSequential::new(&[1.0], Spending::Pocock, Confidence::P95).unwrap();
+        assert_relative_eq!(gs.boundaries[0], 1.959963984540054, epsilon = 1e-9);
+        assert_relative_eq!(gs.nominal_alpha[0], 0.05, epsilon = 1e-9);
+
+        assert_eq!(
+            GroupSequential::new(&[0.5, 0.5, 1.0], Spending::Pocock, Confidence::P95).unwrap_err(),
+            Error::InvalidFractions
+        );
+        assert_eq!(
+            GroupSequential::new(&[], Spending::Pocock, Confidence::P95).unwrap_err(),
+            Error::InvalidFractions
+        );
+    }
+
+    #[test]
+    fn group_sequential_decisions() {
+        let gs =
+            GroupSequential::new(&[0.5, 1.0], Spending::OBrienFleming, Confidence::P95).unwrap();
+        let a: Summary = [1.0, 2.0, 3.0, 4.0, 5.0].iter().collect();
+        let b: Summary = [3.5, 4.5, 5.5, 6.5, 7.5].iter().collect();
+        let diff = a.compare(&b, Confidence::P95);
+
+        // The difference is significant, but not by enough to stop early.
+        assert!(diff.is_significant());
+        assert_eq!(gs.decision(0, &diff), Decision::Continue);
+        assert_eq!(gs.decision(1, &diff), Decision::RejectNull);
+        assert_eq!(gs.decision(1, &a.compare(&a, Confidence::P95)), Decision::AcceptNull);
+    }
+
+    #[test]
+    fn sprt_errors() {
+        assert_eq!(
+            Sprt::new(0.0, Confidence::P95, 0.8).unwrap_err(),
+            Error::InvalidEffectSize(0.0)
+        );
+        assert_eq!(Sprt::new(1.0, Confidence::P95, 0.0).unwrap_err(), Error::InvalidPower(0.0));
+        assert_eq!(
+            Sprt::new(1.0, Confidence::Custom(0.0), 0.8).unwrap_err(),
+            Error::InvalidConfidence(0.0)
+        );
+    }
+}
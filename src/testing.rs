@@ -0,0 +1,119 @@
+//! Random generators of valid inputs, for property-based testing and fuzzing of code which consumes
+//! nanostat's types. Requires the `testing` feature.
+//!
+//! The generators are ordinary [rand] distributions, so they can be driven by any framework which
+//! can supply a seeded random number generator, such as proptest or quickcheck.
+//!
+//! [Summary] implements the [Standard] distribution, so arbitrary summaries can be generated with
+//! [Rng::gen]:
+//!
+//! ```
+//! use nanostat::Summary;
+//! use rand::Rng;
+//!
+//! let s: Summary = rand::thread_rng().gen();
+//! assert!(s.n >= 2.0 && s.variance > 0.0);
+//! ```
+
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+use statrs::distribution::Normal;
+
+use crate::Summary;
+
+/// Generates summaries of between 2 and 1,000 measurements, with means in the range `[-1000, 1000]`
+/// and standard deviations spanning several orders of magnitude, from `1e-3` to `1e3`. The
+/// higher moments and range are unknown, as with [Summary::from_parts].
+impl Distribution<Summary> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Summary {
+        let n = f64::from(rng.gen_range(2..=1_000));
+        let mean = rng.gen_range(-1_000.0..=1_000.0);
+        let std_dev = 10f64.powf(rng.gen_range(-3.0..=3.0));
+        Summary::from_parts(n, mean, std_dev * std_dev).unwrap()
+    }
+}
+
+/// A distribution of paired data sets, such as the timings of the same benchmarks before and
+/// after a change, whose measurements are drawn from correlated normal distributions.
+///
+/// ```
+/// use nanostat::testing::Paired;
+/// use rand::Rng;
+///
+/// let (before, after) = rand::thread_rng().sample(Paired { shift: 1.5, ..Paired::default() });
+/// assert_eq!(before.len(), after.len());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Paired {
+    /// The number of pairs.
+    pub len: usize,
+
+    /// The mean of the first data set.
+    pub mean: f64,
+
+    /// The standard deviation of both data sets.
+    pub std_dev: f64,
+
+    /// The difference between the means of the second data set and the first.
+    pub shift: f64,
+
+    /// The correlation between the measurements of each pair, in the range `[-1, 1]`.
+    pub correlation: f64,
+}
+
+impl Default for Paired {
+    fn default() -> Self {
+        Paired { len: 20, mean: 100.0, std_dev: 10.0, shift: 0.0, correlation: 0.5 }
+    }
+}
+
+impl Distribution<(Vec<f64>, Vec<f64>)> for Paired {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> (Vec<f64>, Vec<f64>) {
+        let z = Normal::new(0.0, 1.0).unwrap();
+        let r = self.correlation.clamp(-1.0, 1.0);
+        (0..self.len)
+            .map(|_| {
+                let (x, e): (f64, f64) = (z.sample(rng), z.sample(rng));
+                let y = r * x + (1.0 - r * r).sqrt() * e;
+                (self.mean + self.std_dev * x, self.mean + self.shift + self.std_dev * y)
+            })
+            .unzip()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::correlation::pearson;
+
+    #[test]
+    fn arbitrary_summaries() {
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+        for _ in 0..1_000 {
+            let s: Summary = rng.gen();
+            assert!(s.check().is_ok());
+            assert!((2.0..=1_000.0).contains(&s.n));
+            assert!(s.variance > 0.0);
+        }
+    }
+
+    #[test]
+    fn paired_data_sets() {
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+        let paired = Paired { len: 10_000, shift: 5.0, correlation: 0.8, ..Paired::default() };
+        let (a, b) = rng.sample(paired);
+
+        let (sa, sb): (Summary, Summary) = (a.iter().collect(), b.iter().collect());
+        assert_relative_eq!(sb.mean - sa.mean, 5.0, epsilon = 0.2);
+        assert_relative_eq!(sb.std_dev(), 10.0, epsilon = 0.3);
+        assert_relative_eq!(
+            pearson(&a, &b, crate::Confidence::P95).unwrap().r,
+            0.8,
+            epsilon = 0.02
+        );
+    }
+}
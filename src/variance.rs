@@ -0,0 +1,238 @@
+//! Tests for differences in the spread of data sets, rather than their location.
+
+use statrs::distribution::{Continuous, ContinuousCDF, FisherSnedecor};
+
+use crate::nonparametric::median;
+use crate::{Confidence, Error};
+
+/// The measure of central tendency from which [levene] calculates deviations.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Center {
+    /// Deviations from the mean, as in Levene's original test.
+    Mean,
+    /// Deviations from the median, as in the Brown-Forsythe test, which is robust to skewed data.
+    #[default]
+    Median,
+}
+
+/// The result of a test for the equality of variances.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Levene {
+    /// The W statistic for the test, which is F-distributed under the null hypothesis.
+    pub w: f64,
+
+    /// The numerator degrees of freedom.
+    pub df_between: f64,
+
+    /// The denominator degrees of freedom.
+    pub df_within: f64,
+
+    /// The p-value for the test: the probability of a W statistic at least this large if all the
+    /// data sets had the same variance.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl Levene {
+    /// Whether or not any of the variances differ significantly.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// The ratio of the variances of two [Summary](crate::Summary) instances, as calculated by
+/// [Summary::variance_ratio](crate::Summary::variance_ratio).
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VarianceRatio {
+    /// The ratio of the second summary's variance to the first's. A ratio of 0.5 indicates that
+    /// the variance was halved.
+    pub ratio: f64,
+
+    /// The numerator degrees of freedom.
+    pub df_num: f64,
+
+    /// The denominator degrees of freedom.
+    pub df_den: f64,
+
+    /// The lower bound of the confidence interval for the ratio.
+    pub ci_low: f64,
+
+    /// The upper bound of the confidence interval for the ratio.
+    pub ci_high: f64,
+
+    /// The p-value for the two-tailed F-test: the probability of a ratio at least this far from 1
+    /// if both summaries had the same variance.
+    pub p_value: f64,
+
+    /// The significance level of the test. It is the maximum allowed value of the p-value.
+    pub alpha: f64,
+}
+
+impl VarianceRatio {
+    /// Whether or not the variances differ significantly.
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        self.p_value < self.alpha
+    }
+}
+
+/// Test whether the given data sets have equal variances using Levene's test, which is a one-way
+/// ANOVA of the absolute deviations of each measurement from its data set's center. The confidence
+/// level must be in the range `(0, 100)`.
+///
+/// ```
+/// use nanostat::Confidence;
+/// use nanostat::variance::{levene, Center};
+///
+/// let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+/// let b = [4.5, 4.8, 4.1, 5.2, 4.9, 4.4, 5.0, 4.6];
+/// assert!(levene(&[&a, &b], Center::Median, Confidence::P95)?.is_significant());
+/// # Ok::<(), nanostat::Error>(())
+/// ```
+pub fn levene(groups: &[&[f64]], center: Center, confidence: Confidence) -> Result<Levene, Error> {
+    let alpha = confidence.checked_alpha()?;
+    if groups.len() < 2 || groups.iter().any(|g| g.len() < 2) {
+        return Err(Error::InsufficientSamples);
+    }
+    if groups.iter().flat_map(|g| g.iter()).any(|x| !x.is_finite()) {
+        return Err(Error::NonFinite);
+    }
+
+    // Calculate the absolute deviations of each measurement from its group's center.
+    let deviations: Vec<Vec<f64>> = groups
+        .iter()
+        .map(|g| {
+            let c = match center {
+                Center::Mean => g.iter().sum::<f64>() / g.len() as f64,
+                Center::Median => median(&mut g.to_vec()),
+            };
+            g.iter().map(|x| (x - c).abs()).collect()
+        })
+        .collect();
+
+    // Calculate the group and grand means of the deviations.
+    let means: Vec<f64> =
+        deviations.iter().map(|z| z.iter().sum::<f64>() / z.len() as f64).collect();
+    let n = deviations.iter().map(|z| z.len()).sum::<usize>() as f64;
+    let mean = deviations.iter().flat_map(|z| z.iter()).sum::<f64>() / n;
+
+    // Calculate the between- and within-group variation of the deviations.
+    let between = deviations
+        .iter()
+        .zip(means.iter())
+        .map(|(z, m)| z.len() as f64 * (m - mean).powf(2.0))
+        .sum::<f64>();
+    let within = deviations
+        .iter()
+        .zip(means.iter())
+        .map(|(z, m)| z.iter().map(|x| (x - m).powf(2.0)).sum::<f64>())
+        .sum::<f64>();
+    if within == 0.0 {
+        return Err(Error::ZeroVariance);
+    }
+
+    // Calculate the W statistic and its p-value.
+    let k = groups.len() as f64;
+    let (df_between, df_within) = (k - 1.0, n - k);
+    let w = df_within / df_between * between / within;
+    let dist_f = FisherSnedecor::new(df_between, df_within).map_err(|_| Error::NonFinite)?;
+    let p_value = 1.0 - dist_f.cdf(w);
+
+    Ok(Levene { w, df_between, df_within, p_value, alpha })
+}
+
+/// Calculate the `p` quantile of the given F distribution, refining statrs's bisection, which is
+/// only accurate to about four digits, with Newton's method.
+pub(crate) fn f_quantile(dist: &FisherSnedecor, p: f64) -> f64 {
+    let mut x = dist.inverse_cdf(p);
+    for _ in 0..4 {
+        let step = (dist.cdf(x) - p) / dist.pdf(x);
+        if !step.is_finite() {
+            break;
+        }
+        x -= step;
+    }
+    x
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+    use crate::Summary;
+
+    const A: [f64; 8] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+    const B: [f64; 8] = [2.0, 4.0, 6.0, 8.0, 10.0, 12.0, 14.0, 16.0];
+    const C: [f64; 8] = [4.5, 4.8, 4.1, 5.2, 4.9, 4.4, 5.0, 4.6];
+
+    #[test]
+    fn levene_mean() {
+        let lev = levene(&[&A, &B], Center::Mean, Confidence::P95).unwrap();
+
+        assert_relative_eq!(lev.w, 4.48, epsilon = 1e-12);
+        assert_relative_eq!(lev.df_between, 1.0);
+        assert_relative_eq!(lev.df_within, 14.0);
+        assert_relative_eq!(lev.p_value, 0.0526879337345453, epsilon = 1e-9);
+        assert!(!lev.is_significant());
+    }
+
+    #[test]
+    fn brown_forsythe() {
+        let lev = levene(&[&A, &C], Center::Median, Confidence::P95).unwrap();
+
+        assert_relative_eq!(lev.w, 16.055603079555174, epsilon = 1e-12);
+        assert_relative_eq!(lev.p_value, 0.001298251572582269, epsilon = 1e-9);
+        assert!(lev.is_significant());
+
+        let lev = levene(&[&A, &B, &C], Center::Median, Confidence::P95).unwrap();
+
+        assert_relative_eq!(lev.w, 11.547744070875743, epsilon = 1e-12);
+        assert_relative_eq!(lev.p_value, 0.0004141560127488977, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn variance_ratio() {
+        let a: Summary = A.iter().collect();
+        let b: Summary = B.iter().collect();
+        let vr = a.variance_ratio(&b, Confidence::P95).unwrap();
+
+        assert_relative_eq!(vr.ratio, 4.0, epsilon = 1e-12);
+        assert_relative_eq!(vr.df_num, 7.0);
+        assert_relative_eq!(vr.df_den, 7.0);
+        assert_relative_eq!(vr.ci_low, 0.8008153551087307, epsilon = 1e-9);
+        assert_relative_eq!(vr.ci_high, 19.97963687625296, epsilon = 1e-9);
+        assert_relative_eq!(vr.p_value, 0.0876228290414025, epsilon = 1e-9);
+        assert!(!vr.is_significant());
+
+        let c: Summary = C.iter().collect();
+        let vr = a.variance_ratio(&c, Confidence::P95).unwrap();
+
+        assert_relative_eq!(vr.ratio, 0.0211607142857143, epsilon = 1e-12);
+        assert_relative_eq!(vr.ci_low, 0.004236456231267172, epsilon = 1e-9);
+        assert_relative_eq!(vr.ci_high, 0.10569584686767756, epsilon = 1e-9);
+        assert_relative_eq!(vr.p_value, 4.581825117900253e-5, epsilon = 1e-9);
+        assert!(vr.is_significant());
+
+        let flat: Summary = [1.0, 1.0].iter().collect();
+        assert_eq!(flat.variance_ratio(&a, Confidence::P95).unwrap_err(), Error::ZeroVariance);
+    }
+
+    #[test]
+    fn levene_errors() {
+        assert_eq!(
+            levene(&[&A], Center::Median, Confidence::P95).unwrap_err(),
+            Error::InsufficientSamples
+        );
+        assert_eq!(
+            levene(&[&[1.0, 1.0], &[2.0, 2.0]], Center::Median, Confidence::P95).unwrap_err(),
+            Error::ZeroVariance
+        );
+    }
+}
@@ -25,6 +25,7 @@ fn main() -> Result<()> {
 
     cmd!(sh, "cargo fmt --check").run()?;
     cmd!(sh, "cargo build --all-targets --all-features").run()?;
+    cmd!(sh, "cargo build --no-default-features --features serde").run()?;
     cmd!(sh, "cargo test --all-features").run()?;
     cmd!(sh, "cargo clippy --all-features --tests --benches").run()?;
 